@@ -0,0 +1,135 @@
+//! Compares ordering pending messages with `EventQueue` against the
+//! `sort_by(event_order)` that `Session` used before, for the messages of a
+//! step and for an injection, and measures `Session` step throughput on a
+//! wide fan-out topology.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use sim::models::Model;
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::event_queue::EventQueue;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Router, Sink, Source};
+use sim_dag::session::{event_order, Session};
+use sim_dag::simulation::SimulationExt;
+
+fn messages(n: usize) -> Vec<Message> {
+    (0..n)
+        .map(|i| {
+            Message::new(
+                format!("source-{}", i % 97),
+                String::from("job"),
+                format!("sink-{}", i % 89),
+                String::from("job"),
+                ((i * 7919) % 1000) as f64,
+                format!("job {}", i),
+            )
+        })
+        .collect()
+}
+
+fn fan_out(width: usize) -> Simulation {
+    let outputs: Vec<String> = (0..width).map(|i| format!("out-{}", i)).collect();
+    let mut models = vec![
+        Model::new(
+            String::from("source"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 10.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("router"),
+            Box::new(Router::new(
+                String::from("job"),
+                outputs.iter().map(|output| (output.clone(), 1.0)).collect(),
+                false,
+                None,
+            )),
+        ),
+    ];
+    let mut connectors = vec![Connector::new(
+        String::from("source to router"),
+        String::from("source"),
+        String::from("router"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    outputs.iter().enumerate().for_each(|(i, output)| {
+        let sink = format!("sink-{}", i);
+        models.push(Model::new(
+            sink.clone(),
+            Box::new(Sink::new(String::from("job"), false)),
+        ));
+        connectors.push(Connector::new(
+            format!("router to {}", sink),
+            String::from("router"),
+            sink,
+            output.clone(),
+            String::from("job"),
+        ));
+    });
+    Simulation::post_with_seed(models, connectors, 41)
+}
+
+fn ordering(c: &mut Criterion) {
+    // the batch a step emits, ordered once per step.
+    let mut group = c.benchmark_group("step ordering");
+    for n in [1_000, 10_000] {
+        let batch = messages(n);
+        group.bench_with_input(BenchmarkId::new("sort_by", n), &batch, |b, batch| {
+            b.iter(|| {
+                let mut ordered = batch.clone();
+                ordered.sort_by(event_order);
+                black_box(ordered)
+            })
+        });
+        let mut queue = EventQueue::new();
+        group.bench_with_input(BenchmarkId::new("event queue", n), &batch, |b, batch| {
+            b.iter(|| {
+                queue.extend(batch.iter().cloned());
+                black_box(queue.drain_ordered())
+            })
+        });
+    }
+    group.finish();
+
+    // one message injected among the pending ones, which are in order.
+    let mut group = c.benchmark_group("injection");
+    for n in [1_000, 10_000] {
+        let mut batch = messages(n);
+        batch.sort_by(event_order);
+        let message = messages(1).remove(0);
+        group.bench_with_input(BenchmarkId::new("sort_by", n), &batch, |b, batch| {
+            b.iter(|| {
+                let mut ordered = batch.clone();
+                ordered.push(message.clone());
+                ordered.sort_by(event_order);
+                black_box(ordered)
+            })
+        });
+        let mut queue = EventQueue::new();
+        group.bench_with_input(BenchmarkId::new("event queue", n), &batch, |b, batch| {
+            b.iter(|| {
+                queue.extend(batch.iter().cloned());
+                queue.push(message.clone());
+                black_box(queue.drain_ordered())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn stepping(c: &mut Criterion) {
+    c.bench_function("session fan-out 1000 steps", |b| {
+        b.iter(|| {
+            let mut session = Session::new(fan_out(500));
+            black_box(session.step_n(1_000).unwrap())
+        })
+    });
+}
+
+criterion_group!(benches, ordering, stepping);
+criterion_main!(benches);
@@ -0,0 +1,184 @@
+//! Moves a large payload through a chain of delays, with `Session` and with
+//! the copying reschedule it replaced, measuring the time and the bytes
+//! allocated per run of each.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use sim::models::Model;
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::event_queue::EventQueue;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Delay, Sink};
+use sim_dag::session::Session;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Bytes allocated, as a criterion measurement.
+struct Allocated;
+
+impl Measurement for Allocated {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATED.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &PayloadCopies
+    }
+}
+
+/// Formats bytes allocated as copies of the payload.
+struct PayloadCopies;
+
+impl ValueFormatter for PayloadCopies {
+    fn scale_values(&self, _typical_value: f64, values: &mut [f64]) -> &'static str {
+        values
+            .iter_mut()
+            .for_each(|value| *value /= PAYLOAD_BYTES as f64);
+        "payload copies"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        _throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        self.scale_values(0.0, values)
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+const HOPS: usize = 10;
+const PAYLOAD_BYTES: usize = 1 << 20;
+
+fn chain() -> Simulation {
+    let mut models: Vec<Model> = (0..HOPS)
+        .map(|i| {
+            Model::new(
+                format!("hop-{}", i),
+                Box::new(Delay::new(
+                    ContinuousDistribution::Constant { value: 1.0 },
+                    String::from("job"),
+                    String::from("delayed job"),
+                    false,
+                    None,
+                )),
+            )
+        })
+        .collect();
+    models.push(Model::new(
+        String::from("exit"),
+        Box::new(Sink::new(String::from("job"), false)),
+    ));
+    let connectors = (0..HOPS)
+        .map(|i| {
+            let target = if i + 1 == HOPS {
+                String::from("exit")
+            } else {
+                format!("hop-{}", i + 1)
+            };
+            Connector::new(
+                format!("hop-{} to {}", i, target),
+                format!("hop-{}", i),
+                target,
+                String::from("delayed job"),
+                String::from("job"),
+            )
+        })
+        .collect();
+    Simulation::post(models, connectors)
+}
+
+fn payload_message() -> Message {
+    Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from("hop-0"),
+        String::from("job"),
+        0.0,
+        "x".repeat(PAYLOAD_BYTES),
+    )
+}
+
+fn run_chain() -> Session {
+    let mut session = Session::new(chain());
+    session.inject_input(payload_message());
+    session.step_until(HOPS as f64 + 1.0).unwrap();
+    session
+}
+
+/// `run_chain` as `Session` stepped before it moved messages: the messages
+/// of every step were put in event order and rescheduled as copies.
+fn run_chain_copying() -> Simulation {
+    let mut simulation = chain();
+    simulation.inject_input(payload_message());
+    while simulation.get_global_time() <= HOPS as f64 + 1.0 {
+        let messages = simulation
+            .step()
+            .unwrap()
+            .into_iter()
+            .collect::<EventQueue>()
+            .into_ordered_vec();
+        simulation.reset_messages();
+        messages
+            .iter()
+            .for_each(|message| simulation.inject_input(message.clone()));
+    }
+    simulation
+}
+
+fn payload<M: Measurement>(c: &mut Criterion<M>) {
+    let mut group = c.benchmark_group(format!("{}-hop 1 MiB payload", HOPS));
+    group.bench_function("copying", |b| b.iter(|| black_box(run_chain_copying())));
+    group.bench_function("session", |b| b.iter(|| black_box(run_chain())));
+    group.finish();
+}
+
+criterion_group!(time, payload);
+criterion_group! {
+    name = allocations;
+    config = Criterion::default().with_measurement(Allocated);
+    targets = payload
+}
+criterion_main!(time, allocations);
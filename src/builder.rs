@@ -0,0 +1,381 @@
+//! Fluent construction of a `Simulation`, optionally from reusable
+//! `CoupledModel`s.
+
+use std::collections::HashMap;
+
+use sim::models::{Model, ReportableModel};
+use sim::simulator::{Connector, Simulation};
+
+use crate::checker::{self, CheckError};
+use crate::input_modeling::ContinuousDistribution;
+use crate::introspection;
+use crate::models::{Delay, Throttle, register_models};
+use crate::simulation::SimulationExt;
+
+/// Collects models and connectors and posts them as a checked `Simulation`.
+///
+/// ```ignore
+/// let simulation = SimulationBuilder::new()
+///     .model("player-01", Box::new(player()))
+///     .model("player-02", Box::new(player()))
+///     .connect("p1 to p2", "player-01", "send", "player-02", "receive")
+///     .connect("p2 to p1", "player-02", "send", "player-01", "receive")
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct SimulationBuilder {
+    models: Vec<Model>,
+    connectors: Vec<Connector>,
+    /// The exposed ports of the coupled models added so far, by id.
+    coupled: HashMap<String, ExposedPorts>,
+    seed: Option<u64>,
+    error: Option<CheckError>,
+}
+
+/// The `(model id, port)` each exposed port of a coupled model stands for.
+#[derive(Clone, Default)]
+struct ExposedPorts {
+    inputs: HashMap<String, (String, String)>,
+    outputs: HashMap<String, (String, String)>,
+}
+
+/// A group of models and the connectors between them, used as one building
+/// block: DEVS coupling. Some of the inner ports are exposed under a name of
+/// their own with `input` and `output`; everything else stays internal.
+///
+/// `SimulationBuilder::coupled` adds an instance under an id, and `connect`
+/// attaches to the instance's exposed ports as if it were one model. `sim`
+/// has no coupled models, so an instance is flattened: its models and
+/// connectors are added with the instance id as prefix, e.g. `line-1/queue`
+/// for the model `queue` of instance `line-1`, and connecting to an exposed
+/// port connects to the inner port behind it. Instances copy their models
+/// through the serialized form, so every instance starts from the same state
+/// of a private RNG its models have, and coupled models can contain coupled
+/// models in turn.
+///
+/// ```ignore
+/// let line = CoupledModel::new()
+///     .model("first", Box::new(station()))
+///     .model("second", Box::new(station()))
+///     .connect("first to second", "first", "processed job", "second", "job")
+///     .input("job", "first", "job")
+///     .output("done", "second", "processed job");
+/// let simulation = SimulationBuilder::new()
+///     .coupled("line-1", &line)
+///     .coupled("line-2", &line)
+///     .connect("line-1 to line-2", "line-1", "done", "line-2", "job")
+///     .build()?;
+/// ```
+#[derive(Default)]
+pub struct CoupledModel {
+    inner: SimulationBuilder,
+    exposed: ExposedPorts,
+}
+
+impl CoupledModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an inner model; see `SimulationBuilder::model`.
+    pub fn model(mut self, id: &str, model: Box<dyn ReportableModel>) -> Self {
+        self.inner = self.inner.model(id, model);
+        self
+    }
+
+    /// Adds an inner coupled model; see `SimulationBuilder::coupled`.
+    pub fn coupled(mut self, id: &str, coupled: &CoupledModel) -> Self {
+        self.inner = self.inner.coupled(id, coupled);
+        self
+    }
+
+    /// Connects two inner models; see `SimulationBuilder::connect`.
+    pub fn connect(
+        mut self,
+        id: &str,
+        source_id: &str,
+        source_port: &str,
+        target_id: &str,
+        target_port: &str,
+    ) -> Self {
+        self.inner = self
+            .inner
+            .connect(id, source_id, source_port, target_id, target_port);
+        self
+    }
+
+    /// Exposes input port `model_port` of the inner model `model_id` as the
+    /// input port `port`, replacing an earlier input of that name.
+    pub fn input(mut self, port: &str, model_id: &str, model_port: &str) -> Self {
+        let inner = self.expose(port, model_id, model_port, true);
+        self.exposed.inputs.insert(port.to_string(), inner);
+        self
+    }
+
+    /// Exposes output port `model_port` of the inner model `model_id` as the
+    /// output port `port`, replacing an earlier output of that name.
+    pub fn output(mut self, port: &str, model_id: &str, model_port: &str) -> Self {
+        let inner = self.expose(port, model_id, model_port, false);
+        self.exposed.outputs.insert(port.to_string(), inner);
+        self
+    }
+
+    /// The inner model and port behind a port exposed as `port`. A model
+    /// that does not exist is reported by `build`.
+    fn expose(
+        &mut self,
+        port: &str,
+        model_id: &str,
+        model_port: &str,
+        input: bool,
+    ) -> (String, String) {
+        let (inner_id, inner_port) = self.inner.resolve(port, model_id, model_port, input);
+        if self.inner.error.is_none()
+            && !self.inner.models.iter().any(|model| model.id() == inner_id)
+        {
+            self.inner.error = Some(CheckError::MissingModel {
+                model_id: inner_id.clone(),
+            });
+        }
+        (inner_id, inner_port)
+    }
+}
+
+impl SimulationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a model. Reusing an id is reported by `build`.
+    pub fn model(mut self, id: &str, model: Box<dyn ReportableModel>) -> Self {
+        self.check_unused(id);
+        self.models.push(Model::new(id.to_string(), model));
+        self
+    }
+
+    /// Adds an instance of `coupled` with id `id`; see `CoupledModel`.
+    /// Problems inside the coupled model are reported by `build`.
+    pub fn coupled(mut self, id: &str, coupled: &CoupledModel) -> Self {
+        self.check_unused(id);
+        if self.error.is_none() {
+            self.error = coupled.inner.error.clone();
+        }
+        let prefixed = |inner_id: &str| format!("{}/{}", id, inner_id);
+        register_models();
+        for model in &coupled.inner.models {
+            match renamed(model, &prefixed(model.id())) {
+                Ok(model) => self.models.push(model),
+                Err(reason) => {
+                    self.error.get_or_insert(CheckError::InvalidModel {
+                        model_id: prefixed(model.id()),
+                        reason,
+                    });
+                }
+            }
+        }
+        self.connectors
+            .extend(coupled.inner.connectors.iter().map(|connector| {
+                Connector::new(
+                    prefixed(connector.id()),
+                    prefixed(connector.source_id()),
+                    prefixed(connector.target_id()),
+                    connector.source_port().to_string(),
+                    connector.target_port().to_string(),
+                )
+            }));
+        let exposed = |ports: &HashMap<String, (String, String)>| {
+            ports
+                .iter()
+                .map(|(port, (model_id, model_port))| {
+                    (port.clone(), (prefixed(model_id), model_port.clone()))
+                })
+                .collect()
+        };
+        self.coupled.insert(
+            id.to_string(),
+            ExposedPorts {
+                inputs: exposed(&coupled.exposed.inputs),
+                outputs: exposed(&coupled.exposed.outputs),
+            },
+        );
+        self
+    }
+
+    /// Connects `source_port` of `source_id` to `target_port` of `target_id`.
+    /// Both models must already have been added; otherwise `build` fails.
+    /// Either can be a coupled model, connected on one of its exposed ports.
+    pub fn connect(
+        mut self,
+        id: &str,
+        source_id: &str,
+        source_port: &str,
+        target_id: &str,
+        target_port: &str,
+    ) -> Self {
+        let (source_id, source_port) = self.resolve(id, source_id, source_port, false);
+        let (target_id, target_port) = self.resolve(id, target_id, target_port, true);
+        if self.error.is_none()
+            && let Some(missing) = [&source_id, &target_id]
+                .into_iter()
+                .find(|model_id| !self.models.iter().any(|model| model.id() == model_id.as_str()))
+        {
+            self.error = Some(CheckError::UnknownModel {
+                connector_id: id.to_string(),
+                model_id: missing.to_string(),
+            });
+        }
+        self.connectors.push(Connector::new(
+            id.to_string(),
+            source_id,
+            target_id,
+            source_port,
+            target_port,
+        ));
+        self
+    }
+
+    /// Reports `id` as a duplicate if a model or coupled model has it already.
+    fn check_unused(&mut self, id: &str) {
+        if self.error.is_none()
+            && (self.coupled.contains_key(id) || self.models.iter().any(|model| model.id() == id))
+        {
+            self.error = Some(CheckError::DuplicateModel {
+                model_id: id.to_string(),
+            });
+        }
+    }
+
+    /// The inner model and port behind `port` of `model_id`, if it is a
+    /// coupled model, and otherwise `model_id` and `port` themselves. An
+    /// exposed port that does not exist is reported by `build`, naming
+    /// `connector_id`.
+    fn resolve(
+        &mut self,
+        connector_id: &str,
+        model_id: &str,
+        port: &str,
+        input: bool,
+    ) -> (String, String) {
+        let Some(exposed) = self.coupled.get(model_id) else {
+            return (model_id.to_string(), port.to_string());
+        };
+        let ports = if input { &exposed.inputs } else { &exposed.outputs };
+        match ports.get(port) {
+            Some(inner) => inner.clone(),
+            None => {
+                self.error.get_or_insert(CheckError::UnknownPort {
+                    connector_id: connector_id.to_string(),
+                    model_id: model_id.to_string(),
+                    port: port.to_string(),
+                });
+                (model_id.to_string(), port.to_string())
+            }
+        }
+    }
+
+    /// Like `connect`, with the messages taking `delay` time units to cross.
+    ///
+    /// `sim`'s connectors transfer instantly, so a positive `delay` is
+    /// modelled by a `Delay` model with id `<id> delay` spliced between the
+    /// two ports, connected by `<id> in` and `<id> out`. It keeps messages in
+    /// order and shows up in reports and diagrams like any other model. A
+    /// zero delay is a plain `connect`; a negative one is reported by `build`.
+    pub fn connect_delayed(
+        mut self,
+        id: &str,
+        source_id: &str,
+        source_port: &str,
+        target_id: &str,
+        target_port: &str,
+        delay: f64,
+    ) -> Self {
+        if delay == 0.0 {
+            return self.connect(id, source_id, source_port, target_id, target_port);
+        }
+        let delay_id = format!("{} delay", id);
+        if self.error.is_none() && !(delay.is_finite() && delay > 0.0) {
+            self.error = Some(CheckError::InvalidModel {
+                model_id: delay_id.clone(),
+                reason: format!("connector delay must be non-negative, got {}", delay),
+            });
+        }
+        let link = Delay::new(
+            ContinuousDistribution::Constant { value: delay },
+            String::from("in"),
+            String::from("out"),
+            false,
+            None,
+        );
+        self.splice(id, &delay_id, Box::new(link), (source_id, source_port), (target_id, target_port))
+    }
+
+    /// Like `connect`, letting at most `rate` messages per time unit cross.
+    ///
+    /// Messages arriving faster queue on the link and are released one every
+    /// `1 / rate`, in order. As for `connect_delayed`, the link is a model
+    /// spliced between the two ports: a `Throttle` with id `<id> throttle`,
+    /// whose `queue_length` is the number of messages waiting on the link.
+    /// A rate that is not positive is reported by `build`.
+    pub fn connect_rate_limited(
+        mut self,
+        id: &str,
+        source_id: &str,
+        source_port: &str,
+        target_id: &str,
+        target_port: &str,
+        rate: f64,
+    ) -> Self {
+        let throttle_id = format!("{} throttle", id);
+        if self.error.is_none() && !(rate.is_finite() && rate > 0.0) {
+            self.error = Some(CheckError::InvalidModel {
+                model_id: throttle_id.clone(),
+                reason: format!("connector rate must be positive, got {}", rate),
+            });
+        }
+        let link = Throttle::new(rate, String::from("in"), String::from("out"), false);
+        self.splice(id, &throttle_id, Box::new(link), (source_id, source_port), (target_id, target_port))
+    }
+
+    /// Adds `link` with id `link_id` between the `(model id, port)` pairs
+    /// `source` and `target`, connected by `<id> in` and `<id> out`.
+    fn splice(
+        self,
+        id: &str,
+        link_id: &str,
+        link: Box<dyn ReportableModel>,
+        (source_id, source_port): (&str, &str),
+        (target_id, target_port): (&str, &str),
+    ) -> Self {
+        self.model(link_id, link)
+            .connect(&format!("{} in", id), source_id, source_port, link_id, "in")
+            .connect(&format!("{} out", id), link_id, "out", target_id, target_port)
+    }
+
+    /// Seeds the simulation RNG, see `SimulationExt::post_with_seed`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Checks the topology with `checker::check` and posts it.
+    pub fn build(self) -> Result<Simulation, CheckError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        checker::check(&self.models, &self.connectors)?;
+        Ok(match self.seed {
+            Some(seed) => Simulation::post_with_seed(self.models, self.connectors, seed),
+            None => Simulation::post(self.models, self.connectors),
+        })
+    }
+}
+
+/// A copy of `model` with id `id`, made through its serialized form.
+fn renamed(model: &Model, id: &str) -> Result<Model, String> {
+    let mut value = introspection::serialized(model);
+    match value.get_mut("id") {
+        Some(model_id) => *model_id = serde_yaml::Value::from(id),
+        None => return Err(String::from("the serialized model has no id")),
+    }
+    serde_yaml::from_value(value).map_err(|err| err.to_string())
+}
@@ -0,0 +1,499 @@
+//! Configuration checks that complement `sim::checker::Checker`.
+//!
+//! `check` runs on the models and connectors before they are posted, and
+//! `TopologyChecker::check_topology` runs the same checks on a posted
+//! `Simulation`. Either way, problems are reported before any stepping.
+//!
+//! Cycles in the connector graph are legal (the ping-pong ring is one), so
+//! they are not part of `check`. Feed-forward topologies can opt in with
+//! `check_acyclic`, or list them with `cycles` to confirm they are intended.
+//!
+//! Models left unconnected (see `orphans`) are usually a wiring mistake, but
+//! not always, so `check` only logs a warning for each of them. Topologies
+//! that must be fully wired can opt in with `check_connected`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+use log::warn;
+use sim::models::Model;
+use sim::simulator::{Connector, Message, Simulation};
+
+use crate::introspection::{self, describe};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckError {
+    /// A model's own configuration is unusable.
+    InvalidModel { model_id: String, reason: String },
+    /// Two models share an id; routing needs ids to be unique.
+    DuplicateModel { model_id: String },
+    /// Two connectors share an id.
+    DuplicateConnector { connector_id: String },
+    /// A change to a running simulation, or a port exposed by a
+    /// `CoupledModel`, names a model that does not exist.
+    MissingModel { model_id: String },
+    /// A running simulation could not be rebuilt with a changed topology,
+    /// typically because one of its models cannot be deserialized.
+    Rebuild { reason: String },
+    /// A connector's source or target names a model that does not exist.
+    UnknownModel {
+        connector_id: String,
+        model_id: String,
+    },
+    /// A connector uses a port its source or target model does not declare.
+    UnknownPort {
+        connector_id: String,
+        model_id: String,
+        port: String,
+    },
+    /// A message about to be injected targets a model that does not exist
+    /// or an input port the model does not declare. `index` is the
+    /// message's position in its batch.
+    UndeliverableMessage {
+        index: usize,
+        target_id: String,
+        target_port: String,
+    },
+    /// Connectors form a directed cycle through these models, listed in
+    /// connector order and starting from the smallest id.
+    Cycle { model_ids: Vec<String> },
+    /// A model is not connected; see `orphans`.
+    Orphan(Orphan),
+}
+
+/// A model that declares ports no connector uses: input ports but no
+/// incoming connector, output ports but no outgoing connector, or both.
+/// Models declaring no input ports are sources and never lack incoming
+/// connectors; models declaring no output ports are sinks and never lack
+/// outgoing ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    pub model_id: String,
+    pub no_incoming: bool,
+    pub no_outgoing: bool,
+}
+
+impl fmt::Display for Orphan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = match (self.no_incoming, self.no_outgoing) {
+            (true, true) => "no connectors at all",
+            (true, false) => "no incoming connectors",
+            _ => "no outgoing connectors",
+        };
+        write!(f, "model '{}' has {}", self.model_id, missing)
+    }
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::InvalidModel { model_id, reason } => {
+                write!(f, "model '{}' is misconfigured: {}", model_id, reason)
+            }
+            CheckError::DuplicateModel { model_id } => {
+                write!(f, "more than one model has id '{}'", model_id)
+            }
+            CheckError::DuplicateConnector { connector_id } => {
+                write!(f, "more than one connector has id '{}'", connector_id)
+            }
+            CheckError::MissingModel { model_id } => {
+                write!(f, "no model has id '{}'", model_id)
+            }
+            CheckError::Rebuild { reason } => {
+                write!(f, "the simulation could not be rebuilt: {}", reason)
+            }
+            CheckError::UnknownModel {
+                connector_id,
+                model_id,
+            } => write!(
+                f,
+                "connector '{}' references unknown model '{}'",
+                connector_id, model_id
+            ),
+            CheckError::UnknownPort {
+                connector_id,
+                model_id,
+                port,
+            } => write!(
+                f,
+                "connector '{}' uses port '{}', which model '{}' does not declare",
+                connector_id, port, model_id
+            ),
+            CheckError::UndeliverableMessage {
+                index,
+                target_id,
+                target_port,
+            } => write!(
+                f,
+                "message {} targets port '{}' of model '{}', which does not exist",
+                index, target_port, target_id
+            ),
+            CheckError::Cycle { model_ids } => write!(
+                f,
+                "connectors form a cycle: {} -> {}",
+                model_ids.join(" -> "),
+                model_ids.first().map_or("", String::as_str)
+            ),
+            CheckError::Orphan(orphan) => write!(f, "{}", orphan),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+pub trait TopologyChecker {
+    fn check_topology(&self) -> Result<(), CheckError>;
+
+    /// Fails with the first of `cycles`, if any.
+    fn check_acyclic(&self) -> Result<(), CheckError>;
+
+    /// The directed cycles of the connector graph; see `cycles`.
+    fn cycles(&self) -> Vec<Vec<String>>;
+
+    /// Fails with the first of `orphans`, if any.
+    fn check_connected(&self) -> Result<(), CheckError>;
+
+    /// The models left unconnected; see `orphans`.
+    fn orphans(&self) -> Vec<Orphan>;
+}
+
+impl TopologyChecker for Simulation {
+    fn check_topology(&self) -> Result<(), CheckError> {
+        check_all(&introspection::models(self), &introspection::connectors(self))
+    }
+
+    fn check_acyclic(&self) -> Result<(), CheckError> {
+        check_acyclic(&introspection::connectors(self))
+    }
+
+    fn cycles(&self) -> Vec<Vec<String>> {
+        cycles(&introspection::connectors(self))
+    }
+
+    fn check_connected(&self) -> Result<(), CheckError> {
+        first_orphan(&introspection::models(self), &introspection::connectors(self))
+    }
+
+    fn orphans(&self) -> Vec<Orphan> {
+        find_orphans(&introspection::models(self), &introspection::connectors(self))
+    }
+}
+
+/// Runs every check on a topology that has not been posted yet. This is
+/// the only check that can catch duplicate model ids: a posted simulation
+/// keeps one model per id. Unconnected models are logged as warnings.
+pub fn check(models: &[Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    let models: Vec<&Model> = models.iter().collect();
+    check_all(&models, connectors)?;
+    find_orphans(&models, connectors)
+        .iter()
+        .for_each(|orphan| warn!("{}", orphan));
+    Ok(())
+}
+
+/// Fails with a `CheckError::Orphan` for the first model, by id, that is
+/// left unconnected.
+pub fn check_connected(models: &[Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    first_orphan(&models.iter().collect::<Vec<_>>(), connectors)
+}
+
+/// The models in `models` that are left unconnected by `connectors`, sorted
+/// by id; see `Orphan`. Models that declare no ports at all are never listed,
+/// since nothing is known about them.
+pub fn orphans(models: &[Model], connectors: &[Connector]) -> Vec<Orphan> {
+    find_orphans(&models.iter().collect::<Vec<_>>(), connectors)
+}
+
+/// Validates the configuration of every model defined in this crate.
+pub fn check_models(models: &[Model]) -> Result<(), CheckError> {
+    check_model_configuration(&models.iter().collect::<Vec<_>>())
+}
+
+/// Checks that every message in `messages` can be delivered on `simulation`:
+/// its target model exists and declares the target port as an input (models
+/// that cannot be described accept any). Fails on the first message that
+/// cannot.
+pub fn check_messages(simulation: &Simulation, messages: &[Message]) -> Result<(), CheckError> {
+    InputPorts::of(simulation).check(messages)
+}
+
+/// The input ports of every model of a simulation, for checking many
+/// batches of messages against one topology; see `check_messages`.
+pub(crate) struct InputPorts {
+    /// `None` for models that cannot be described, which accept any port.
+    ports: HashMap<String, Option<Vec<String>>>,
+}
+
+impl InputPorts {
+    pub(crate) fn of(simulation: &Simulation) -> Self {
+        let ports = simulation
+            .get_models()
+            .iter()
+            .map(|(id, model)| {
+                let ports_in = describe(model).ok().map(|description| description.ports_in);
+                (id.clone(), ports_in)
+            })
+            .collect();
+        Self { ports }
+    }
+
+    fn accepts(&self, message: &Message) -> bool {
+        self.ports.get(message.target_id()).is_some_and(|ports_in| {
+            ports_in
+                .as_ref()
+                .is_none_or(|ports_in| ports_in.iter().any(|port| port == message.target_port()))
+        })
+    }
+
+    pub(crate) fn check(&self, messages: &[Message]) -> Result<(), CheckError> {
+        match messages.iter().position(|message| !self.accepts(message)) {
+            Some(index) => Err(CheckError::UndeliverableMessage {
+                index,
+                target_id: messages[index].target_id().to_string(),
+                target_port: messages[index].target_port().to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Fails with a `CheckError::Cycle` if the connectors form any directed
+/// cycle.
+pub fn check_acyclic(connectors: &[Connector]) -> Result<(), CheckError> {
+    match cycles(connectors).into_iter().next() {
+        Some(model_ids) => Err(CheckError::Cycle { model_ids }),
+        None => Ok(()),
+    }
+}
+
+/// One directed cycle for every group of models that can reach each other
+/// through connectors (every strongly connected component with a cycle),
+/// ordered by the smallest id in each. A cycle lists its models in connector
+/// order, starting from that smallest id; a model connected to itself is a
+/// cycle of one. Models in the same group may lie on further cycles, which
+/// are not listed separately.
+pub fn cycles(connectors: &[Connector]) -> Vec<Vec<String>> {
+    let mut graph: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    connectors.iter().for_each(|connector| {
+        graph
+            .entry(connector.source_id())
+            .or_default()
+            .insert(connector.target_id());
+        graph.entry(connector.target_id()).or_default();
+    });
+    let mut components = strongly_connected_components(&graph);
+    components.iter_mut().for_each(|component| component.sort());
+    components.sort();
+    components
+        .into_iter()
+        .filter_map(|component| shortest_cycle(&graph, &component))
+        .collect()
+}
+
+/// Tarjan's algorithm over `graph`.
+fn strongly_connected_components<'a>(
+    graph: &BTreeMap<&'a str, BTreeSet<&'a str>>,
+) -> Vec<Vec<&'a str>> {
+    struct Tarjan<'a, 'g> {
+        graph: &'g BTreeMap<&'a str, BTreeSet<&'a str>>,
+        index: HashMap<&'a str, usize>,
+        low_link: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    impl<'a> Tarjan<'a, '_> {
+        fn visit(&mut self, node: &'a str) {
+            let index = self.index.len();
+            self.index.insert(node, index);
+            self.low_link.insert(node, index);
+            self.stack.push(node);
+            let graph = self.graph;
+            for &next in &graph[node] {
+                if !self.index.contains_key(next) {
+                    self.visit(next);
+                    let low = usize::min(self.low_link[node], self.low_link[next]);
+                    self.low_link.insert(node, low);
+                } else if self.stack.contains(&next) {
+                    let low = usize::min(self.low_link[node], self.index[next]);
+                    self.low_link.insert(node, low);
+                }
+            }
+            if self.low_link[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        graph,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+    for &node in graph.keys() {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
+
+/// The shortest cycle through the first (smallest) model of `component`
+/// that stays inside the component, or `None` if the component is a single
+/// model without a connector to itself.
+fn shortest_cycle(
+    graph: &BTreeMap<&str, BTreeSet<&str>>,
+    component: &[&str],
+) -> Option<Vec<String>> {
+    let start = *component.first()?;
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for &next in &graph[node] {
+            if next == start {
+                let mut cycle = vec![node.to_string()];
+                let mut current = node;
+                while current != start {
+                    current = previous[current];
+                    cycle.push(current.to_string());
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if component.contains(&next) && !previous.contains_key(next) {
+                previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn check_all(models: &[&Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    check_unique_ids(models, connectors)?;
+    check_model_configuration(models)?;
+    check_connector_models(models, connectors)?;
+    check_connector_ports(models, connectors)
+}
+
+/// Checks that no two models and no two connectors share an id. Messages
+/// are routed by model id, so a model whose id is taken would silently
+/// replace the other (or never receive anything) once posted.
+fn check_unique_ids(models: &[&Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    let mut model_ids = BTreeSet::new();
+    if let Some(model) = models.iter().find(|model| !model_ids.insert(model.id())) {
+        return Err(CheckError::DuplicateModel {
+            model_id: model.id().to_string(),
+        });
+    }
+    let mut connector_ids = BTreeSet::new();
+    match connectors
+        .iter()
+        .find(|connector| !connector_ids.insert(connector.id()))
+    {
+        Some(connector) => Err(CheckError::DuplicateConnector {
+            connector_id: connector.id().to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn first_orphan(models: &[&Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    match find_orphans(models, connectors).into_iter().next() {
+        Some(orphan) => Err(CheckError::Orphan(orphan)),
+        None => Ok(()),
+    }
+}
+
+fn find_orphans(models: &[&Model], connectors: &[Connector]) -> Vec<Orphan> {
+    let mut orphans: Vec<Orphan> = models
+        .iter()
+        .filter_map(|model| {
+            let description = describe(model).ok()?;
+            let no_incoming = !description.ports_in.is_empty()
+                && !connectors
+                    .iter()
+                    .any(|connector| connector.target_id() == model.id());
+            let no_outgoing = !description.ports_out.is_empty()
+                && !connectors
+                    .iter()
+                    .any(|connector| connector.source_id() == model.id());
+            (no_incoming || no_outgoing).then(|| Orphan {
+                model_id: model.id().to_string(),
+                no_incoming,
+                no_outgoing,
+            })
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+    orphans
+}
+
+fn check_model_configuration(models: &[&Model]) -> Result<(), CheckError> {
+    models.iter().try_for_each(|model| {
+        describe(model)
+            .and_then(|description| description.validation)
+            .map_err(|reason| CheckError::InvalidModel {
+                model_id: model.id().to_string(),
+                reason,
+            })
+    })
+}
+
+fn check_connector_models(models: &[&Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    connectors.iter().try_for_each(|connector| {
+        [connector.source_id(), connector.target_id()]
+            .into_iter()
+            .try_for_each(|model_id| {
+                if models.iter().any(|model| model.id() == model_id) {
+                    Ok(())
+                } else {
+                    Err(CheckError::UnknownModel {
+                        connector_id: connector.id().to_string(),
+                        model_id: model_id.to_string(),
+                    })
+                }
+            })
+    })
+}
+
+/// Checks that every connector leaves from a declared output port and
+/// arrives on a declared input port. A model that declares no ports in a
+/// direction accepts no connector there. Models that cannot be described
+/// are skipped, since nothing is known about them.
+fn check_connector_ports(models: &[&Model], connectors: &[Connector]) -> Result<(), CheckError> {
+    connectors.iter().try_for_each(|connector| {
+        let ends = [
+            (connector.source_id(), connector.source_port(), true),
+            (connector.target_id(), connector.target_port(), false),
+        ];
+        ends.into_iter().try_for_each(|(model_id, port, outgoing)| {
+            let Some(model) = models.iter().find(|model| model.id() == model_id) else {
+                return Ok(());
+            };
+            let ports = match describe(model) {
+                Ok(description) if outgoing => description.ports_out,
+                Ok(description) => description.ports_in,
+                Err(_) => return Ok(()),
+            };
+            if ports.iter().any(|declared| declared == port) {
+                Ok(())
+            } else {
+                Err(CheckError::UnknownPort {
+                    connector_id: connector.id().to_string(),
+                    model_id: model_id.to_string(),
+                    port: port.to_string(),
+                })
+            }
+        })
+    })
+}
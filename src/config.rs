@@ -0,0 +1,563 @@
+//! Loading a simulation topology from a YAML or JSON document.
+//!
+//! A document lists models, each with an `id`, a `type` tag and that type's
+//! constructor parameters, and the connectors between them:
+//!
+//! ```yaml
+//! seed: 42
+//! models:
+//!   - id: player-01
+//!     type: Processor
+//!     serviceTime: { exp: { lambda: 0.9 } }
+//!     jobPort: receive
+//!     processedJobPort: send
+//!   - id: Store
+//!     type: Storage
+//!     putPort: put
+//!     getPort: get
+//!     storedPort: stored
+//!     storeRecords: true
+//! connectors:
+//!   - id: player-01 to Store
+//!     sourceId: player-01
+//!     sourcePort: send
+//!     targetId: Store
+//!     targetPort: put
+//! ```
+//!
+//! Distributions are written as a single-key map from the camel-cased
+//! variant name to its parameters, the serde representation of both
+//! `sim::input_modeling::ContinuousRandomVariable` (for `sim`'s built-in
+//! models) and `ContinuousDistribution` (for the models of this crate).
+//!
+//! Stochastic models draw from the global RNG, seeded with the top-level
+//! `seed`. A model with a `seed` of its own draws from a private RNG seeded
+//! with it instead, so its draws stay the same when the top-level seed
+//! changes.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{Generator, Processor, ReportableModel, Storage};
+use sim::simulator::Simulation;
+
+use crate::builder::SimulationBuilder;
+use crate::checker::CheckError;
+use crate::input_modeling::{ContinuousDistribution, seeded_rng};
+use crate::models::{
+    Arrivals, Assembler, Balancing, Batcher, Breakdowns, Buffer, Classifier, Condition, Delay, Gate,
+    Inspector, LoadBalancer, Merge, Overflow, Priority, Router, Sink, Source, Splitter, Station,
+    Throttle,
+};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The document could not be read.
+    Io(std::io::Error),
+    /// The document is malformed, names an unknown model type or has
+    /// parameters that do not match the type.
+    Parse(String),
+    /// The topology was read but fails the checks in `crate::checker`.
+    Check(CheckError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "failed to read configuration: {}", err),
+            ConfigError::Parse(msg) => write!(f, "invalid configuration: {}", msg),
+            ConfigError::Check(err) => write!(f, "configuration check failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<CheckError> for ConfigError {
+    fn from(err: CheckError) -> Self {
+        ConfigError::Check(err)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulationConfig {
+    #[serde(default)]
+    seed: Option<u64>,
+    models: Vec<ModelConfig>,
+    #[serde(default)]
+    connectors: Vec<ConnectorConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelConfig {
+    id: String,
+    /// Seeds an RNG of the model's own instead of the global one.
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(flatten)]
+    model: ModelType,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+enum ModelType {
+    Processor {
+        service_time: ContinuousRandomVariable,
+        #[serde(default)]
+        queue_capacity: Option<usize>,
+        job_port: String,
+        processed_job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    /// `sim`'s `Storage`, or a `Buffer` when a `capacity` or
+    /// `initial_contents` is given.
+    Storage {
+        put_port: String,
+        get_port: String,
+        stored_port: String,
+        #[serde(default)]
+        capacity: Option<usize>,
+        #[serde(default)]
+        initial_contents: Option<Vec<String>>,
+        #[serde(default)]
+        overflow: Overflow,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Generator {
+        interarrival_time: ContinuousRandomVariable,
+        job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Assembler {
+        inputs: Vec<String>,
+        assembly_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Batcher {
+        batch_size: usize,
+        #[serde(default)]
+        timeout: Option<f64>,
+        job_port: String,
+        batch_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Classifier {
+        job_port: String,
+        key: String,
+        rules: Vec<(Condition, String)>,
+        default_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Delay {
+        delay_time: ContinuousDistribution,
+        job_port: String,
+        delayed_job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Gate {
+        job_port: String,
+        control_port: String,
+        released_job_port: String,
+        #[serde(default)]
+        open: bool,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Inspector {
+        p_pass: f64,
+        job_port: String,
+        pass_port: String,
+        fail_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    LoadBalancer {
+        job_port: String,
+        control_port: String,
+        outputs: Vec<String>,
+        #[serde(default)]
+        balancing: Balancing,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Merge {
+        inputs: Vec<String>,
+        job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Router {
+        job_port: String,
+        routes: Vec<(String, f64)>,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Sink {
+        job_port: String,
+        #[serde(default)]
+        interarrival_stats: bool,
+        #[serde(default)]
+        additional_ports: Vec<String>,
+    },
+    /// Takes either an `interarrivalTime` distribution, a `schedule` of
+    /// arrival times or `periodic` arrivals with a `period` and a `jitter`.
+    Source {
+        #[serde(flatten)]
+        arrivals: Arrivals,
+        #[serde(default)]
+        max_count: Option<usize>,
+        job_port: String,
+        #[serde(default)]
+        backpressure_port: Option<String>,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Splitter {
+        #[serde(default)]
+        spacing: Option<f64>,
+        batch_port: String,
+        job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Station {
+        service_time: ContinuousDistribution,
+        #[serde(default)]
+        queue_capacity: Option<usize>,
+        servers: usize,
+        #[serde(default)]
+        warmup: f64,
+        #[serde(default)]
+        priority: Priority,
+        #[serde(default)]
+        setup_time: Option<ContinuousDistribution>,
+        #[serde(default)]
+        breakdowns: Option<Breakdowns>,
+        job_port: String,
+        processed_job_port: String,
+        #[serde(default)]
+        backpressure_port: Option<String>,
+        #[serde(default)]
+        store_records: bool,
+    },
+    Throttle {
+        rate: f64,
+        job_port: String,
+        released_job_port: String,
+        #[serde(default)]
+        store_records: bool,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectorConfig {
+    id: String,
+    source_id: String,
+    source_port: String,
+    target_id: String,
+    target_port: String,
+}
+
+impl ModelType {
+    /// The model, drawing from `rng` if it is stochastic.
+    fn into_model(self, rng: Option<DynRng>) -> Box<dyn ReportableModel> {
+        match self {
+            ModelType::Processor {
+                service_time,
+                queue_capacity,
+                job_port,
+                processed_job_port,
+                store_records,
+            } => Box::new(Processor::new(
+                service_time,
+                queue_capacity,
+                job_port,
+                processed_job_port,
+                store_records,
+                rng,
+            )),
+            ModelType::Storage {
+                put_port,
+                get_port,
+                stored_port,
+                capacity: None,
+                initial_contents: None,
+                store_records,
+                ..
+            } => Box::new(Storage::new(put_port, get_port, stored_port, store_records)),
+            ModelType::Storage {
+                put_port,
+                get_port,
+                stored_port,
+                capacity,
+                initial_contents,
+                overflow,
+                store_records,
+            } => {
+                let buffer = Buffer::new(
+                    put_port,
+                    get_port,
+                    stored_port,
+                    capacity,
+                    overflow,
+                    store_records,
+                );
+                match initial_contents {
+                    Some(items) => Box::new(buffer.with_initial_contents(items)),
+                    None => Box::new(buffer),
+                }
+            }
+            ModelType::Generator {
+                interarrival_time,
+                job_port,
+                store_records,
+            } => Box::new(Generator::new(
+                interarrival_time,
+                None,
+                job_port,
+                store_records,
+                rng,
+            )),
+            ModelType::Assembler {
+                inputs,
+                assembly_port,
+                store_records,
+            } => Box::new(Assembler::new(inputs, assembly_port, store_records)),
+            ModelType::Batcher {
+                batch_size,
+                timeout,
+                job_port,
+                batch_port,
+                store_records,
+            } => {
+                let batcher = Batcher::new(batch_size, job_port, batch_port, store_records);
+                match timeout {
+                    Some(idle) => Box::new(batcher.with_timeout(idle)),
+                    None => Box::new(batcher),
+                }
+            }
+            ModelType::Delay {
+                delay_time,
+                job_port,
+                delayed_job_port,
+                store_records,
+            } => Box::new(Delay::new(
+                delay_time,
+                job_port,
+                delayed_job_port,
+                store_records,
+                rng,
+            )),
+            ModelType::Gate {
+                job_port,
+                control_port,
+                released_job_port,
+                open,
+                store_records,
+            } => Box::new(Gate::new(
+                job_port,
+                control_port,
+                released_job_port,
+                open,
+                store_records,
+            )),
+            ModelType::Inspector {
+                p_pass,
+                job_port,
+                pass_port,
+                fail_port,
+                store_records,
+            } => Box::new(Inspector::new(
+                p_pass,
+                job_port,
+                pass_port,
+                fail_port,
+                store_records,
+                rng,
+            )),
+            ModelType::Router {
+                job_port,
+                routes,
+                store_records,
+            } => Box::new(Router::new(job_port, routes, store_records, rng)),
+            ModelType::LoadBalancer {
+                job_port,
+                control_port,
+                outputs,
+                balancing,
+                store_records,
+            } => Box::new(LoadBalancer::new(
+                job_port,
+                control_port,
+                outputs,
+                balancing,
+                store_records,
+            )),
+            ModelType::Classifier {
+                job_port,
+                key,
+                rules,
+                default_port,
+                store_records,
+            } => Box::new(Classifier::new(
+                job_port,
+                key,
+                rules,
+                default_port,
+                store_records,
+            )),
+            ModelType::Merge {
+                inputs,
+                job_port,
+                store_records,
+            } => Box::new(Merge::new(inputs, job_port, store_records)),
+            ModelType::Sink {
+                job_port,
+                interarrival_stats,
+                additional_ports,
+            } => Box::new(
+                Sink::new(job_port, interarrival_stats).with_additional_ports(additional_ports),
+            ),
+            ModelType::Source {
+                arrivals,
+                max_count,
+                job_port,
+                backpressure_port,
+                store_records,
+            } => {
+                let source = Source::from_arrivals(arrivals, max_count, job_port, store_records, rng);
+                match backpressure_port {
+                    Some(port) => Box::new(source.with_backpressure(port)),
+                    None => Box::new(source),
+                }
+            }
+            ModelType::Splitter {
+                spacing,
+                batch_port,
+                job_port,
+                store_records,
+            } => {
+                let splitter = Splitter::new(batch_port, job_port, store_records);
+                match spacing {
+                    Some(spacing) => Box::new(splitter.with_spacing(spacing)),
+                    None => Box::new(splitter),
+                }
+            }
+            ModelType::Station {
+                service_time,
+                queue_capacity,
+                servers,
+                warmup,
+                priority,
+                setup_time,
+                breakdowns,
+                job_port,
+                processed_job_port,
+                backpressure_port,
+                store_records,
+            } => {
+                let station = Station::new(
+                    service_time,
+                    queue_capacity,
+                    servers,
+                    job_port,
+                    processed_job_port,
+                    store_records,
+                    rng,
+                )
+                .with_warmup(warmup)
+                .with_priority(priority);
+                let station = match setup_time {
+                    Some(setup_time) => station.with_setup_time(setup_time),
+                    None => station,
+                };
+                let station = match breakdowns {
+                    Some(breakdowns) => station
+                        .with_breakdowns(breakdowns.time_to_failure, breakdowns.time_to_repair),
+                    None => station,
+                };
+                match backpressure_port {
+                    Some(port) => Box::new(station.with_backpressure(port)),
+                    None => Box::new(station),
+                }
+            }
+            ModelType::Throttle {
+                rate,
+                job_port,
+                released_job_port,
+                store_records,
+            } => Box::new(Throttle::new(
+                rate,
+                job_port,
+                released_job_port,
+                store_records,
+            )),
+        }
+    }
+}
+
+impl SimulationConfig {
+    fn build(self) -> Result<Simulation, ConfigError> {
+        let builder = self
+            .models
+            .into_iter()
+            .fold(SimulationBuilder::new(), |builder, config| {
+                builder.model(&config.id, config.model.into_model(config.seed.map(seeded_rng)))
+            });
+        let builder = self.connectors.iter().fold(builder, |builder, connector| {
+            builder.connect(
+                &connector.id,
+                &connector.source_id,
+                &connector.source_port,
+                &connector.target_id,
+                &connector.target_port,
+            )
+        });
+        let builder = match self.seed {
+            Some(seed) => builder.seed(seed),
+            None => builder,
+        };
+        Ok(builder.build()?)
+    }
+}
+
+/// Constructors for a `Simulation` described by a configuration document.
+pub trait FromConfig: Sized {
+    fn from_yaml_str(document: &str) -> Result<Self, ConfigError>;
+    fn from_json_str(document: &str) -> Result<Self, ConfigError>;
+
+    fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::from_yaml_str(&fs::read_to_string(path).map_err(ConfigError::Io)?)
+    }
+
+    fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::from_json_str(&fs::read_to_string(path).map_err(ConfigError::Io)?)
+    }
+}
+
+impl FromConfig for Simulation {
+    fn from_yaml_str(document: &str) -> Result<Self, ConfigError> {
+        serde_yaml::from_str::<SimulationConfig>(document)
+            .map_err(|err| ConfigError::Parse(err.to_string()))?
+            .build()
+    }
+
+    fn from_json_str(document: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str::<SimulationConfig>(document)
+            .map_err(|err| ConfigError::Parse(err.to_string()))?
+            .build()
+    }
+}
@@ -0,0 +1,314 @@
+//! Diagram output for simulation topologies, complementing the DOT output of
+//! `Simulation::generate_dot_graph`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use sim::simulator::{Connector, Simulation};
+
+use crate::introspection;
+use crate::models::Ports;
+
+pub trait Diagram {
+    /// Renders the topology as a Graphviz DOT digraph in which every model is
+    /// a record node: its declared input ports on the left, its id in the
+    /// middle and its output ports on the right. Each connector is an edge
+    /// from the source port cell to the target port cell, labeled
+    /// `source_port -> target_port`. A port a model does not declare attaches
+    /// to the node as a whole.
+    fn generate_port_dot_graph(&self) -> String;
+
+    /// Like `generate_port_dot_graph`, with the nodes grouped into one
+    /// labeled `subgraph cluster_<type>` per model type (`Processor`,
+    /// `Storage`, ...). Edges are drawn outside the clusters, so connectors
+    /// cross cluster boundaries freely.
+    fn generate_dot_graph_clustered(&self) -> String;
+
+    /// Renders the topology as a Mermaid `graph LR` flowchart: one node per
+    /// model, labeled with the model id, and one edge per connector, labeled
+    /// with the connector id and its source and target ports.
+    fn generate_mermaid_graph(&self) -> String;
+
+    /// Like `generate_port_dot_graph`, with the number of messages that
+    /// crossed each connector appended to its label, e.g. `send -> receive
+    /// (12)`. `traffic` maps connector ids to counts, as collected by
+    /// `Session::traffic`. Connectors without traffic are drawn dashed.
+    fn generate_dot_graph_with_traffic(&self, traffic: &HashMap<String, usize>) -> String;
+
+    /// Like `generate_mermaid_graph`, with the message counts in `traffic`
+    /// appended to the edge labels as for `generate_dot_graph_with_traffic`.
+    /// Connectors without traffic are drawn as dotted links.
+    fn generate_mermaid_graph_with_traffic(&self, traffic: &HashMap<String, usize>) -> String;
+
+    /// Lays out `generate_port_dot_graph` with Graphviz and writes the result
+    /// as SVG to the file at `path`, replacing it if it exists. Needs the
+    /// Graphviz `dot` executable on the `PATH`.
+    fn render_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), RenderError>;
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// The `dot` executable could not be started, typically because
+    /// Graphviz is not installed.
+    GraphvizUnavailable(io::Error),
+    /// `dot` ran but failed, with its error output.
+    Graphviz(String),
+    /// The SVG could not be written.
+    Io(io::Error),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::GraphvizUnavailable(err) => {
+                write!(f, "could not run Graphviz 'dot', is it installed? {}", err)
+            }
+            RenderError::Graphviz(stderr) => write!(f, "Graphviz 'dot' failed: {}", stderr),
+            RenderError::Io(err) => write!(f, "failed to write SVG: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl Diagram for Simulation {
+    fn generate_port_dot_graph(&self) -> String {
+        dot_graph(self, false, None)
+    }
+
+    fn generate_dot_graph_clustered(&self) -> String {
+        dot_graph(self, true, None)
+    }
+
+    fn generate_mermaid_graph(&self) -> String {
+        mermaid_graph(self, None)
+    }
+
+    fn generate_dot_graph_with_traffic(&self, traffic: &HashMap<String, usize>) -> String {
+        dot_graph(self, false, Some(traffic))
+    }
+
+    fn generate_mermaid_graph_with_traffic(&self, traffic: &HashMap<String, usize>) -> String {
+        mermaid_graph(self, Some(traffic))
+    }
+
+    fn render_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), RenderError> {
+        let mut dot = Command::new("dot")
+            .arg("-Tsvg")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(RenderError::GraphvizUnavailable)?;
+        if let Some(mut stdin) = dot.stdin.take() {
+            // dot reports a graph it could not read on stderr, below.
+            let _ = stdin.write_all(self.generate_port_dot_graph().as_bytes());
+        }
+        let output = dot.wait_with_output().map_err(RenderError::Io)?;
+        if !output.status.success() {
+            return Err(RenderError::Graphviz(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        std::fs::write(path, output.stdout).map_err(RenderError::Io)
+    }
+}
+
+fn mermaid_graph(simulation: &Simulation, traffic: Option<&HashMap<String, usize>>) -> String {
+    let models = introspection::models(simulation);
+    let node_ids = node_ids(models.iter().map(|model| model.id()));
+    let mut graph = String::from("graph LR\n");
+    models.iter().for_each(|model| {
+        graph.push_str(&format!(
+            "    {}[\"{}\"]\n",
+            node_ids[model.id()],
+            mermaid_label(model.id())
+        ));
+    });
+    introspection::connectors(simulation).iter().for_each(|connector| {
+        let (Some(source), Some(target)) = (
+            node_ids.get(connector.source_id()),
+            node_ids.get(connector.target_id()),
+        ) else {
+            return;
+        };
+        let count = traffic.map(|traffic| traffic_count(traffic, connector));
+        let label = format!(
+            "{}: {} -> {}",
+            connector.id(),
+            connector.source_port(),
+            connector.target_port()
+        );
+        graph.push_str(&format!(
+            "    {} {}|\"{}\"| {}\n",
+            source,
+            if count == Some(0) { "-.->" } else { "-->" },
+            mermaid_label(&with_count(label, count)),
+            target
+        ));
+    });
+    graph
+}
+
+fn dot_graph(
+    simulation: &Simulation,
+    clustered: bool,
+    traffic: Option<&HashMap<String, usize>>,
+) -> String {
+    let models = introspection::models(simulation);
+    let node_ids = node_ids(models.iter().map(|model| model.id()));
+    let mut ports: HashMap<&str, (Vec<String>, Vec<String>)> = HashMap::new();
+    // without clustering every node goes in the one unnamed group.
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    models.iter().for_each(|model| {
+        let (ports_in, ports_out) = (model.ports_in(), model.ports_out());
+        let label = if ports_in.is_empty() && ports_out.is_empty() {
+            record_label(model.id())
+        } else {
+            format!(
+                "{{{{{}}}|{}|{{{}}}}}",
+                port_cells("in", &ports_in),
+                record_label(model.id()),
+                port_cells("out", &ports_out)
+            )
+        };
+        let group = if clustered {
+            introspection::model_type(&introspection::serialized(model)).to_string()
+        } else {
+            String::new()
+        };
+        groups
+            .entry(group)
+            .or_default()
+            .push(format!("{} [label=\"{}\"];", node_ids[model.id()], label));
+        ports.insert(model.id(), (ports_in, ports_out));
+    });
+
+    let mut graph = String::from("digraph {\n    rankdir=LR;\n    node [shape=record];\n");
+    groups.iter().for_each(|(model_type, nodes)| {
+        if clustered {
+            graph.push_str(&format!(
+                "    subgraph {} {{\n        label=\"{}\";\n",
+                cluster_id(model_type),
+                dot_string(model_type)
+            ));
+            nodes
+                .iter()
+                .for_each(|node| graph.push_str(&format!("        {}\n", node)));
+            graph.push_str("    }\n");
+        } else {
+            nodes
+                .iter()
+                .for_each(|node| graph.push_str(&format!("    {}\n", node)));
+        }
+    });
+    introspection::connectors(simulation).iter().for_each(|connector| {
+        let (Some(source), Some(target)) = (
+            node_ids.get(connector.source_id()),
+            node_ids.get(connector.target_id()),
+        ) else {
+            return;
+        };
+        let source_cell = ports[connector.source_id()]
+            .1
+            .iter()
+            .position(|port| port == connector.source_port())
+            .map_or(String::new(), |index| format!(":out_{}", index));
+        let target_cell = ports[connector.target_id()]
+            .0
+            .iter()
+            .position(|port| port == connector.target_port())
+            .map_or(String::new(), |index| format!(":in_{}", index));
+        let count = traffic.map(|traffic| traffic_count(traffic, connector));
+        let label = format!("{} -> {}", connector.source_port(), connector.target_port());
+        graph.push_str(&format!(
+            "    {}{} -> {}{} [label=\"{}\"{}];\n",
+            source,
+            source_cell,
+            target,
+            target_cell,
+            dot_string(&with_count(label, count)),
+            if count == Some(0) { ", style=dashed" } else { "" }
+        ));
+    });
+    graph.push_str("}\n");
+    graph
+}
+
+fn traffic_count(traffic: &HashMap<String, usize>, connector: &Connector) -> usize {
+    traffic.get(connector.id()).copied().unwrap_or_default()
+}
+
+/// `label` followed by the message count in parentheses, if there is one.
+fn with_count(label: String, count: Option<usize>) -> String {
+    match count {
+        Some(count) => format!("{} ({})", label, count),
+        None => label,
+    }
+}
+
+/// A DOT subgraph id for `model_type`. Graphviz only draws subgraphs whose id
+/// starts with `cluster` as a box.
+fn cluster_id(model_type: &str) -> String {
+    let sanitized: String = model_type
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    format!("cluster_{}", sanitized)
+}
+
+/// Maps model ids to node identifiers valid in both Mermaid and DOT.
+/// Characters outside `[A-Za-z0-9_]` become `_`, and a numeric suffix keeps
+/// ids unique when two models sanitize to the same identifier.
+fn node_ids<'a>(ids: impl Iterator<Item = &'a str>) -> HashMap<&'a str, String> {
+    let mut node_ids: HashMap<&str, String> = HashMap::new();
+    ids.for_each(|id| {
+        let sanitized: String = id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+            .collect();
+        // prefixing avoids leading digits and reserved words such as `end`.
+        let base = format!("m_{}", sanitized);
+        let mut node_id = base.clone();
+        let mut suffix = 2;
+        while node_ids.values().any(|existing| *existing == node_id) {
+            node_id = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        node_ids.insert(id, node_id);
+    });
+    node_ids
+}
+
+fn mermaid_label(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// Record cells `<{prefix}_{i}> port` for `ports`, stacked vertically.
+fn port_cells(prefix: &str, ports: &[String]) -> String {
+    ports
+        .iter()
+        .enumerate()
+        .map(|(index, port)| format!("<{}_{}> {}", prefix, index, record_label(port)))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Escapes the characters that structure a record label, inside a quoted
+/// DOT string.
+fn record_label(text: &str) -> String {
+    dot_string(text).chars().fold(String::new(), |mut label, c| {
+        if matches!(c, '{' | '}' | '|' | '<' | '>') {
+            label.push('\\');
+        }
+        label.push(c);
+        label
+    })
+}
+
+fn dot_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
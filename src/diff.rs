@@ -0,0 +1,120 @@
+//! Structured comparison of two simulation states, for tracking down where
+//! two runs that should agree start to diverge.
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+use sim::simulator::Simulation;
+
+/// One point at which two simulations differ; see `diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateDifference {
+    /// The simulation clocks differ.
+    Clock { left: f64, right: f64 },
+    /// A model exists in only one of the simulations; `left` tells which.
+    MissingModel { model_id: String, left: bool },
+    /// The serialized models differ, first at `field`, a `/`-separated path
+    /// into the model such as `state/records/3/time`. `None` marks a field
+    /// or element present on one side only.
+    Model {
+        model_id: String,
+        field: String,
+        left: Option<Value>,
+        right: Option<Value>,
+    },
+    /// The pending messages differ, first at position `index` in delivery
+    /// order. `None` marks a message pending on one side only.
+    PendingMessage {
+        index: usize,
+        left: Option<Value>,
+        right: Option<Value>,
+    },
+}
+
+/// Compares `left` and `right` model by model, by their serialized form, and
+/// reports where they first diverge: the clock, then each model that
+/// differs in id order, then the pending messages. Only the first differing
+/// field of each model and the first differing pending message are
+/// reported, since a divergence usually drags everything after it along.
+/// Identical simulations give an empty vector.
+pub fn diff(left: &Simulation, right: &Simulation) -> Vec<StateDifference> {
+    let mut differences = Vec::new();
+    let (left_time, right_time) = (left.get_global_time(), right.get_global_time());
+    if left_time != right_time {
+        differences.push(StateDifference::Clock {
+            left: left_time,
+            right: right_time,
+        });
+    }
+
+    let (left_models, right_models) = (left.get_models(), right.get_models());
+    let ids: BTreeSet<&String> = left_models.keys().chain(right_models.keys()).collect();
+    ids.into_iter().for_each(|id| {
+        match (left_models.get(id), right_models.get(id)) {
+            (Some(left_model), Some(right_model)) => {
+                let (left_value, right_value) = (to_value(left_model), to_value(right_model));
+                if let Some((field, left, right)) = first_difference(&left_value, &right_value) {
+                    differences.push(StateDifference::Model {
+                        model_id: id.clone(),
+                        field,
+                        left,
+                        right,
+                    });
+                }
+            }
+            (left_model, _) => differences.push(StateDifference::MissingModel {
+                model_id: id.clone(),
+                left: left_model.is_some(),
+            }),
+        }
+    });
+
+    let (left_messages, right_messages) = (left.get_messages(), right.get_messages());
+    let pending = (0..usize::max(left_messages.len(), right_messages.len())).find_map(|index| {
+        let left = left_messages.get(index).map(to_value);
+        let right = right_messages.get(index).map(to_value);
+        (left != right).then_some(StateDifference::PendingMessage { index, left, right })
+    });
+    differences.extend(pending);
+    differences
+}
+
+/// A `/`-separated path and the values found there on either side.
+type Divergence = (String, Option<Value>, Option<Value>);
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// The path to the first leaf at which `left` and `right` differ, with the
+/// values found there. Object keys are visited in sorted order.
+fn first_difference(left: &Value, right: &Value) -> Option<Divergence> {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+            keys.into_iter()
+                .find_map(|key| nested(key, left.get(key), right.get(key)))
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            (0..usize::max(left.len(), right.len())).find_map(|index| {
+                nested(&index.to_string(), left.get(index), right.get(index))
+            })
+        }
+        _ if left == right => None,
+        _ => Some((String::new(), Some(left.clone()), Some(right.clone()))),
+    }
+}
+
+fn nested(key: &str, left: Option<&Value>, right: Option<&Value>) -> Option<Divergence> {
+    match (left, right) {
+        (Some(left), Some(right)) => first_difference(left, right).map(|(path, left, right)| {
+            let field = if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}/{}", key, path)
+            };
+            (field, left, right)
+        }),
+        _ => Some((key.to_string(), left.cloned(), right.cloned())),
+    }
+}
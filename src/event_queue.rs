@@ -0,0 +1,141 @@
+//! A priority queue of messages in event order.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use sim::simulator::Message;
+
+use crate::session::{DEFAULT_TIME_EPSILON, event_order_within};
+
+/// A min-heap of messages keyed on `session::event_order`, with ties broken
+/// by insertion order. Pushing and popping are O(log n), so draining `n`
+/// messages costs O(n log n) however they arrive, without re-sorting the
+/// whole queue on every insertion.
+pub struct EventQueue {
+    heap: BinaryHeap<Reverse<Entry>>,
+    next_sequence: u64,
+    time_epsilon: f64,
+}
+
+struct Entry {
+    message: Message,
+    sequence: u64,
+    time_epsilon: f64,
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        event_order_within(&self.message, &other.message, self.time_epsilon)
+            .then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::with_time_epsilon(DEFAULT_TIME_EPSILON)
+    }
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A queue treating times that round to the same multiple of `epsilon`
+    /// as simultaneous; see `session::event_order_within`.
+    pub fn with_time_epsilon(epsilon: f64) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            next_sequence: 0,
+            time_epsilon: epsilon,
+        }
+    }
+
+    pub fn push(&mut self, message: Message) {
+        self.heap.push(Reverse(Entry {
+            message,
+            sequence: self.next_sequence,
+            time_epsilon: self.time_epsilon,
+        }));
+        self.next_sequence += 1;
+    }
+
+    /// Removes and returns the earliest message.
+    pub fn pop(&mut self) -> Option<Message> {
+        self.heap.pop().map(|Reverse(entry)| entry.message)
+    }
+
+    /// The earliest message, without removing it.
+    pub fn peek(&self) -> Option<&Message> {
+        self.heap.peek().map(|Reverse(entry)| &entry.message)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn time_epsilon(&self) -> f64 {
+        self.time_epsilon
+    }
+
+    /// Treats times that round to the same multiple of `epsilon` as
+    /// simultaneous from now on, reordering the queued messages.
+    pub fn set_time_epsilon(&mut self, epsilon: f64) {
+        self.time_epsilon = epsilon;
+        let entries = std::mem::take(&mut self.heap).into_vec();
+        self.heap = entries
+            .into_iter()
+            .map(|Reverse(mut entry)| {
+                entry.time_epsilon = epsilon;
+                Reverse(entry)
+            })
+            .collect();
+    }
+
+    /// Removes every message and returns them earliest first, keeping the
+    /// queue's allocation for reuse.
+    pub fn drain_ordered(&mut self) -> Vec<Message> {
+        let mut messages = Vec::with_capacity(self.len());
+        while let Some(message) = self.pop() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// Every message, earliest first.
+    pub fn into_ordered_vec(mut self) -> Vec<Message> {
+        self.drain_ordered()
+    }
+}
+
+impl Extend<Message> for EventQueue {
+    fn extend<I: IntoIterator<Item = Message>>(&mut self, messages: I) {
+        messages.into_iter().for_each(|message| self.push(message));
+    }
+}
+
+impl FromIterator<Message> for EventQueue {
+    fn from_iter<I: IntoIterator<Item = Message>>(messages: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(messages);
+        queue
+    }
+}
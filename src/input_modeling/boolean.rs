@@ -0,0 +1,35 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::utils::errors::SimulationError;
+
+/// A random yes/no decision, sampled from the simulation RNG.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BooleanDistribution {
+    /// `true` with probability `p`.
+    Bernoulli { p: f64 },
+}
+
+impl BooleanDistribution {
+    /// Checks the parameters, describing the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Bernoulli { p } => {
+                if !(0.0..=1.0).contains(p) {
+                    return Err(format!("bernoulli probability must be in [0, 1], got {}", p));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn random_variate(&self, uniform_rng: DynRng) -> Result<bool, SimulationError> {
+        self.validate()
+            .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+        let mut rng = uniform_rng.borrow_mut();
+        match self {
+            Self::Bernoulli { p } => Ok(rng.gen_bool(*p)),
+        }
+    }
+}
@@ -0,0 +1,618 @@
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Gamma, LogNormal, Normal, Weibull};
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::utils::errors::SimulationError;
+
+/// A continuous random variable, sampled from the simulation RNG.
+///
+/// This mirrors `sim::input_modeling::ContinuousRandomVariable`, with the
+/// addition of a deterministic `Constant` variant for fixed durations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContinuousDistribution {
+    /// Always yields `value`.
+    Constant { value: f64 },
+    /// Exponential distribution with rate `lambda`.
+    Exp { lambda: f64 },
+    /// Normal distribution. Draws may be negative; use `TruncatedNormal` for
+    /// durations.
+    Normal { mean: f64, std_dev: f64 },
+    /// Normal distribution restricted to non-negative values. Negative draws
+    /// are rejected and resampled, so the result follows the normal
+    /// distribution conditioned on `x >= 0` rather than piling up mass at 0.
+    TruncatedNormal { mean: f64, std_dev: f64 },
+    /// Inverse-transform sampling over observed data, interpolating linearly
+    /// between neighbouring order statistics. `samples` must be sorted in
+    /// ascending order; `ContinuousDistribution::empirical` sorts for you.
+    Empirical { samples: Vec<f64> },
+    /// A histogram with bin edges `bins` (ascending, one more than `counts`)
+    /// and a count per bin. A bin is chosen in proportion to its count and
+    /// the draw is uniform within it, i.e. the CDF is linear inside each bin.
+    Histogram { bins: Vec<f64>, counts: Vec<f64> },
+    /// Weibull distribution, sampled by inverse transform. A `shape` below 1
+    /// gives a decreasing failure rate, above 1 an increasing one.
+    Weibull { shape: f64, scale: f64 },
+    /// Gamma distribution with mean `shape * scale`, sampled with the
+    /// Marsaglia-Tsang method.
+    Gamma { shape: f64, scale: f64 },
+    /// Triangular distribution on `[min, max]` peaking at `mode`, for inputs
+    /// only known from minimum, most likely and maximum estimates. Sampled
+    /// by inverse transform; the mean is `(min + mode + max) / 3`.
+    Triangular { min: f64, mode: f64, max: f64 },
+    /// Lognormal distribution, the exponential of a normal draw with mean
+    /// `mu` and standard deviation `sigma`. The median is `exp(mu)`.
+    LogNormal { mu: f64, sigma: f64 },
+    /// Uniform distribution on `[min, max)`.
+    Uniform { min: f64, max: f64 },
+    /// Pareto distribution on `[scale, inf)`, for heavy-tailed workloads:
+    /// `P(X > x) = (scale / x)^shape`. Sampled by inverse transform. The
+    /// mean is infinite for a `shape` of 1 or less and the variance for a
+    /// `shape` of 2 or less.
+    Pareto { scale: f64, shape: f64 },
+    /// Beta distribution on `[0, 1]`, for yields and other proportions. The
+    /// mean is `alpha / (alpha + beta)`. Sampled as `X / (X + Y)` from two
+    /// gamma draws with shapes `alpha` and `beta`.
+    Beta { alpha: f64, beta: f64 },
+}
+
+/// Upper bound on rejected draws for `TruncatedNormal`, so a configuration
+/// with almost no mass above zero fails instead of spinning forever.
+const MAX_RESAMPLES: usize = 10_000;
+
+impl ContinuousDistribution {
+    /// An `Empirical` distribution over `samples`, in any order.
+    pub fn empirical(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        Self::Empirical { samples }
+    }
+
+    /// Checks the parameters, describing the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Constant { value } => {
+                if !value.is_finite() {
+                    return Err(format!("constant value must be finite, got {}", value));
+                }
+                Ok(())
+            }
+            Self::Exp { lambda } => {
+                if !(lambda.is_finite() && *lambda > 0.0) {
+                    return Err(format!("exp lambda must be positive, got {}", lambda));
+                }
+                Ok(())
+            }
+            Self::Normal { mean, std_dev } | Self::TruncatedNormal { mean, std_dev } => {
+                if !(mean.is_finite() && std_dev.is_finite() && *std_dev >= 0.0) {
+                    return Err(format!(
+                        "normal needs a finite mean and non-negative std_dev, got mean {} and std_dev {}",
+                        mean, std_dev
+                    ));
+                }
+                Ok(())
+            }
+            Self::Empirical { samples } => {
+                if samples.is_empty() {
+                    return Err(String::from("empirical distribution has no samples"));
+                }
+                if !samples.is_sorted() {
+                    return Err(String::from("empirical samples must be sorted ascending"));
+                }
+                Ok(())
+            }
+            Self::Histogram { bins, counts } => {
+                if counts.is_empty() || bins.len() != counts.len() + 1 {
+                    return Err(format!(
+                        "histogram needs one more bin edge than counts, got {} edges and {} counts",
+                        bins.len(),
+                        counts.len()
+                    ));
+                }
+                if bins.windows(2).any(|edges| edges[0] >= edges[1]) {
+                    return Err(String::from("histogram bin edges must be strictly increasing"));
+                }
+                if counts.iter().any(|count| !count.is_finite() || *count < 0.0) {
+                    return Err(String::from("histogram counts must be non-negative"));
+                }
+                if counts.iter().sum::<f64>() <= 0.0 {
+                    return Err(String::from("histogram counts must not all be zero"));
+                }
+                Ok(())
+            }
+            Self::Weibull { shape, scale } | Self::Gamma { shape, scale } => {
+                if !(shape.is_finite() && *shape > 0.0 && scale.is_finite() && *scale > 0.0) {
+                    return Err(format!(
+                        "shape and scale must be positive, got shape {} and scale {}",
+                        shape, scale
+                    ));
+                }
+                Ok(())
+            }
+            Self::Triangular { min, mode, max } => {
+                if ![min, mode, max].iter().all(|value| value.is_finite()) {
+                    return Err(String::from("triangular bounds and mode must be finite"));
+                }
+                if !(min <= mode && mode <= max && min < max) {
+                    return Err(format!(
+                        "triangular needs min <= mode <= max with min < max, got {}, {}, {}",
+                        min, mode, max
+                    ));
+                }
+                Ok(())
+            }
+            Self::LogNormal { mu, sigma } => {
+                if !(mu.is_finite() && sigma.is_finite() && *sigma > 0.0) {
+                    return Err(format!(
+                        "lognormal needs a finite mu and positive sigma, got mu {} and sigma {}",
+                        mu, sigma
+                    ));
+                }
+                Ok(())
+            }
+            Self::Uniform { min, max } => {
+                if !(min.is_finite() && max.is_finite() && min < max) {
+                    return Err(format!(
+                        "uniform needs finite bounds with min < max, got min {} and max {}",
+                        min, max
+                    ));
+                }
+                Ok(())
+            }
+            Self::Pareto { scale, shape } => {
+                if !(scale.is_finite() && *scale > 0.0 && shape.is_finite() && *shape > 0.0) {
+                    return Err(format!(
+                        "pareto scale and shape must be positive, got scale {} and shape {}",
+                        scale, shape
+                    ));
+                }
+                Ok(())
+            }
+            Self::Beta { alpha, beta } => {
+                if !(alpha.is_finite() && *alpha > 0.0 && beta.is_finite() && *beta > 0.0) {
+                    return Err(format!(
+                        "beta alpha and beta must be positive, got alpha {} and beta {}",
+                        alpha, beta
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The cumulative distribution function: the probability of a draw no
+    /// greater than `x`. Closed forms are used where they exist; the gamma
+    /// family (`Normal`, `TruncatedNormal`, `LogNormal` and `Gamma`) goes
+    /// through the regularized incomplete gamma function and `Beta` through
+    /// the regularized incomplete beta function, both accurate to about
+    /// 1e-14. `Empirical` and `Histogram` follow the piecewise linear CDF
+    /// they sample from. The inverse is `inverse_cdf`. Expects valid
+    /// parameters; see `validate`.
+    pub fn cdf(&self, x: f64) -> f64 {
+        match self {
+            Self::Constant { value } => step(x, *value),
+            Self::Exp { lambda } => {
+                if x <= 0.0 {
+                    0.0
+                } else {
+                    -(-lambda * x).exp_m1()
+                }
+            }
+            Self::Normal { mean, std_dev } => normal_cdf(x, *mean, *std_dev),
+            Self::TruncatedNormal { mean, std_dev } => {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                let below_zero = normal_cdf(0.0, *mean, *std_dev);
+                (normal_cdf(x, *mean, *std_dev) - below_zero) / (1.0 - below_zero)
+            }
+            Self::Empirical { samples } => {
+                let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+                    return 0.0;
+                };
+                if x < *first {
+                    return 0.0;
+                }
+                if x >= *last {
+                    return 1.0;
+                }
+                // samples[lower] <= x < samples[lower + 1]
+                let lower = samples.partition_point(|sample| *sample <= x) - 1;
+                let fraction = (x - samples[lower]) / (samples[lower + 1] - samples[lower]);
+                (lower as f64 + fraction) / (samples.len() - 1) as f64
+            }
+            Self::Histogram { bins, counts } => {
+                let total: f64 = counts.iter().sum();
+                let below: f64 = bins
+                    .windows(2)
+                    .zip(counts)
+                    .map(|(edges, count)| {
+                        count * ((x - edges[0]) / (edges[1] - edges[0])).clamp(0.0, 1.0)
+                    })
+                    .sum();
+                below / total
+            }
+            Self::Weibull { shape, scale } => {
+                if x <= 0.0 {
+                    0.0
+                } else {
+                    -(-(x / scale).powf(*shape)).exp_m1()
+                }
+            }
+            Self::Gamma { shape, scale } => regularized_gamma(*shape, x / scale).0,
+            Self::Triangular { min, mode, max } => {
+                if x <= *min {
+                    0.0
+                } else if x >= *max {
+                    1.0
+                } else if x <= *mode {
+                    (x - min).powi(2) / ((max - min) * (mode - min))
+                } else {
+                    1.0 - (max - x).powi(2) / ((max - min) * (max - mode))
+                }
+            }
+            Self::LogNormal { mu, sigma } => {
+                if x <= 0.0 {
+                    0.0
+                } else {
+                    normal_cdf(x.ln(), *mu, *sigma)
+                }
+            }
+            Self::Uniform { min, max } => ((x - min) / (max - min)).clamp(0.0, 1.0),
+            Self::Pareto { scale, shape } => {
+                if x <= *scale {
+                    0.0
+                } else {
+                    1.0 - (scale / x).powf(*shape)
+                }
+            }
+            Self::Beta { alpha, beta } => regularized_beta(*alpha, *beta, x),
+        }
+    }
+
+    /// The quantile function: the smallest `x` with `cdf(x) >= p`, for `p`
+    /// in `[0, 1]`, and NaN for any other `p`. Closed forms are used where
+    /// they exist; `Normal`, `TruncatedNormal`, `LogNormal`, `Gamma` and
+    /// `Beta` are inverted numerically by bisection on `cdf`, to within a
+    /// few ULPs.
+    /// Unbounded variants give an infinite `x` for `p` of 0 or 1. Expects
+    /// valid parameters; see `validate`.
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        if !(0.0..=1.0).contains(&p) {
+            return f64::NAN;
+        }
+        match self {
+            Self::Constant { value } => *value,
+            Self::Exp { lambda } => -(-p).ln_1p() / lambda,
+            Self::Normal { mean, std_dev } => {
+                if *std_dev == 0.0 {
+                    return *mean;
+                }
+                match p {
+                    0.0 => f64::NEG_INFINITY,
+                    1.0 => f64::INFINITY,
+                    _ => invert(|x| self.cdf(x), p, mean - std_dev, mean + std_dev),
+                }
+            }
+            Self::TruncatedNormal { mean, std_dev } => match p {
+                0.0 => 0.0,
+                1.0 => f64::INFINITY,
+                _ => invert(|x| self.cdf(x), p, 0.0, f64::max(mean + std_dev, *std_dev)),
+            },
+            Self::Empirical { samples } => {
+                if samples.is_empty() {
+                    return f64::NAN;
+                }
+                let position = p * (samples.len() - 1) as f64;
+                let lower = position.floor() as usize;
+                let upper = usize::min(lower + 1, samples.len() - 1);
+                let fraction = position - lower as f64;
+                samples[lower] + fraction * (samples[upper] - samples[lower])
+            }
+            Self::Histogram { bins, counts } => {
+                let mut remaining = p * counts.iter().sum::<f64>();
+                let bin = counts
+                    .iter()
+                    .position(|count| {
+                        if *count > 0.0 && remaining <= *count {
+                            return true;
+                        }
+                        remaining -= count;
+                        false
+                    })
+                    .unwrap_or(counts.len() - 1);
+                let fraction = (remaining / counts[bin]).clamp(0.0, 1.0);
+                bins[bin] + fraction * (bins[bin + 1] - bins[bin])
+            }
+            Self::Weibull { shape, scale } => scale * (-(-p).ln_1p()).powf(1.0 / shape),
+            Self::Gamma { scale, .. } => match p {
+                0.0 => 0.0,
+                1.0 => f64::INFINITY,
+                _ => invert(|x| self.cdf(x), p, 0.0, *scale),
+            },
+            Self::Triangular { min, mode, max } => {
+                let split = (mode - min) / (max - min);
+                if p < split {
+                    min + (p * (max - min) * (mode - min)).sqrt()
+                } else {
+                    max - ((1.0 - p) * (max - min) * (max - mode)).sqrt()
+                }
+            }
+            Self::LogNormal { mu, sigma } => Self::Normal {
+                mean: *mu,
+                std_dev: *sigma,
+            }
+            .inverse_cdf(p)
+            .exp(),
+            Self::Uniform { min, max } => min + p * (max - min),
+            Self::Pareto { scale, shape } => scale / (1.0 - p).powf(1.0 / shape),
+            Self::Beta { .. } => match p {
+                0.0 => 0.0,
+                1.0 => 1.0,
+                _ => invert(|x| self.cdf(x), p, 0.0, 1.0),
+            },
+        }
+    }
+
+    pub fn random_variate(&self, uniform_rng: DynRng) -> Result<f64, SimulationError> {
+        let mut rng = uniform_rng.borrow_mut();
+        match self {
+            Self::Constant { value } => Ok(*value),
+            Self::Exp { lambda } => Ok(Exp::new(*lambda)
+                .map_err(|_| SimulationError::InvalidModelConfiguration)?
+                .sample(&mut *rng)),
+            Self::Normal { mean, std_dev } => Ok(Normal::new(*mean, *std_dev)
+                .map_err(|_| SimulationError::InvalidModelConfiguration)?
+                .sample(&mut *rng)),
+            Self::TruncatedNormal { mean, std_dev } => {
+                let normal = Normal::new(*mean, *std_dev)
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                (0..MAX_RESAMPLES)
+                    .map(|_| normal.sample(&mut *rng))
+                    .find(|variate| *variate >= 0.0)
+                    .ok_or(SimulationError::InvalidModelConfiguration)
+            }
+            Self::Empirical { samples } => {
+                if samples.is_empty() {
+                    return Err(SimulationError::InvalidModelConfiguration);
+                }
+                let position = rng.gen_range(0.0..1.0) * (samples.len() - 1) as f64;
+                let lower = position.floor() as usize;
+                let upper = usize::min(lower + 1, samples.len() - 1);
+                let fraction = position - lower as f64;
+                Ok(samples[lower] + fraction * (samples[upper] - samples[lower]))
+            }
+            Self::Histogram { bins, counts } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                let total: f64 = counts.iter().sum();
+                let mut draw = rng.gen_range(0.0..total);
+                let bin = counts
+                    .iter()
+                    .position(|count| {
+                        draw -= count;
+                        draw < 0.0
+                    })
+                    .unwrap_or(counts.len() - 1);
+                Ok(rng.gen_range(bins[bin]..bins[bin + 1]))
+            }
+            Self::Weibull { shape, scale } => Ok(Weibull::new(*scale, *shape)
+                .map_err(|_| SimulationError::InvalidModelConfiguration)?
+                .sample(&mut *rng)),
+            Self::Gamma { shape, scale } => Ok(Gamma::new(*shape, *scale)
+                .map_err(|_| SimulationError::InvalidModelConfiguration)?
+                .sample(&mut *rng)),
+            Self::Triangular { min, mode, max } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                let u: f64 = rng.gen_range(0.0..1.0);
+                let split = (mode - min) / (max - min);
+                if u < split {
+                    Ok(min + (u * (max - min) * (mode - min)).sqrt())
+                } else {
+                    Ok(max - ((1.0 - u) * (max - min) * (max - mode)).sqrt())
+                }
+            }
+            Self::LogNormal { mu, sigma } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                Ok(LogNormal::new(*mu, *sigma)
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?
+                    .sample(&mut *rng))
+            }
+            Self::Uniform { min, max } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                Ok(rng.gen_range(*min..*max))
+            }
+            Self::Pareto { scale, shape } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                // 1 - u is in (0, 1], which keeps the draw finite.
+                let u: f64 = rng.gen_range(0.0..1.0);
+                Ok(scale / (1.0 - u).powf(1.0 / shape))
+            }
+            Self::Beta { alpha, beta } => {
+                self.validate()
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                let x = Gamma::new(*alpha, 1.0)
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                let y = Gamma::new(*beta, 1.0)
+                    .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+                // with tiny shapes both draws can underflow to zero.
+                (0..MAX_RESAMPLES)
+                    .map(|_| (x.sample(&mut *rng), y.sample(&mut *rng)))
+                    .find(|(x, y)| x + y > 0.0)
+                    .map(|(x, y)| x / (x + y))
+                    .ok_or(SimulationError::InvalidModelConfiguration)
+            }
+        }
+    }
+}
+
+/// The smallest `x` with `cdf(x) >= p` for a non-decreasing `cdf`, for `p`
+/// strictly between 0 and 1: widens `[lower, upper]` until it brackets `x`,
+/// then bisects down to neighbouring floats.
+pub(crate) fn invert<F: Fn(f64) -> f64>(cdf: F, p: f64, mut lower: f64, mut upper: f64) -> f64 {
+    const MAX_HALVINGS: usize = 2_000;
+    while cdf(lower) >= p {
+        lower -= upper - lower;
+    }
+    while cdf(upper) < p {
+        upper += upper - lower;
+    }
+    for _ in 0..MAX_HALVINGS {
+        let middle = lower + (upper - lower) / 2.0;
+        if middle <= lower || middle >= upper {
+            break;
+        }
+        if cdf(middle) < p {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    upper
+}
+
+/// The CDF of a point mass at `at`.
+fn step(x: f64, at: f64) -> f64 {
+    if x >= at { 1.0 } else { 0.0 }
+}
+
+fn normal_cdf(x: f64, mean: f64, std_dev: f64) -> f64 {
+    if std_dev == 0.0 {
+        return step(x, mean);
+    }
+    // Phi(z) = erfc(-z / sqrt 2) / 2, and erfc(t) = Q(1/2, t^2) for t >= 0.
+    let z = (x - mean) / std_dev;
+    let tail = regularized_gamma(0.5, z * z / 2.0).1 / 2.0;
+    if z < 0.0 { tail } else { 1.0 - tail }
+}
+
+/// The regularized lower and upper incomplete gamma functions `(P(a, x),
+/// Q(a, x))`, for `a > 0`, by series below `a + 1` and by continued
+/// fraction above, computing whichever of the two is small directly.
+fn regularized_gamma(a: f64, x: f64) -> (f64, f64) {
+    const EPSILON: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+    const MAX_TERMS: usize = 1_000;
+    if x <= 0.0 {
+        return (0.0, 1.0);
+    }
+    if x.is_infinite() {
+        return (1.0, 0.0);
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..MAX_TERMS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        let p = sum * prefactor;
+        (p, 1.0 - p)
+    } else {
+        // modified Lentz's method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_TERMS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        let q = prefactor * h;
+        (1.0 - q, q)
+    }
+}
+
+/// The regularized incomplete beta function `I_x(a, b)`, for `a, b > 0`, by
+/// continued fraction, using `I_x(a, b) = 1 - I_(1-x)(b, a)` where that
+/// converges faster.
+pub(crate) fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let prefactor =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (-x).ln_1p()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        prefactor * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - prefactor * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// The continued fraction of `regularized_beta`, by the modified Lentz's
+/// method.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+    const MAX_TERMS: usize = 1_000;
+    let clamp = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..MAX_TERMS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// The natural logarithm of the gamma function for `x > 0`, by the Lanczos
+/// approximation (g = 7, nine coefficients).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // reflection: Gamma(x) Gamma(1 - x) = pi / sin(pi x)
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, coefficient)| sum + coefficient / (x + (i + 1) as f64));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
@@ -0,0 +1,56 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::utils::errors::SimulationError;
+
+/// A random index into a list of choices, sampled from the simulation RNG.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexDistribution {
+    /// Index `i` with probability proportional to `weights[i]`. Weights do
+    /// not need to sum to one.
+    WeightedIndex { weights: Vec<f64> },
+}
+
+impl IndexDistribution {
+    /// Checks the parameters, describing the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::WeightedIndex { weights } => {
+                if weights.is_empty() {
+                    return Err(String::from("weighted index has no weights"));
+                }
+                if let Some((index, weight)) = weights
+                    .iter()
+                    .enumerate()
+                    .find(|(_, weight)| !weight.is_finite() || **weight < 0.0)
+                {
+                    return Err(format!("weight {} is invalid: {}", index, weight));
+                }
+                if weights.iter().sum::<f64>() <= 0.0 {
+                    return Err(String::from("weights must not all be zero"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn random_variate(&self, uniform_rng: DynRng) -> Result<usize, SimulationError> {
+        self.validate()
+            .map_err(|_| SimulationError::InvalidModelConfiguration)?;
+        let mut rng = uniform_rng.borrow_mut();
+        match self {
+            Self::WeightedIndex { weights } => {
+                let total: f64 = weights.iter().sum();
+                let mut draw = rng.gen_range(0.0..total);
+                Ok(weights
+                    .iter()
+                    .position(|weight| {
+                        draw -= weight;
+                        draw < 0.0
+                    })
+                    .unwrap_or(weights.len() - 1))
+            }
+        }
+    }
+}
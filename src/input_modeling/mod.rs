@@ -0,0 +1,164 @@
+//! Random variables used to parameterize the models in this crate.
+
+pub mod boolean;
+pub mod continuous;
+pub mod index;
+
+pub use self::boolean::BooleanDistribution;
+pub use self::continuous::ContinuousDistribution;
+pub use self::index::IndexDistribution;
+
+use std::fmt;
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use sim::input_modeling::dynamic_rng::{dyn_rng, DynRng};
+
+/// A deterministic RNG for `seed`. Pass it to a model constructor (or use
+/// `SimulationExt::post_with_seed`) to make a run reproducible.
+pub fn seeded_rng(seed: u64) -> DynRng {
+    dyn_rng(Pcg64Mcg::seed_from_u64(seed))
+}
+
+/// The seed of the RNG stream of `model_id` under `master_seed`: a hash of
+/// both, computed the same way on every platform and Rust version.
+pub fn stream_seed(master_seed: u64, model_id: &str) -> u64 {
+    // FNV-1a over the id, then the SplitMix64 finalizer to spread the bits.
+    let hash = model_id.bytes().fold(0xcbf2_9ce4_8422_2325 ^ master_seed, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let mut z = hash.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// An RNG stream of its own for `model_id`, derived from `master_seed`.
+///
+/// Give every stochastic model its stream (pass it as the model's `rng`) to
+/// get common random numbers across configurations: each model's draws then
+/// depend only on the master seed and its id, so changing one model leaves
+/// the draws of every other model as they were.
+pub fn stream_rng(master_seed: u64, model_id: &str) -> DynRng {
+    seeded_rng(stream_seed(master_seed, model_id))
+}
+
+/// The RNG for `model_id`: seeded with `seed` if one is given, which pins
+/// the model's draws whatever the master seed, e.g. to vary everything but
+/// one model in a sensitivity analysis, and otherwise its `stream_rng`.
+pub fn model_rng(master_seed: u64, model_id: &str, seed: Option<u64>) -> DynRng {
+    match seed {
+        Some(seed) => seeded_rng(seed),
+        None => stream_rng(master_seed, model_id),
+    }
+}
+
+/// Where an RNG made by `seeded_rng` (or `stream_rng`, `model_rng`) is in its
+/// stream. Resuming it gives a generator that draws exactly what the
+/// original draws from the point of capture on, independent of it.
+///
+/// Snapshots and rebuilt simulations carry their RNGs over this way, and the
+/// private RNG of a model of this crate serializes as its state, so a model
+/// read back from its serialized form keeps its stream. It serializes as a
+/// hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RngState(u128);
+
+impl RngState {
+    /// The state of `rng`, or `None` if it is not a generator of this
+    /// crate's type (`Pcg64Mcg`).
+    pub fn capture(rng: &DynRng) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Mcg {
+            state: u128,
+        }
+        let rng = rng.borrow();
+        let generator = rng.as_any().downcast_ref::<Pcg64Mcg>()?;
+        // `Pcg64Mcg` has no accessor for its state, only its serde form.
+        let serialized = serde_json::to_string(generator).ok()?;
+        let Mcg { state } = serde_json::from_str(&serialized).ok()?;
+        Some(Self(state))
+    }
+
+    /// A new generator continuing from this state.
+    pub fn resume(&self) -> DynRng {
+        dyn_rng(self.generator())
+    }
+
+    pub(crate) fn generator(&self) -> Pcg64Mcg {
+        Pcg64Mcg::new(self.0)
+    }
+}
+
+impl Serialize for RngState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:032x}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for RngState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StateVisitor;
+
+        impl Visitor<'_> for StateVisitor {
+            type Value = RngState;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an RNG state as a hex string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<RngState, E> {
+                u128::from_str_radix(value, 16)
+                    .map(RngState)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(StateVisitor)
+    }
+}
+
+/// (De)serializes the private RNG of a model as its `RngState`, for use with
+/// `#[serde(with = "...")]`. A generator `RngState::capture` cannot read is
+/// left out, as if the model had none.
+pub(crate) mod private_rng {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sim::input_modeling::dynamic_rng::DynRng;
+
+    use super::RngState;
+
+    pub(crate) fn serialize<S: Serializer>(
+        rng: &Option<DynRng>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        rng.as_ref().and_then(RngState::capture).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DynRng>, D::Error> {
+        Ok(Option::<RngState>::deserialize(deserializer)?.map(|state| state.resume()))
+    }
+}
+
+/// The Kolmogorov-Smirnov statistic of `samples` against `distribution`:
+/// the largest distance between the empirical CDF of the samples and
+/// `ContinuousDistribution::cdf`, between 0 and 1. The smaller, the better
+/// the fit. For `n` samples from the distribution itself, the statistic
+/// exceeds `1.36 / sqrt(n)` only 5% of the time, provided the distribution
+/// was not fitted to these same samples. Empty `samples` give 0.
+pub fn goodness_of_fit(samples: &[f64], distribution: &ContinuousDistribution) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let cdf = distribution.cdf(*sample);
+            f64::max((i + 1) as f64 / n - cdf, cdf - i as f64 / n)
+        })
+        .fold(0.0, f64::max)
+}
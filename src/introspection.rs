@@ -0,0 +1,118 @@
+//! Read-only access to model configuration through its serialized form.
+//!
+//! `sim::models::Model` hides the concrete model behind a trait object, but
+//! every model serializes its configuration and state alongside its `id` and
+//! `type`. These helpers read that representation.
+
+use serde_yaml::{Mapping, Value};
+use sim::models::{Model, SerializableModel};
+use sim::simulator::{Connector, Simulation};
+
+use crate::input_modeling::ContinuousDistribution;
+use crate::models::{describe_serialized, Concrete, Description, Ports};
+
+pub(crate) fn serialized(model: &Model) -> Value {
+    serde_yaml::to_value(model).unwrap_or(Value::Null)
+}
+
+pub(crate) fn model_type(value: &Value) -> &str {
+    value.get("type").and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Describes `model`, falling back to the `portsIn`/`portsOut` convention of
+/// `sim`'s built-in models for types this crate does not define. The
+/// distributions of a built-in model are validated with
+/// `builtin_distributions`.
+pub(crate) fn describe(model: &Model) -> Result<Description, String> {
+    let value = serialized(model);
+    describe_serialized(model_type(&value), value.clone()).unwrap_or_else(|| {
+        Ok(Description {
+            ports_in: conventional_ports(&value, "portsIn"),
+            ports_out: conventional_ports(&value, "portsOut"),
+            validation: builtin_distributions(&value),
+        })
+    })
+}
+
+/// Validates the distribution parameters of a built-in model.
+///
+/// `sim`'s models do not validate their `ContinuousRandomVariable`s, so a
+/// field of the model that also reads as a `ContinuousDistribution`, i.e.
+/// the variants both define with the same parameters (`exp`, `normal`,
+/// `logNormal`, `gamma`, `weibull`, `triangular`, ...), is checked with
+/// `ContinuousDistribution::validate`. The reason names the field, e.g.
+/// `serviceTime: exp lambda must be positive, got -1`.
+fn builtin_distributions(value: &Value) -> Result<(), String> {
+    let Some(fields) = value.as_mapping() else {
+        return Ok(());
+    };
+    fields.iter().try_for_each(|(field, value)| {
+        match serde_yaml::from_value::<ContinuousDistribution>(value.clone()) {
+            Ok(distribution) => distribution
+                .validate()
+                .map_err(|reason| format!("{}: {}", field.as_str().unwrap_or_default(), reason)),
+            Err(_) => Ok(()),
+        }
+    })
+}
+
+fn conventional_ports(value: &Value, section: &str) -> Vec<String> {
+    value
+        .get(section)
+        .and_then(Value::as_mapping)
+        .map(|ports| {
+            ports
+                .values()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl Ports for Model {
+    fn ports_in(&self) -> Vec<String> {
+        describe(self)
+            .map(|description| description.ports_in)
+            .unwrap_or_default()
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        describe(self)
+            .map(|description| description.ports_out)
+            .unwrap_or_default()
+    }
+}
+
+impl Concrete for Model {
+    fn as_concrete<T: 'static>(&self) -> Option<&T> {
+        SerializableModel::as_any(self).downcast_ref()
+    }
+}
+
+/// The models of a serialized simulation: `sim` serializes them as a map
+/// from model id to model.
+pub(crate) fn serialized_models(simulation: &Value) -> Option<&Mapping> {
+    simulation.get("models").and_then(Value::as_mapping)
+}
+
+/// `serialized_models`, for editing the models in place.
+pub(crate) fn serialized_models_mut(simulation: &mut Value) -> Option<&mut Mapping> {
+    simulation.get_mut("models").and_then(Value::as_mapping_mut)
+}
+
+/// The connectors of a posted simulation, in the order they were posted.
+pub(crate) fn connectors(simulation: &Simulation) -> Vec<Connector> {
+    serde_yaml::to_value(simulation)
+        .ok()
+        .and_then(|value| value.get("connectors").cloned())
+        .and_then(|connectors| serde_yaml::from_value(connectors).ok())
+        .unwrap_or_default()
+}
+
+/// The models of a posted simulation, sorted by id.
+pub(crate) fn models(simulation: &Simulation) -> Vec<&Model> {
+    let mut models: Vec<&Model> = simulation.get_models().values().collect();
+    models.sort_by(|a, b| a.id().cmp(b.id()));
+    models
+}
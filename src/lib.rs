@@ -0,0 +1,23 @@
+//! Additional models and input modeling built on top of the `sim` discrete
+//! event simulation library.
+//!
+//! Everything here plugs into a regular `sim::simulator::Simulation`: models
+//! implement the same `DevsModel`/`Reportable` traits as the built-in
+//! `Processor` and `Storage`, so they can be mixed freely in one topology.
+
+pub mod builder;
+pub mod checker;
+pub mod config;
+pub mod diagram;
+pub mod diff;
+pub mod event_queue;
+pub mod input_modeling;
+mod introspection;
+pub mod models;
+pub mod payload;
+pub mod replay;
+pub mod replication;
+pub mod report;
+pub mod session;
+pub mod simulation;
+pub mod stats;
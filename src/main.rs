@@ -8,7 +8,12 @@ use sim::models::Reportable;
 use sim::models::{Model, Processor, Storage};
 use sim::report::Report;
 use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::checker::TopologyChecker;
+use sim_dag::diagram::Diagram;
+use sim_dag::report::Report as RecordReport;
+use sim_dag::simulation::SimulationExt;
 use std::io::Write;
+use std::path::PathBuf;
 /// A command-line application to simulate a ping-pong game with N players.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -21,13 +26,25 @@ struct Args {
     #[clap(short, long)]
     end_time: Option<f64>,
 
-    /// Simulation end time
+    /// Maximum number of simulation steps
     #[clap(short, long)]
     iterations: Option<usize>,
 
     /// Generate a diagram of the connected players
     #[clap(long, default_value_t = false)]
     diagram: bool,
+
+    /// Generate the diagram in Mermaid syntax instead of DOT
+    #[clap(long, default_value_t = false)]
+    mermaid: bool,
+
+    /// Seed for the simulation RNG, for reproducible runs
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// Write the collected model records to a CSV file
+    #[clap(long)]
+    csv: Option<PathBuf>,
 }
 
 fn main() {
@@ -101,7 +118,10 @@ fn main() {
         String::from("put"),
     ));
 
-    let mut simulation = Simulation::post(models, connectors);
+    let mut simulation = match args.seed {
+        Some(seed) => Simulation::post_with_seed(models, connectors, seed),
+        None => Simulation::post(models, connectors),
+    };
     info!("Checking simulation configuration...");
     match simulation.check() {
         Ok(_) => info!("Simulation checks complete"),
@@ -110,10 +130,18 @@ fn main() {
             std::process::exit(1); // Exit with an error code
         }
     }
+    if let Err(err) = simulation.check_topology() {
+        error!("Topology check failed: {}", err);
+        std::process::exit(1);
+    }
     if args.diagram {
         info!("Generating Simulation diagram...");
-        let dot_graph = simulation.generate_dot_graph();
-        println!("{}", dot_graph);
+        if args.mermaid {
+            println!("{}", simulation.generate_mermaid_graph());
+        } else {
+            let dot_graph = simulation.generate_port_dot_graph();
+            println!("{}", dot_graph);
+        }
         // You can save this to a file or pipe it to a graphviz tool like dot
     } else {
         info!("Starting simulation...");
@@ -128,21 +156,33 @@ fn main() {
             "Ball".to_string(),
         )];
 
-        initial_messages.iter().for_each(|m| {
-            info!("injecting initial messages: {:?}", m);
-            simulation.inject_input(m.clone())
-        });
-
-        // let msgs= match (args.end_time, args.iterations) {
-        match (args.end_time, args.iterations) {
-            (Some(end_time), _) => simulation.step_until(end_time).unwrap(),
-            (_, Some(iterations)) => simulation.step_n(iterations).unwrap(),
-            (_,_) => panic!("must provide either 'end_time' or 'iterations'.")
-        };
+        info!("injecting initial messages: {:?}", initial_messages);
+        if let Err(err) = simulation.inject_inputs(initial_messages) {
+            error!("Failed to inject initial messages: {}", err);
+            std::process::exit(1);
+        }
+
+        if args.end_time.is_none() && args.iterations.is_none() {
+            error!("Either --end-time or --iterations must be given");
+            std::process::exit(1);
+        }
+        match simulation.step_until_any(args.end_time, args.iterations) {
+            Ok((_, reason)) => info!("Simulation stopped: {:?}", reason),
+            Err(err) => {
+                error!("Simulation failed: {}", err);
+                std::process::exit(1);
+            }
+        }
         // println!("Simulation finished with {} messages", msgs.len());
         
         let storage_model = simulation.get_models().get("Store").unwrap();
-        println!("round-trip count:{}", &storage_model.records().iter().count())
+        println!("round-trip count:{}", &storage_model.records().iter().count());
+        if let Some(path) = &args.csv
+            && let Err(err) = RecordReport::new(&simulation).write_csv_file(path)
+        {
+            error!("Failed to write records to {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
         // println!("{}", serde_json::to_string(records).unwrap());
         //
         // info!("Simulation complete. Messages: {:?}", msgs);
@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// The assembler joins one part from each of its input ports into a single
+/// assembled job. Parts wait on their own input until a part is available
+/// on every input; then the oldest part of each is consumed and the
+/// assembly is emitted at once. The assembled job's content is the list of
+/// the part contents in input order, encoded with `payload::encode`.
+///
+/// Backlogs are unbounded: an input whose parts arrive faster than those of
+/// another keeps growing, which `backlogs` and the status make visible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assembler {
+    inputs: Vec<String>,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    assembly: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    waiting: Vec<VecDeque<String>>,
+    pending: Vec<ModelMessage>,
+    assembled: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Assembler {
+    pub fn new(inputs: Vec<String>, assembly_port: String, store_records: bool) -> Self {
+        let state = State {
+            waiting: vec![VecDeque::new(); inputs.len()],
+            ..State::default()
+        };
+        Self {
+            inputs,
+            ports_out: PortsOut {
+                assembly: assembly_port,
+            },
+            store_records,
+            max_records: None,
+            state,
+        }
+    }
+
+    /// Number of parts waiting on each input port, in input order.
+    pub fn backlogs(&self) -> Vec<(&str, usize)> {
+        self.inputs
+            .iter()
+            .zip(self.state.waiting.iter())
+            .map(|(input, waiting)| (input.as_str(), waiting.len()))
+            .collect()
+    }
+
+    /// Number of assemblies emitted so far.
+    pub fn assembled(&self) -> usize {
+        self.state.assembled
+    }
+}
+
+serializable_model!(Assembler);
+
+impl Validate for Assembler {
+    fn validate(&self) -> Result<(), String> {
+        if self.inputs.is_empty() {
+            return Err(String::from("an assembler needs at least one input"));
+        }
+        if let Some((index, input)) = self
+            .inputs
+            .iter()
+            .enumerate()
+            .find(|(index, input)| self.inputs[..*index].contains(input))
+        {
+            return Err(format!("input {} repeats port '{}'", index, input));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Assembler {
+    fn ports_in(&self) -> Vec<String> {
+        self.inputs.clone()
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.assembly.clone()]
+    }
+}
+
+impl DevsModel for Assembler {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        let index = self
+            .inputs
+            .iter()
+            .position(|input| *input == incoming_message.port_name)
+            .ok_or(SimulationError::PortNotFound)?;
+        let time = services.global_time();
+        // a deserialized assembler may come without state.
+        self.state.waiting.resize(self.inputs.len(), VecDeque::new());
+        self.state.waiting[index].push_back(incoming_message.content.clone());
+        self.record(time, "Arrival", &incoming_message.port_name);
+        if self.state.waiting.iter().all(|waiting| !waiting.is_empty()) {
+            let parts: Vec<String> = self
+                .state
+                .waiting
+                .iter_mut()
+                .filter_map(VecDeque::pop_front)
+                .collect();
+            let content = payload::encode(&parts)?;
+            self.record(time, "Assembly", &content);
+            self.state.assembled += 1;
+            self.state.pending.push(ModelMessage {
+                port_name: self.ports_out.assembly.clone(),
+                content,
+            });
+        }
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Assembler {
+    fn status(&self) -> String {
+        let backlogs: Vec<String> = self
+            .backlogs()
+            .iter()
+            .map(|(input, backlog)| format!("{}: {}", input, backlog))
+            .collect();
+        format!(
+            "Assembled {}, waiting {}",
+            self.state.assembled,
+            backlogs.join(", ")
+        )
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Assembler {}
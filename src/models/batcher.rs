@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// The batcher accumulates arriving jobs and emits them as one batch once
+/// `batch_size` have arrived, then starts over. A batch is a single message
+/// whose content is the list of the batched contents, in arrival order,
+/// encoded with `payload::encode` (decode it as a `Vec<String>`).
+///
+/// With `with_timeout`, a partial batch is flushed once no job has arrived
+/// for that long. Each arrival is recorded as an `Accumulation` whose
+/// subject is the number of jobs now held, e.g. `7/12`; each batch as a
+/// `Batch` (or `Timeout` when flushed early) with the number of jobs in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Batcher {
+    batch_size: usize,
+    #[serde(default)]
+    timeout: Option<f64>,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    batch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    held: Vec<String>,
+    /// Time left until the partial batch is flushed.
+    until_timeout: f64,
+    pending: Vec<ModelMessage>,
+    batches: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            held: Vec::new(),
+            until_timeout: f64::INFINITY,
+            pending: Vec::new(),
+            batches: 0,
+            records: Vec::new(),
+            records_stored: 0,
+        }
+    }
+}
+
+impl Batcher {
+    pub fn new(batch_size: usize, job_port: String, batch_port: String, store_records: bool) -> Self {
+        Self {
+            batch_size,
+            timeout: None,
+            ports_in: PortsIn { job: job_port },
+            ports_out: PortsOut { batch: batch_port },
+            store_records,
+            max_records: None,
+            state: State::default(),
+        }
+    }
+
+    /// Flushes a partial batch after `idle` time units without an arrival.
+    pub fn with_timeout(mut self, idle: f64) -> Self {
+        self.timeout = Some(idle);
+        self
+    }
+
+    /// Number of jobs held for the next batch.
+    pub fn accumulated(&self) -> usize {
+        self.state.held.len()
+    }
+
+    /// Number of batches emitted so far, full or flushed.
+    pub fn batches(&self) -> usize {
+        self.state.batches
+    }
+
+    fn emit_batch(&mut self, time: f64, action: &str) -> Result<(), SimulationError> {
+        let held: Vec<String> = self.state.held.drain(..).collect();
+        self.record(time, action, &held.len().to_string());
+        self.state.pending.push(ModelMessage {
+            port_name: self.ports_out.batch.clone(),
+            content: payload::encode(&held)?,
+        });
+        self.state.batches += 1;
+        self.state.until_timeout = f64::INFINITY;
+        Ok(())
+    }
+}
+
+serializable_model!(Batcher);
+
+impl Validate for Batcher {
+    fn validate(&self) -> Result<(), String> {
+        if self.batch_size == 0 {
+            return Err(String::from("batch size must be at least 1"));
+        }
+        if let Some(timeout) = self.timeout
+            && !(timeout.is_finite() && timeout > 0.0)
+        {
+            return Err(format!("timeout must be positive, got {}", timeout));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Batcher {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.batch.clone()]
+    }
+}
+
+impl DevsModel for Batcher {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        if self.validate().is_err() {
+            return Err(SimulationError::InvalidModelConfiguration);
+        }
+        let time = services.global_time();
+        self.state.held.push(incoming_message.content.clone());
+        let accumulation = format!("{}/{}", self.state.held.len(), self.batch_size);
+        self.record(time, "Accumulation", &accumulation);
+        if self.state.held.len() >= self.batch_size {
+            self.emit_batch(time, "Batch")?;
+        } else {
+            self.state.until_timeout = self.timeout.unwrap_or(f64::INFINITY);
+        }
+        Ok(())
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        if self.state.until_timeout <= 0.0 && !self.state.held.is_empty() {
+            self.emit_batch(services.global_time(), "Timeout")?;
+        }
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        self.state.until_timeout -= time_delta;
+    }
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            self.state.until_timeout
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Batcher {
+    fn status(&self) -> String {
+        format!(
+            "Accumulated {}/{} jobs, {} batches",
+            self.state.held.len(),
+            self.batch_size,
+            self.state.batches
+        )
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Batcher {}
@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// What a `Buffer` does with a put that arrives while it is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Overflow {
+    /// Discard the item and record an `Overflow`.
+    #[default]
+    Reject,
+    /// Hold the put back and store it, in arrival order, as soon as a get
+    /// frees a slot.
+    Block,
+}
+
+/// A storage holding any number of items up to an optional `capacity`. A
+/// message on the put port stores its content; a message on the get port
+/// emits the oldest stored item on the stored port and frees its slot.
+/// Without a capacity the buffer never overflows. `with_initial_contents`
+/// pre-loads items, which gets can retrieve from clock 0.
+///
+/// For puts stamped with a creation time (see `payload::stamp`) the buffer
+/// also tracks the mean time from creation to arrival at the buffer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Buffer {
+    #[serde(default)]
+    capacity: Option<usize>,
+    #[serde(default)]
+    overflow: Overflow,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    put: String,
+    get: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    stored: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    items: VecDeque<String>,
+    blocked: VecDeque<String>,
+    overflows: usize,
+    releasing: Vec<String>,
+    timed_arrivals: usize,
+    sojourn_total: f64,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Buffer {
+    pub fn new(
+        put_port: String,
+        get_port: String,
+        stored_port: String,
+        capacity: Option<usize>,
+        overflow: Overflow,
+        store_records: bool,
+    ) -> Self {
+        Self {
+            capacity,
+            overflow,
+            ports_in: PortsIn {
+                put: put_port,
+                get: get_port,
+            },
+            ports_out: PortsOut {
+                stored: stored_port,
+            },
+            store_records,
+            max_records: None,
+            state: State::default(),
+        }
+    }
+
+    /// Number of items currently stored.
+    pub fn occupancy(&self) -> usize {
+        self.state.items.len()
+    }
+
+    /// Number of puts rejected because the buffer was full.
+    pub fn overflows(&self) -> usize {
+        self.state.overflows
+    }
+
+    /// Number of puts waiting for a free slot.
+    pub fn blocked(&self) -> usize {
+        self.state.blocked.len()
+    }
+
+    /// Mean time from creation to arrival of the puts stamped with a
+    /// creation time, if any have arrived.
+    pub fn mean_sojourn(&self) -> Option<f64> {
+        (self.state.timed_arrivals > 0)
+            .then(|| self.state.sojourn_total / self.state.timed_arrivals as f64)
+    }
+
+    /// Starts the buffer holding `items`, oldest first, as if they had been
+    /// put before the run. Replaces anything stored so far.
+    pub fn with_initial_contents(mut self, items: Vec<String>) -> Self {
+        self.state.items = items.into();
+        self
+    }
+
+    fn is_full(&self) -> bool {
+        self.capacity
+            .is_some_and(|capacity| self.state.items.len() >= capacity)
+    }
+}
+
+serializable_model!(Buffer);
+
+impl Validate for Buffer {
+    fn validate(&self) -> Result<(), String> {
+        if self.capacity == Some(0) {
+            return Err(String::from("a buffer capacity must be at least 1"));
+        }
+        if let Some(capacity) = self.capacity
+            && self.state.items.len() > capacity
+        {
+            return Err(format!(
+                "{} initial items exceed the buffer capacity of {}",
+                self.state.items.len(),
+                capacity
+            ));
+        }
+        if self.ports_in.put == self.ports_in.get {
+            return Err(String::from("put and get ports must differ"));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Buffer {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.put.clone(), self.ports_in.get.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.stored.clone()]
+    }
+}
+
+impl DevsModel for Buffer {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        let time = services.global_time();
+        if incoming_message.port_name == self.ports_in.put {
+            if let Some(created) = payload::creation_time(&incoming_message.content) {
+                self.state.timed_arrivals += 1;
+                self.state.sojourn_total += time - created;
+            }
+            if !self.is_full() {
+                self.state.items.push_back(incoming_message.content.clone());
+                self.record(time, "Put", &incoming_message.content);
+            } else if self.overflow == Overflow::Block {
+                self.state.blocked.push_back(incoming_message.content.clone());
+                self.record(time, "Blocked", &incoming_message.content);
+            } else {
+                self.state.overflows += 1;
+                self.record(time, "Overflow", &incoming_message.content);
+            }
+            Ok(())
+        } else if incoming_message.port_name == self.ports_in.get {
+            if let Some(item) = self.state.items.pop_front() {
+                self.record(time, "Get", &item);
+                self.state.releasing.push(item);
+            }
+            if !self.is_full()
+                && let Some(item) = self.state.blocked.pop_front()
+            {
+                self.record(time, "Put", &item);
+                self.state.items.push_back(item);
+            }
+            Ok(())
+        } else {
+            Err(SimulationError::PortNotFound)
+        }
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let releasing: Vec<String> = self.state.releasing.drain(..).collect();
+        Ok(releasing
+            .into_iter()
+            .map(|content| ModelMessage {
+                port_name: self.ports_out.stored.clone(),
+                content,
+            })
+            .collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.releasing.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Buffer {
+    fn status(&self) -> String {
+        let capacity = self
+            .capacity
+            .map_or(String::from("unbounded"), |capacity| capacity.to_string());
+        let status = format!(
+            "Holding {}/{} items, {} blocked, {} overflows",
+            self.state.items.len(),
+            capacity,
+            self.state.blocked.len(),
+            self.state.overflows
+        );
+        match self.mean_sojourn() {
+            Some(mean) => format!("{}, mean sojourn {:.4}", status, mean),
+            None => status,
+        }
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Buffer {}
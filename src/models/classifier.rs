@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// The classifier forwards each arriving job, unchanged, to the port of the
+/// first rule its `key` satisfies, or to the default port if none does. The
+/// key is looked up with `payload::field`, so jobs can carry it either as a
+/// field of a JSON payload or as a `key=value` pair, e.g.
+/// `priority=high job 3@12.5`. A job without the key goes to the default
+/// port, and so does one whose value is not a number when the rule compares
+/// numbers.
+///
+/// Unlike a `Router` the choice never depends on the RNG.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Classifier {
+    ports_in: PortsIn,
+    key: String,
+    rules: Vec<Rule>,
+    default_port: String,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+/// A test on the value of a `Classifier`'s key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    /// The value is exactly this text.
+    Equals(String),
+    /// The value is a number below this one.
+    LessThan(f64),
+    /// The value is a number above this one.
+    GreaterThan(f64),
+}
+
+impl Condition {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Condition::Equals(expected) => value == expected,
+            Condition::LessThan(bound) => value.parse::<f64>().is_ok_and(|value| value < *bound),
+            Condition::GreaterThan(bound) => {
+                value.parse::<f64>().is_ok_and(|value| value > *bound)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    condition: Condition,
+    port: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    pending: Vec<ModelMessage>,
+    /// Jobs sent by each rule, in rule order.
+    rule_counts: Vec<usize>,
+    unmatched: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Classifier {
+    pub fn new(
+        job_port: String,
+        key: String,
+        rules: Vec<(Condition, String)>,
+        default_port: String,
+        store_records: bool,
+    ) -> Self {
+        let state = State {
+            rule_counts: vec![0; rules.len()],
+            ..State::default()
+        };
+        Self {
+            ports_in: PortsIn { job: job_port },
+            key,
+            rules: rules
+                .into_iter()
+                .map(|(condition, port)| Rule { condition, port })
+                .collect(),
+            default_port,
+            store_records,
+            max_records: None,
+            state,
+        }
+    }
+
+    /// Number of jobs sent by each rule, in rule order, with the rule's port.
+    pub fn rule_counts(&self) -> Vec<(&str, usize)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                (rule.port.as_str(), self.state.rule_counts.get(index).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    /// Number of jobs no rule matched, sent to the default port.
+    pub fn unmatched(&self) -> usize {
+        self.state.unmatched
+    }
+
+    /// The index of the first rule `content` satisfies, if any.
+    fn classify(&self, content: &str) -> Option<usize> {
+        let value = payload::field(content, &self.key)?;
+        self.rules
+            .iter()
+            .position(|rule| rule.condition.matches(&value))
+    }
+}
+
+serializable_model!(Classifier);
+
+impl Validate for Classifier {
+    fn validate(&self) -> Result<(), String> {
+        if self.key.is_empty() {
+            return Err(String::from("a classifier needs a key"));
+        }
+        if let Some((index, _)) = self.rules.iter().enumerate().find(|(_, rule)| {
+            matches!(
+                rule.condition,
+                Condition::LessThan(bound) | Condition::GreaterThan(bound) if !bound.is_finite()
+            )
+        }) {
+            return Err(format!("rule {} compares against a non-finite bound", index));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Classifier {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        let mut ports: Vec<String> = Vec::new();
+        for port in self
+            .rules
+            .iter()
+            .map(|rule| &rule.port)
+            .chain([&self.default_port])
+        {
+            if !ports.contains(port) {
+                ports.push(port.clone());
+            }
+        }
+        ports
+    }
+}
+
+impl DevsModel for Classifier {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        let port = match self.classify(&incoming_message.content) {
+            Some(index) => {
+                // a deserialized classifier may come without state.
+                self.state.rule_counts.resize(self.rules.len(), 0);
+                self.state.rule_counts[index] += 1;
+                self.rules[index].port.clone()
+            }
+            None => {
+                self.state.unmatched += 1;
+                self.default_port.clone()
+            }
+        };
+        self.record(services.global_time(), "Classify", &port);
+        self.state.pending.push(ModelMessage {
+            port_name: port,
+            content: incoming_message.content.clone(),
+        });
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Classifier {
+    fn status(&self) -> String {
+        let counts: Vec<String> = self
+            .rule_counts()
+            .iter()
+            .map(|(port, count)| format!("{}: {}", port, count))
+            .chain([format!("default {}: {}", self.default_port, self.state.unmatched)])
+            .collect();
+        format!("Classified {}", counts.join(", "))
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Classifier {}
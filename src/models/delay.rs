@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::input_modeling::ContinuousDistribution;
+
+/// The delay model holds each arriving job for a duration drawn from
+/// `delay_time`, then emits it unchanged. Jobs leave in arrival order: a job
+/// never overtakes one that entered the delay before it, even when its own
+/// draw is shorter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delay {
+    delay_time: ContinuousDistribution,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::input_modeling::private_rng"
+    )]
+    rng: Option<DynRng>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    delayed_job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    until_next_event: f64,
+    in_transit: VecDeque<Transit>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            until_next_event: f64::INFINITY,
+            in_transit: VecDeque::new(),
+            records: Vec::new(),
+            records_stored: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Transit {
+    remaining: f64,
+    content: String,
+}
+
+impl Delay {
+    pub fn new(
+        delay_time: ContinuousDistribution,
+        job_port: String,
+        delayed_job_port: String,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        Self {
+            delay_time,
+            ports_in: PortsIn { job: job_port },
+            ports_out: PortsOut {
+                delayed_job: delayed_job_port,
+            },
+            store_records,
+            max_records: None,
+            rng,
+            state: State::default(),
+        }
+    }
+
+    /// Number of jobs currently held by the delay.
+    pub fn in_transit(&self) -> usize {
+        self.state.in_transit.len()
+    }
+
+    fn refresh_until_next_event(&mut self) {
+        self.state.until_next_event = self
+            .state
+            .in_transit
+            .front()
+            .map_or(f64::INFINITY, |transit| transit.remaining);
+    }
+}
+
+serializable_model!(Delay);
+
+impl Validate for Delay {
+    fn validate(&self) -> Result<(), String> {
+        self.delay_time.validate()
+    }
+}
+
+impl Ports for Delay {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.delayed_job.clone()]
+    }
+}
+
+impl DevsModel for Delay {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+        let sampled = self.delay_time.random_variate(rng)?;
+        // hold the job at least as long as the one ahead of it to keep FIFO order.
+        let remaining = self
+            .state
+            .in_transit
+            .back()
+            .map_or(sampled, |last| f64::max(sampled, last.remaining));
+        self.state.in_transit.push_back(Transit {
+            remaining,
+            content: incoming_message.content.clone(),
+        });
+        self.refresh_until_next_event();
+        self.record(services.global_time(), "Arrival", &incoming_message.content);
+        Ok(())
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let mut outgoing_messages = Vec::new();
+        while self
+            .state
+            .in_transit
+            .front()
+            .is_some_and(|transit| transit.remaining <= 0.0)
+        {
+            if let Some(transit) = self.state.in_transit.pop_front() {
+                self.record(services.global_time(), "Departure", &transit.content);
+                outgoing_messages.push(ModelMessage {
+                    port_name: self.ports_out.delayed_job.clone(),
+                    content: transit.content,
+                });
+            }
+        }
+        self.refresh_until_next_event();
+        Ok(outgoing_messages)
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        self.state
+            .in_transit
+            .iter_mut()
+            .for_each(|transit| transit.remaining -= time_delta);
+        self.refresh_until_next_event();
+    }
+
+    fn until_next_event(&self) -> f64 {
+        self.state.until_next_event
+    }
+}
+
+impl Reportable for Delay {
+    fn status(&self) -> String {
+        format!("Delaying {} jobs", self.state.in_transit.len())
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Delay {}
@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+
+/// The gate passes jobs through while open and holds them while closed.
+/// Messages with content `open` or `close` on the control port change its
+/// state; opening releases every held job in arrival order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gate {
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+    control: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    job: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    open: bool,
+    held: VecDeque<String>,
+    releasing: Vec<String>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Gate {
+    pub fn new(
+        job_port: String,
+        control_port: String,
+        released_job_port: String,
+        open: bool,
+        store_records: bool,
+    ) -> Self {
+        Self {
+            ports_in: PortsIn {
+                job: job_port,
+                control: control_port,
+            },
+            ports_out: PortsOut {
+                job: released_job_port,
+            },
+            store_records,
+            max_records: None,
+            state: State {
+                open,
+                ..State::default()
+            },
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.open
+    }
+
+    /// Number of jobs waiting for the gate to open.
+    pub fn held(&self) -> usize {
+        self.state.held.len()
+    }
+}
+
+serializable_model!(Gate);
+
+impl Validate for Gate {
+    fn validate(&self) -> Result<(), String> {
+        if self.ports_in.job == self.ports_in.control {
+            return Err(String::from("job and control ports must differ"));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Gate {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone(), self.ports_in.control.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.job.clone()]
+    }
+}
+
+impl DevsModel for Gate {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        let time = services.global_time();
+        if incoming_message.port_name == self.ports_in.job {
+            self.record(time, "Arrival", &incoming_message.content);
+            if self.state.open {
+                self.state.releasing.push(incoming_message.content.clone());
+            } else {
+                self.state.held.push_back(incoming_message.content.clone());
+            }
+            Ok(())
+        } else if incoming_message.port_name == self.ports_in.control {
+            match incoming_message.content.as_str() {
+                "open" => {
+                    self.state.open = true;
+                    self.record(time, "Open", &incoming_message.content);
+                    let held: Vec<String> = self.state.held.drain(..).collect();
+                    self.state.releasing.extend(held);
+                    Ok(())
+                }
+                "close" => {
+                    self.state.open = false;
+                    self.record(time, "Close", &incoming_message.content);
+                    Ok(())
+                }
+                _ => Err(SimulationError::InvalidMessage),
+            }
+        } else {
+            Err(SimulationError::PortNotFound)
+        }
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let releasing: Vec<String> = self.state.releasing.drain(..).collect();
+        Ok(releasing
+            .into_iter()
+            .map(|content| {
+                self.record(services.global_time(), "Release", &content);
+                ModelMessage {
+                    port_name: self.ports_out.job.clone(),
+                    content,
+                }
+            })
+            .collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.releasing.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Gate {
+    fn status(&self) -> String {
+        format!(
+            "{}, holding {} jobs",
+            if self.state.open { "Open" } else { "Closed" },
+            self.state.held.len()
+        )
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Gate {}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::input_modeling::BooleanDistribution;
+
+/// The inspector checks each arriving job and forwards it, unchanged, to
+/// its pass port with probability `p_pass` or to its fail port otherwise.
+/// Each verdict is recorded as a `Pass` or `Fail` with the job content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inspector {
+    p_pass: f64,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::input_modeling::private_rng"
+    )]
+    rng: Option<DynRng>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    pass: String,
+    fail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    pending: Vec<ModelMessage>,
+    passed: usize,
+    failed: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Inspector {
+    pub fn new(
+        p_pass: f64,
+        job_port: String,
+        pass_port: String,
+        fail_port: String,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        Self {
+            p_pass,
+            ports_in: PortsIn { job: job_port },
+            ports_out: PortsOut {
+                pass: pass_port,
+                fail: fail_port,
+            },
+            store_records,
+            max_records: None,
+            rng,
+            state: State::default(),
+        }
+    }
+
+    /// Number of jobs that passed inspection.
+    pub fn passed(&self) -> usize {
+        self.state.passed
+    }
+
+    /// Number of jobs that failed inspection.
+    pub fn failed(&self) -> usize {
+        self.state.failed
+    }
+
+    fn verdict_distribution(&self) -> BooleanDistribution {
+        BooleanDistribution::Bernoulli { p: self.p_pass }
+    }
+}
+
+serializable_model!(Inspector);
+
+impl Validate for Inspector {
+    fn validate(&self) -> Result<(), String> {
+        self.verdict_distribution().validate()?;
+        if self.ports_out.pass == self.ports_out.fail {
+            return Err(String::from("pass and fail ports must differ"));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Inspector {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.pass.clone(), self.ports_out.fail.clone()]
+    }
+}
+
+impl DevsModel for Inspector {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+        let (action, port) = if self.verdict_distribution().random_variate(rng)? {
+            self.state.passed += 1;
+            ("Pass", self.ports_out.pass.clone())
+        } else {
+            self.state.failed += 1;
+            ("Fail", self.ports_out.fail.clone())
+        };
+        self.record(services.global_time(), action, &incoming_message.content);
+        self.state.pending.push(ModelMessage {
+            port_name: port,
+            content: incoming_message.content.clone(),
+        });
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Inspector {
+    fn status(&self) -> String {
+        format!("Passed {}, failed {}", self.state.passed, self.state.failed)
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Inspector {}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+
+/// How a `LoadBalancer` picks an output for each job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Balancing {
+    /// Cycle through the outputs in order.
+    #[default]
+    RoundRobin,
+    /// Join the shortest queue: pick the output with the smallest estimated
+    /// queue, the first such output on ties.
+    ShortestQueue,
+}
+
+/// The load balancer forwards each arriving job, unchanged, to one of its
+/// outputs, chosen by its `Balancing` policy.
+///
+/// The balancer cannot see downstream queues, so it keeps an estimate per
+/// output: every dispatch adds one. A message `<output>=<length>` on the
+/// control port (e.g. `server-a=3`, sent by whatever observes that server)
+/// replaces the estimate for that output with the reported length. Without
+/// feedback, `ShortestQueue` balances the dispatch counts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadBalancer {
+    ports_in: PortsIn,
+    outputs: Vec<String>,
+    #[serde(default)]
+    balancing: Balancing,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+    control: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    pending: Vec<ModelMessage>,
+    next: usize,
+    estimated_queues: Vec<usize>,
+    dispatch_counts: Vec<usize>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl LoadBalancer {
+    pub fn new(
+        job_port: String,
+        control_port: String,
+        outputs: Vec<String>,
+        balancing: Balancing,
+        store_records: bool,
+    ) -> Self {
+        let state = State {
+            estimated_queues: vec![0; outputs.len()],
+            dispatch_counts: vec![0; outputs.len()],
+            ..State::default()
+        };
+        Self {
+            ports_in: PortsIn {
+                job: job_port,
+                control: control_port,
+            },
+            outputs,
+            balancing,
+            store_records,
+            max_records: None,
+            state,
+        }
+    }
+
+    /// Number of jobs dispatched on each output port, in output order.
+    pub fn dispatch_counts(&self) -> Vec<(&str, usize)> {
+        self.outputs
+            .iter()
+            .zip(self.state.dispatch_counts.iter())
+            .map(|(output, count)| (output.as_str(), *count))
+            .collect()
+    }
+
+    fn choose_output(&mut self) -> usize {
+        match self.balancing {
+            Balancing::RoundRobin => {
+                let index = self.state.next % self.outputs.len();
+                self.state.next = (index + 1) % self.outputs.len();
+                index
+            }
+            Balancing::ShortestQueue => self
+                .state
+                .estimated_queues
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, length)| **length)
+                .map_or(0, |(index, _)| index),
+        }
+    }
+
+    fn apply_feedback(&mut self, content: &str) -> Result<(), SimulationError> {
+        let (output, length) = content
+            .split_once('=')
+            .ok_or(SimulationError::InvalidMessage)?;
+        let index = self
+            .outputs
+            .iter()
+            .position(|candidate| candidate == output.trim())
+            .ok_or(SimulationError::InvalidMessage)?;
+        self.state.estimated_queues[index] = length
+            .trim()
+            .parse()
+            .map_err(|_| SimulationError::InvalidMessage)?;
+        Ok(())
+    }
+}
+
+serializable_model!(LoadBalancer);
+
+impl Validate for LoadBalancer {
+    fn validate(&self) -> Result<(), String> {
+        if self.outputs.is_empty() {
+            return Err(String::from("a load balancer needs at least one output"));
+        }
+        if self.ports_in.job == self.ports_in.control {
+            return Err(String::from("job and control ports must differ"));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for LoadBalancer {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone(), self.ports_in.control.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        self.outputs.clone()
+    }
+}
+
+impl DevsModel for LoadBalancer {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        // a deserialized load balancer may come without state.
+        self.state.estimated_queues.resize(self.outputs.len(), 0);
+        self.state.dispatch_counts.resize(self.outputs.len(), 0);
+        if incoming_message.port_name == self.ports_in.control {
+            return self.apply_feedback(&incoming_message.content);
+        }
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        if self.validate().is_err() {
+            return Err(SimulationError::InvalidModelConfiguration);
+        }
+        let index = self.choose_output();
+        let port = self.outputs[index].clone();
+        self.state.estimated_queues[index] += 1;
+        self.state.dispatch_counts[index] += 1;
+        self.record(services.global_time(), "Dispatch", &port);
+        self.state.pending.push(ModelMessage {
+            port_name: port,
+            content: incoming_message.content.clone(),
+        });
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for LoadBalancer {
+    fn status(&self) -> String {
+        let counts: Vec<String> = self
+            .dispatch_counts()
+            .iter()
+            .map(|(output, count)| format!("{}: {}", output, count))
+            .collect();
+        format!("Dispatched {}", counts.join(", "))
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for LoadBalancer {}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+
+/// The merge funnels the jobs arriving on any of its input ports into one
+/// output, unchanged and without delay, so they leave in the order they
+/// arrived. Jobs arriving at the same time leave in delivery order. Unlike
+/// an `Assembler` it never waits for the other inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Merge {
+    inputs: Vec<String>,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    job: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    /// Jobs received by each input, in input order.
+    counts: Vec<usize>,
+    pending: Vec<ModelMessage>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Merge {
+    pub fn new(inputs: Vec<String>, job_port: String, store_records: bool) -> Self {
+        let state = State {
+            counts: vec![0; inputs.len()],
+            ..State::default()
+        };
+        Self {
+            inputs,
+            ports_out: PortsOut { job: job_port },
+            store_records,
+            max_records: None,
+            state,
+        }
+    }
+
+    /// Number of jobs received on each input port, in input order.
+    pub fn counts(&self) -> Vec<(&str, usize)> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                (input.as_str(), self.state.counts.get(index).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    /// Number of jobs received on all inputs together.
+    pub fn merged(&self) -> usize {
+        self.state.counts.iter().sum()
+    }
+}
+
+serializable_model!(Merge);
+
+impl Validate for Merge {
+    fn validate(&self) -> Result<(), String> {
+        if self.inputs.is_empty() {
+            return Err(String::from("a merge needs at least one input"));
+        }
+        if let Some((index, input)) = self
+            .inputs
+            .iter()
+            .enumerate()
+            .find(|(index, input)| self.inputs[..*index].contains(input))
+        {
+            return Err(format!("input {} repeats port '{}'", index, input));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Merge {
+    fn ports_in(&self) -> Vec<String> {
+        self.inputs.clone()
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.job.clone()]
+    }
+}
+
+impl DevsModel for Merge {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        let index = self
+            .inputs
+            .iter()
+            .position(|input| *input == incoming_message.port_name)
+            .ok_or(SimulationError::PortNotFound)?;
+        // a deserialized merge may come without state.
+        self.state.counts.resize(self.inputs.len(), 0);
+        self.state.counts[index] += 1;
+        self.record(services.global_time(), "Arrival", &incoming_message.content);
+        self.state.pending.push(ModelMessage {
+            port_name: self.ports_out.job.clone(),
+            content: incoming_message.content.clone(),
+        });
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Merge {
+    fn status(&self) -> String {
+        let counts: Vec<String> = self
+            .counts()
+            .iter()
+            .map(|(input, count)| format!("{}: {}", input, count))
+            .collect();
+        format!("Merged {}, {}", self.merged(), counts.join(", "))
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Merge {}
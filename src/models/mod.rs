@@ -0,0 +1,270 @@
+//! Models that can be wrapped in a `sim::models::Model` next to the built-in
+//! `Processor`, `Storage` and `Generator`.
+//!
+//! # Records
+//!
+//! Every model keeps all of its records by default, which grows without
+//! bound over a long run. `with_max_records(n)` on any model of this crate
+//! keeps only the last `n` records instead, dropping the oldest as new ones
+//! are stored; `with_max_records(0)` keeps none at all. Counters, running
+//! statistics and the status are kept separately from the records, so they
+//! stay exact either way.
+
+/// Implements `SerializableModel` the same way `sim`'s derive macro does, so
+/// the model shows up in the serialized simulation state under its type name.
+///
+/// Also implements the record keeping every model of this crate shares (see
+/// the module documentation): `with_max_records`, `records_stored` and a
+/// private `record`, which logs the transition at trace level (see
+/// `crate::session`) and stores a record if the model's `store_records` is
+/// set, or always for `serializable_model!(Model, always_records)`. The model
+/// needs `store_records` (unless it always records) and `max_records`
+/// fields, and `records` and `records_stored` in its `state`.
+macro_rules! serializable_model {
+    ($name:ident) => {
+        serializable_model!(@impl $name, |model| model.store_records);
+    };
+    ($name:ident, always_records) => {
+        serializable_model!(@impl $name, |_model| true);
+    };
+    (@impl $name:ident, |$model:ident| $stores_records:expr) => {
+        impl sim::models::SerializableModel for $name {
+            fn get_type(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn serialize(&self) -> serde_yaml::Value {
+                serde_yaml::to_value(self).unwrap_or(serde_yaml::Value::Null)
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        impl $name {
+            /// Keeps only the last `max_records` records; see the module
+            /// documentation of `crate::models`.
+            pub fn with_max_records(mut self, max_records: usize) -> Self {
+                self.max_records = Some(max_records);
+                self
+            }
+
+            /// Number of records stored so far, counting those dropped since
+            /// to keep within `with_max_records`.
+            pub fn records_stored(&self) -> usize {
+                self.state.records_stored
+            }
+
+            fn record(&mut self, time: f64, action: &str, subject: &str) {
+                log::trace!(
+                    "time={} type={} kind={} subject={}",
+                    time,
+                    stringify!($name),
+                    action,
+                    subject
+                );
+                let $model = &*self;
+                if $stores_records {
+                    self.state.records_stored += 1;
+                    $crate::models::push_record(
+                        &mut self.state.records,
+                        self.max_records,
+                        sim::models::ModelRecord {
+                            time,
+                            action: action.to_string(),
+                            subject: subject.to_string(),
+                        },
+                    );
+                }
+            }
+        }
+    };
+}
+
+pub mod assembler;
+pub mod batcher;
+pub mod buffer;
+pub mod classifier;
+pub mod delay;
+pub mod gate;
+pub mod inspector;
+pub mod load_balancer;
+pub mod merge;
+pub mod router;
+pub mod sink;
+pub mod source;
+pub mod splitter;
+pub mod station;
+pub mod throttle;
+
+pub use self::assembler::Assembler;
+pub use self::batcher::Batcher;
+pub use self::buffer::{Buffer, Overflow};
+pub use self::classifier::{Classifier, Condition};
+pub use self::delay::Delay;
+pub use self::gate::Gate;
+pub use self::inspector::Inspector;
+pub use self::load_balancer::{Balancing, LoadBalancer};
+pub use self::merge::Merge;
+pub use self::router::Router;
+pub use self::sink::Sink;
+pub use self::source::{Arrivals, Source};
+pub use self::splitter::Splitter;
+pub use self::station::{Breakdowns, Priority, Station};
+pub use self::throttle::Throttle;
+
+use std::sync::Once;
+
+use serde::de::DeserializeOwned;
+use sim::models::ReportableModel;
+use sim::models::model_factory;
+
+/// Configuration checks for a model, run by `crate::checker` before the
+/// simulation is stepped.
+pub trait Validate {
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// The port names a model accepts messages on and emits messages from.
+///
+/// `Ports` is also implemented for `sim::models::Model`. For `sim`'s built-in
+/// models the ports are read from the `portsIn`/`portsOut` sections of the
+/// serialized model, the convention all of them follow.
+pub trait Ports {
+    fn ports_in(&self) -> Vec<String>;
+    fn ports_out(&self) -> Vec<String>;
+}
+
+/// Typed access to the model behind a `sim::models::Model`, for inherent
+/// accessors such as `Buffer::occupancy` or `Station::mean_queue_length`.
+///
+/// `as_concrete` downcasts the model's `SerializableModel::as_any`, which
+/// `serializable_model!` implements for every model of this crate.
+pub trait Concrete {
+    /// The model if it is a `T`, `None` if it is of another type.
+    fn as_concrete<T: 'static>(&self) -> Option<&T>;
+}
+
+/// Appends `record` to `records`, first dropping the oldest records so
+/// that no more than `max_records` are kept.
+pub(crate) fn push_record<T>(records: &mut Vec<T>, max_records: Option<usize>, record: T) {
+    match max_records {
+        Some(0) => {}
+        Some(max_records) => {
+            if records.len() >= max_records {
+                records.drain(..=records.len() - max_records);
+            }
+            records.push(record);
+        }
+        None => records.push(record),
+    }
+}
+
+/// Number of records `model` has stored so far. The built-in models of `sim`
+/// never drop records, so for them it is the number they hold.
+pub(crate) fn records_stored(model: &sim::models::Model) -> usize {
+    macro_rules! stored {
+        ($($name:ident),*) => {
+            $(if let Some(model) = model.as_concrete::<$name>() {
+                return model.records_stored();
+            })*
+        };
+    }
+    stored!(
+        Assembler,
+        Batcher,
+        Buffer,
+        Classifier,
+        Delay,
+        Gate,
+        Inspector,
+        LoadBalancer,
+        Merge,
+        Router,
+        Sink,
+        Source,
+        Splitter,
+        Station,
+        Throttle
+    );
+    model.records().len()
+}
+
+/// What the checker needs to know about a model of this crate.
+pub(crate) struct Description {
+    pub(crate) ports_in: Vec<String>,
+    pub(crate) ports_out: Vec<String>,
+    pub(crate) validation: Result<(), String>,
+}
+
+/// Describes the serialized form of one of this crate's models. Returns
+/// `None` when `model_type` is not defined here, e.g. for `sim`'s built-ins.
+pub(crate) fn describe_serialized(
+    model_type: &str,
+    value: serde_yaml::Value,
+) -> Option<Result<Description, String>> {
+    fn describe<T: Validate + Ports + DeserializeOwned>(
+        value: serde_yaml::Value,
+    ) -> Result<Description, String> {
+        let model = serde_yaml::from_value::<T>(value).map_err(|err| err.to_string())?;
+        Ok(Description {
+            ports_in: model.ports_in(),
+            ports_out: model.ports_out(),
+            validation: model.validate(),
+        })
+    }
+    match model_type {
+        "Assembler" => Some(describe::<Assembler>(value)),
+        "Batcher" => Some(describe::<Batcher>(value)),
+        "Buffer" => Some(describe::<Buffer>(value)),
+        "Classifier" => Some(describe::<Classifier>(value)),
+        "Delay" => Some(describe::<Delay>(value)),
+        "Gate" => Some(describe::<Gate>(value)),
+        "Inspector" => Some(describe::<Inspector>(value)),
+        "LoadBalancer" => Some(describe::<LoadBalancer>(value)),
+        "Merge" => Some(describe::<Merge>(value)),
+        "Router" => Some(describe::<Router>(value)),
+        "Sink" => Some(describe::<Sink>(value)),
+        "Source" => Some(describe::<Source>(value)),
+        "Splitter" => Some(describe::<Splitter>(value)),
+        "Station" => Some(describe::<Station>(value)),
+        "Throttle" => Some(describe::<Throttle>(value)),
+        _ => None,
+    }
+}
+
+/// Registers the models of this crate with `sim`'s model factory, so that a
+/// serialized simulation containing them can be deserialized again, e.g.
+/// when a model is added to a running simulation. Safe to call repeatedly.
+///
+/// A private RNG serializes as its `RngState`, so a rebuilt model carries on
+/// with its stream, unless `RngState::capture` cannot read the generator: the
+/// rebuilt model then draws from the simulation's global RNG.
+pub fn register_models() {
+    fn construct<T: ReportableModel + DeserializeOwned + 'static>(
+        value: serde_yaml::Value,
+    ) -> Option<Box<dyn ReportableModel>> {
+        serde_yaml::from_value::<T>(value)
+            .ok()
+            .map(|model| Box::new(model) as Box<dyn ReportableModel>)
+    }
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        model_factory::register("Assembler", construct::<Assembler>);
+        model_factory::register("Batcher", construct::<Batcher>);
+        model_factory::register("Buffer", construct::<Buffer>);
+        model_factory::register("Classifier", construct::<Classifier>);
+        model_factory::register("Delay", construct::<Delay>);
+        model_factory::register("Gate", construct::<Gate>);
+        model_factory::register("Inspector", construct::<Inspector>);
+        model_factory::register("LoadBalancer", construct::<LoadBalancer>);
+        model_factory::register("Merge", construct::<Merge>);
+        model_factory::register("Router", construct::<Router>);
+        model_factory::register("Sink", construct::<Sink>);
+        model_factory::register("Source", construct::<Source>);
+        model_factory::register("Splitter", construct::<Splitter>);
+        model_factory::register("Station", construct::<Station>);
+        model_factory::register("Throttle", construct::<Throttle>);
+    });
+}
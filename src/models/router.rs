@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::input_modeling::IndexDistribution;
+
+/// The router forwards each arriving job, unchanged, to one of its output
+/// ports. The port is drawn at random with probability proportional to its
+/// weight; weights do not need to sum to one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Router {
+    ports_in: PortsIn,
+    routes: Vec<Route>,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::input_modeling::private_rng"
+    )]
+    rng: Option<DynRng>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Route {
+    port: String,
+    weight: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    pending: Vec<ModelMessage>,
+    dispatch_counts: Vec<usize>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Router {
+    pub fn new(
+        job_port: String,
+        routes: Vec<(String, f64)>,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        let state = State {
+            dispatch_counts: vec![0; routes.len()],
+            ..State::default()
+        };
+        Self {
+            ports_in: PortsIn { job: job_port },
+            routes: routes
+                .into_iter()
+                .map(|(port, weight)| Route { port, weight })
+                .collect(),
+            store_records,
+            max_records: None,
+            rng,
+            state,
+        }
+    }
+
+    /// Number of jobs dispatched on each output port, in route order.
+    pub fn dispatch_counts(&self) -> Vec<(&str, usize)> {
+        self.routes
+            .iter()
+            .zip(self.state.dispatch_counts.iter())
+            .map(|(route, count)| (route.port.as_str(), *count))
+            .collect()
+    }
+
+    fn route_distribution(&self) -> IndexDistribution {
+        IndexDistribution::WeightedIndex {
+            weights: self.routes.iter().map(|route| route.weight).collect(),
+        }
+    }
+}
+
+serializable_model!(Router);
+
+impl Validate for Router {
+    fn validate(&self) -> Result<(), String> {
+        if self.routes.is_empty() {
+            return Err(String::from("a router needs at least one route"));
+        }
+        if let Some(route) = self
+            .routes
+            .iter()
+            .find(|route| !route.weight.is_finite() || route.weight < 0.0)
+        {
+            return Err(format!(
+                "route '{}' has invalid weight {}",
+                route.port, route.weight
+            ));
+        }
+        if self.routes.iter().map(|route| route.weight).sum::<f64>() <= 0.0 {
+            return Err(String::from("route weights must not all be zero"));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Router {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        self.routes.iter().map(|route| route.port.clone()).collect()
+    }
+}
+
+impl DevsModel for Router {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+        let index = self.route_distribution().random_variate(rng)?;
+        let port = self.routes[index].port.clone();
+        self.state.dispatch_counts[index] += 1;
+        self.record(services.global_time(), "Dispatch", &port);
+        self.state.pending.push(ModelMessage {
+            port_name: port,
+            content: incoming_message.content.clone(),
+        });
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(self.state.pending.drain(..).collect())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.pending.is_empty() {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Router {
+    fn status(&self) -> String {
+        let counts: Vec<String> = self
+            .dispatch_counts()
+            .iter()
+            .map(|(port, count)| format!("{}: {}", port, count))
+            .collect();
+        format!("Dispatched {}", counts.join(", "))
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Router {}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// The sink absorbs every job it receives, recording an `Arrival` with the
+/// arrival time and the job content. It never emits anything.
+///
+/// With `interarrival_stats` it also keeps a running mean and variance of
+/// the time between consecutive arrivals, so long runs need not scan the
+/// records.
+///
+/// Jobs stamped with a creation time (see `payload::stamp`), such as those
+/// from a `Source`, also count towards the mean sojourn time: the time from
+/// creation to arrival at the sink. Unstamped jobs, like a manually injected
+/// `Ball`, are absorbed without counting towards it.
+///
+/// A sink can collect from several sources on distinct ports, given with
+/// `with_additional_ports`. All counts and statistics are over every port
+/// together; `arrivals_on` counts per port and `arrival_ports` tells which
+/// port each record arrived on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sink {
+    ports_in: PortsIn,
+    #[serde(default)]
+    interarrival_stats: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+    #[serde(default)]
+    additional: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    arrivals: usize,
+    last_arrival: Option<f64>,
+    /// Running mean and sum of squared deviations of the interarrival times
+    /// (Welford's method).
+    interarrival_mean: f64,
+    interarrival_m2: f64,
+    timed_arrivals: usize,
+    sojourn_total: f64,
+    #[serde(default)]
+    port_arrivals: BTreeMap<String, usize>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+    /// The port of each record; only kept with additional ports.
+    #[serde(default)]
+    record_ports: Vec<String>,
+}
+
+impl Sink {
+    pub fn new(job_port: String, interarrival_stats: bool) -> Self {
+        Self {
+            ports_in: PortsIn {
+                job: job_port,
+                additional: Vec::new(),
+            },
+            interarrival_stats,
+            max_records: None,
+            state: State::default(),
+        }
+    }
+
+    /// Also accepts jobs on each of `ports`, besides the job port.
+    pub fn with_additional_ports(mut self, ports: Vec<String>) -> Self {
+        self.ports_in.additional = ports;
+        self
+    }
+
+    /// Number of jobs absorbed so far, on all ports.
+    pub fn arrivals(&self) -> usize {
+        self.state.arrivals
+    }
+
+    /// Number of jobs absorbed so far on `port`.
+    pub fn arrivals_on(&self, port: &str) -> usize {
+        self.state.port_arrivals.get(port).copied().unwrap_or(0)
+    }
+
+    /// The port each of the `records` arrived on, in the same order. Empty
+    /// for a sink without additional ports, where it is always the job port.
+    pub fn arrival_ports(&self) -> &[String] {
+        &self.state.record_ports
+    }
+
+    /// Mean time between consecutive arrivals, if interarrival statistics are
+    /// enabled and at least two jobs have arrived.
+    pub fn mean_interarrival(&self) -> Option<f64> {
+        (self.interarrival_stats && self.state.arrivals >= 2).then_some(self.state.interarrival_mean)
+    }
+
+    /// Sample standard deviation of the interarrival times, if interarrival
+    /// statistics are enabled and at least three jobs have arrived.
+    pub fn interarrival_std_dev(&self) -> Option<f64> {
+        let intervals = self.state.arrivals.saturating_sub(1);
+        (self.interarrival_stats && intervals >= 2)
+            .then(|| (self.state.interarrival_m2 / (intervals - 1) as f64).sqrt())
+    }
+
+    /// Mean time from creation to arrival of the jobs stamped with a creation
+    /// time, if any have arrived.
+    pub fn mean_sojourn(&self) -> Option<f64> {
+        (self.state.timed_arrivals > 0)
+            .then(|| self.state.sojourn_total / self.state.timed_arrivals as f64)
+    }
+}
+
+serializable_model!(Sink, always_records);
+
+impl Validate for Sink {
+    fn validate(&self) -> Result<(), String> {
+        let ports = self.ports_in();
+        if let Some(port) = ports
+            .iter()
+            .enumerate()
+            .find_map(|(index, port)| ports[..index].contains(port).then_some(port))
+        {
+            return Err(format!("sink port {} is listed twice", port));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Sink {
+    fn ports_in(&self) -> Vec<String> {
+        std::iter::once(self.ports_in.job.clone())
+            .chain(self.ports_in.additional.iter().cloned())
+            .collect()
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl DevsModel for Sink {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        let port = &incoming_message.port_name;
+        if *port != self.ports_in.job && !self.ports_in.additional.contains(port) {
+            return Err(SimulationError::PortNotFound);
+        }
+        let time = services.global_time();
+        self.state.arrivals += 1;
+        *self.state.port_arrivals.entry(port.clone()).or_default() += 1;
+        if self.interarrival_stats
+            && let Some(last_arrival) = self.state.last_arrival
+        {
+            let interarrival = time - last_arrival;
+            let intervals = (self.state.arrivals - 1) as f64;
+            let delta = interarrival - self.state.interarrival_mean;
+            self.state.interarrival_mean += delta / intervals;
+            self.state.interarrival_m2 += delta * (interarrival - self.state.interarrival_mean);
+        }
+        self.state.last_arrival = Some(time);
+        if let Some(created) = payload::creation_time(&incoming_message.content) {
+            self.state.timed_arrivals += 1;
+            self.state.sojourn_total += time - created;
+        }
+        self.record(time, "Arrival", &incoming_message.content);
+        if !self.ports_in.additional.is_empty() {
+            super::push_record(&mut self.state.record_ports, self.max_records, port.clone());
+        }
+        Ok(())
+    }
+
+    fn events_int(&mut self, _services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        Ok(Vec::new())
+    }
+
+    fn time_advance(&mut self, _time_delta: f64) {}
+
+    fn until_next_event(&self) -> f64 {
+        f64::INFINITY
+    }
+}
+
+impl Reportable for Sink {
+    fn status(&self) -> String {
+        let mut status = format!("Absorbed {} jobs", self.state.arrivals);
+        if let Some(mean) = self.mean_sojourn() {
+            status.push_str(&format!(", mean sojourn {:.4}", mean));
+        }
+        if let Some(mean) = self.mean_interarrival() {
+            status.push_str(&format!(", mean interarrival {:.4}", mean));
+        }
+        status
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Sink {}
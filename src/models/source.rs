@@ -0,0 +1,348 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::input_modeling::ContinuousDistribution;
+use crate::payload;
+
+/// The source emits a job at time zero and then after every draw from
+/// `interarrival_time`. With a `max_count` it goes quiet once that many jobs
+/// have been emitted, letting the rest of the simulation drain.
+///
+/// A source built with `from_schedule` instead emits one job at each listed
+/// time and then goes quiet, e.g. to replay recorded arrivals, and one
+/// built from `Arrivals::Periodic` emits near-periodic jobs, every period
+/// give or take a jitter.
+///
+/// Every job is stamped with its creation time (see `payload::stamp`), e.g.
+/// `{"content":"job 3","createdAt":12.5}`, so that a `Sink` or `Buffer`
+/// downstream can measure how long it spent in the system. `with_payload`
+/// replaces the `job 3` content with a typed payload, which
+/// `payload::decode` reads back from the stamped job.
+///
+/// `with_backpressure` lets a downstream `Station` hold the source back (see
+/// `Station::with_backpressure`): after a `busy` on the backpressure port,
+/// jobs keep being generated on schedule but wait in order, and each
+/// `ready` releases the oldest waiting one. Newly generated jobs queue
+/// behind any still waiting, so jobs always leave in generation order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Source {
+    #[serde(flatten)]
+    arrivals: Arrivals,
+    #[serde(default)]
+    max_count: Option<usize>,
+    #[serde(default)]
+    backpressure_port: Option<String>,
+    #[serde(default)]
+    payload: Option<String>,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::input_modeling::private_rng"
+    )]
+    rng: Option<DynRng>,
+    #[serde(default)]
+    state: State,
+}
+
+/// When a `Source` emits jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Arrivals {
+    /// At time zero and then after every draw from the distribution.
+    InterarrivalTime(ContinuousDistribution),
+    /// At each of these times, which must be ascending and non-negative.
+    Schedule(Vec<f64>),
+    /// At time zero and then every `period` plus a draw from `jitter`, for
+    /// near-periodic traffic. A draw that would make the interarrival time
+    /// negative is clamped so that the next job comes no earlier than the
+    /// last one.
+    Periodic {
+        period: f64,
+        jitter: ContinuousDistribution,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    job: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    until_next_event: f64,
+    generated: usize,
+    /// Whether a `busy` has been received since the last `ready`.
+    #[serde(default)]
+    held: bool,
+    /// Jobs generated but held back, oldest first.
+    #[serde(default)]
+    waiting: VecDeque<String>,
+    /// Waiting jobs released by a `ready` but not sent yet.
+    #[serde(default)]
+    releasing: Vec<String>,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Source {
+    pub fn new(
+        interarrival_time: ContinuousDistribution,
+        max_count: Option<usize>,
+        job_port: String,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        Self::from_arrivals(
+            Arrivals::InterarrivalTime(interarrival_time),
+            max_count,
+            job_port,
+            store_records,
+            rng,
+        )
+    }
+
+    /// A source emitting one job at each of `times`, in ascending order.
+    pub fn from_schedule(times: Vec<f64>, job_port: String, store_records: bool) -> Self {
+        Self::from_arrivals(Arrivals::Schedule(times), None, job_port, store_records, None)
+    }
+
+    pub fn from_arrivals(
+        arrivals: Arrivals,
+        max_count: Option<usize>,
+        job_port: String,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        let until_next_event = match &arrivals {
+            Arrivals::Schedule(times) => times.first().copied().unwrap_or(f64::INFINITY),
+            Arrivals::InterarrivalTime(_) | Arrivals::Periodic { .. } => 0.0,
+        };
+        Self {
+            arrivals,
+            max_count,
+            backpressure_port: None,
+            payload: None,
+            ports_out: PortsOut { job: job_port },
+            store_records,
+            max_records: None,
+            rng,
+            state: State {
+                until_next_event,
+                ..State::default()
+            },
+        }
+    }
+
+    /// Number of jobs generated so far, including any still waiting.
+    pub fn generated(&self) -> usize {
+        self.state.generated
+    }
+
+    /// Holds jobs back while a downstream model signals `busy` on `port`;
+    /// see `Source`.
+    pub fn with_backpressure(mut self, port: String) -> Self {
+        self.backpressure_port = Some(port);
+        self
+    }
+
+    /// Emits `payload`, encoded with `payload::encode`, as the content of
+    /// every job instead of numbering them.
+    pub fn with_payload<T: Serialize>(mut self, payload: &T) -> Result<Self, SimulationError> {
+        self.payload = Some(payload::encode(payload)?);
+        Ok(self)
+    }
+
+    /// Whether the source is holding jobs back.
+    pub fn is_held(&self) -> bool {
+        self.state.held
+    }
+
+    /// Number of generated jobs waiting to be sent.
+    pub fn waiting(&self) -> usize {
+        self.state.waiting.len() + self.state.releasing.len()
+    }
+
+    fn exhausted(&self) -> bool {
+        let scheduled = match &self.arrivals {
+            Arrivals::Schedule(times) => Some(times.len()),
+            Arrivals::InterarrivalTime(_) | Arrivals::Periodic { .. } => None,
+        };
+        [self.max_count, scheduled]
+            .into_iter()
+            .flatten()
+            .any(|count| self.state.generated >= count)
+    }
+
+    /// Sends `content` now, or queues it behind the waiting jobs.
+    fn send_or_hold(&mut self, content: String) -> Vec<ModelMessage> {
+        if !self.state.held && self.state.waiting.is_empty() {
+            return vec![ModelMessage {
+                port_name: self.ports_out.job.clone(),
+                content,
+            }];
+        }
+        self.state.waiting.push_back(content);
+        if self.state.held {
+            return Vec::new();
+        }
+        self.state
+            .waiting
+            .pop_front()
+            .map(|content| ModelMessage {
+                port_name: self.ports_out.job.clone(),
+                content,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+serializable_model!(Source);
+
+impl Validate for Source {
+    fn validate(&self) -> Result<(), String> {
+        match &self.arrivals {
+            Arrivals::InterarrivalTime(interarrival_time) => interarrival_time.validate(),
+            Arrivals::Schedule(times) => {
+                if times.iter().any(|time| !time.is_finite() || *time < 0.0) {
+                    return Err(String::from("scheduled times must be non-negative"));
+                }
+                if !times.is_sorted() {
+                    return Err(String::from("scheduled times must be sorted ascending"));
+                }
+                Ok(())
+            }
+            Arrivals::Periodic { period, jitter } => {
+                if !(period.is_finite() && *period > 0.0) {
+                    return Err(format!("period must be positive, got {}", period));
+                }
+                jitter.validate()
+            }
+        }
+    }
+}
+
+impl Ports for Source {
+    fn ports_in(&self) -> Vec<String> {
+        self.backpressure_port.iter().cloned().collect()
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.job.clone()]
+    }
+}
+
+impl DevsModel for Source {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if self.backpressure_port.as_ref() != Some(&incoming_message.port_name) {
+            return Err(SimulationError::PortNotFound);
+        }
+        let time = services.global_time();
+        match incoming_message.content.as_str() {
+            "busy" => {
+                self.state.held = true;
+                self.record(time, "Hold", &incoming_message.content);
+                Ok(())
+            }
+            "ready" => {
+                self.state.held = false;
+                self.record(time, "Resume", &incoming_message.content);
+                self.state.releasing.extend(self.state.waiting.pop_front());
+                Ok(())
+            }
+            _ => Err(SimulationError::InvalidMessage),
+        }
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        if !self.state.releasing.is_empty() {
+            return Ok(self
+                .state
+                .releasing
+                .drain(..)
+                .map(|content| ModelMessage {
+                    port_name: self.ports_out.job.clone(),
+                    content,
+                })
+                .collect());
+        }
+        if self.exhausted() {
+            self.state.until_next_event = f64::INFINITY;
+            return Ok(Vec::new());
+        }
+        self.state.generated += 1;
+        let content = match &self.payload {
+            Some(content) => payload::stamp(content, services.global_time()),
+            None => payload::stamp(
+                &format!("{} {}", self.ports_out.job, self.state.generated),
+                services.global_time(),
+            ),
+        };
+        self.record(services.global_time(), "Generation", &content);
+        self.state.until_next_event = match &self.arrivals {
+            _ if self.exhausted() => f64::INFINITY,
+            Arrivals::InterarrivalTime(interarrival_time) => {
+                let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+                interarrival_time.random_variate(rng)?
+            }
+            Arrivals::Schedule(times) => {
+                times[self.state.generated] - times[self.state.generated - 1]
+            }
+            Arrivals::Periodic { period, jitter } => {
+                let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+                f64::max(period + jitter.random_variate(rng)?, 0.0)
+            }
+        };
+        Ok(self.send_or_hold(content))
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        self.state.until_next_event -= time_delta;
+    }
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.releasing.is_empty() {
+            self.state.until_next_event
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Reportable for Source {
+    fn status(&self) -> String {
+        let status = match self.max_count {
+            Some(max_count) => format!("Generated {}/{} jobs", self.state.generated, max_count),
+            None => format!("Generated {} jobs", self.state.generated),
+        };
+        match self.backpressure_port {
+            Some(_) => format!("{}, {} waiting", status, self.waiting()),
+            None => status,
+        }
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Source {}
@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::payload;
+
+/// The splitter expands each arriving batch into its individual jobs. A
+/// batch is either a list of contents, as emitted by a `Batcher`, which are
+/// emitted as they are, or a count `n`, which emits `n` new jobs named like
+/// a `Source` would (`job 1`, `job 2`, ... after the output port). An empty
+/// batch emits nothing.
+///
+/// By default every job of a batch leaves at once. `with_spacing` emits
+/// them one at a time instead, the given time apart, with jobs of later
+/// batches queued behind those of earlier ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Splitter {
+    #[serde(default)]
+    spacing: Option<f64>,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    batch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    until_next_event: f64,
+    waiting: VecDeque<String>,
+    created: usize,
+    emitted: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            until_next_event: f64::INFINITY,
+            waiting: VecDeque::new(),
+            created: 0,
+            emitted: 0,
+            records: Vec::new(),
+            records_stored: 0,
+        }
+    }
+}
+
+impl Splitter {
+    pub fn new(batch_port: String, job_port: String, store_records: bool) -> Self {
+        Self {
+            spacing: None,
+            ports_in: PortsIn { batch: batch_port },
+            ports_out: PortsOut { job: job_port },
+            store_records,
+            max_records: None,
+            state: State::default(),
+        }
+    }
+
+    /// Emits the jobs of a batch one at a time, `spacing` time units apart.
+    pub fn with_spacing(mut self, spacing: f64) -> Self {
+        self.spacing = Some(spacing);
+        self
+    }
+
+    /// Number of jobs emitted so far.
+    pub fn emitted(&self) -> usize {
+        self.state.emitted
+    }
+
+    fn jobs(&mut self, content: &str) -> Result<Vec<String>, SimulationError> {
+        if let Ok(contents) = payload::decode::<Vec<String>>(content) {
+            return Ok(contents);
+        }
+        let count: usize = payload::decode(content)?;
+        Ok((0..count)
+            .map(|_| {
+                self.state.created += 1;
+                format!("{} {}", self.ports_out.job, self.state.created)
+            })
+            .collect())
+    }
+
+    fn emit(&mut self, time: f64, content: String) -> ModelMessage {
+        self.state.emitted += 1;
+        self.record(time, "Emission", &content);
+        ModelMessage {
+            port_name: self.ports_out.job.clone(),
+            content,
+        }
+    }
+}
+
+serializable_model!(Splitter);
+
+impl Validate for Splitter {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(spacing) = self.spacing
+            && !(spacing.is_finite() && spacing >= 0.0)
+        {
+            return Err(format!("spacing must be non-negative, got {}", spacing));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Splitter {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.batch.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.job.clone()]
+    }
+}
+
+impl DevsModel for Splitter {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.batch {
+            return Err(SimulationError::PortNotFound);
+        }
+        let jobs = self.jobs(&incoming_message.content)?;
+        self.record(services.global_time(), "Split", &jobs.len().to_string());
+        self.state.waiting.extend(jobs);
+        if self.state.until_next_event == f64::INFINITY && !self.state.waiting.is_empty() {
+            self.state.until_next_event = 0.0;
+        }
+        Ok(())
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let time = services.global_time();
+        let released: Vec<String> = match self.spacing {
+            Some(_) => self.state.waiting.pop_front().into_iter().collect(),
+            None => self.state.waiting.drain(..).collect(),
+        };
+        let messages = released
+            .into_iter()
+            .map(|content| self.emit(time, content))
+            .collect();
+        self.state.until_next_event = match self.spacing {
+            Some(spacing) if !self.state.waiting.is_empty() => spacing,
+            _ => f64::INFINITY,
+        };
+        Ok(messages)
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        self.state.until_next_event -= time_delta;
+    }
+
+    fn until_next_event(&self) -> f64 {
+        self.state.until_next_event
+    }
+}
+
+impl Reportable for Splitter {
+    fn status(&self) -> String {
+        format!(
+            "Emitted {} jobs, {} waiting",
+            self.state.emitted,
+            self.state.waiting.len()
+        )
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Splitter {}
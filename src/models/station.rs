@@ -0,0 +1,797 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::input_modeling::dynamic_rng::DynRng;
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+use crate::input_modeling::ContinuousDistribution;
+use crate::payload;
+
+/// A processor with `servers` identical servers sharing one FIFO queue. Each
+/// job gets an independent service time draw; jobs queue when every server
+/// is busy. With a `queue_capacity`, a job arriving to a full queue is
+/// dropped and recorded as a `Drop`, unless the station signals backpressure
+/// (see below); without one the queue is unbounded.
+///
+/// Utilization and the time-average queue length are integrated over the
+/// time after the warm-up period set with `with_warmup`, if any.
+///
+/// With `store_records`, the service time of every completed job is kept
+/// too, pooled over the servers, and is available from `service_times`.
+///
+/// The distribution of the queue length is kept as two histograms, also
+/// after the warm-up only: `queue_length_counts` per arriving job and
+/// `queue_length_fractions` over time.
+///
+/// By default jobs are served first come, first served. `with_priority`
+/// orders the queue by job priority instead (see `job_priority`), and can
+/// let a higher-priority arrival preempt a lower-priority job in service.
+///
+/// `with_setup_time` adds a changeover before service whenever a job's type
+/// (see `job_type`) differs from that of the last typed job to start
+/// service. The first typed job, untyped jobs and jobs resuming after a
+/// preemption start without one. Setups are recorded as `Setup` and their
+/// total is reported by `total_setup_time` and the status.
+///
+/// `with_backpressure` adds flow control for an upstream `Source` (see
+/// `Source::with_backpressure`), so that jobs are held back instead of
+/// dropped: the station sends `busy` on the backpressure port once every
+/// server is busy and its queue is at capacity, in the internal transition
+/// of the very step the queue fills, and `ready` once the queue is below
+/// capacity again. Jobs that arrive at a full station before the sender saw
+/// `busy`, such as jobs sent at the same instant, were already on their way
+/// and join the queue past its capacity instead of being dropped. Only a
+/// station with a `queue_capacity` ever signals, and the handshake assumes a
+/// single upstream sender.
+///
+/// `with_breakdowns` makes the whole station fail after a draw from the
+/// time to failure, counted from the start of the run or the last repair
+/// whether or not it is serving, and stay down for a draw from the time to
+/// repair. While it is down, jobs in service are held with their remaining
+/// service time, which resumes after the repair, and arrivals queue (or are
+/// dropped) as if every server were busy. Failures and repairs are recorded
+/// as `Failure` and `Repair`; see `failures`, `uptime`, `downtime` and
+/// `availability`. Time down does not count as busy in `utilization`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Station {
+    service_time: ContinuousDistribution,
+    #[serde(default)]
+    queue_capacity: Option<usize>,
+    servers: usize,
+    #[serde(default)]
+    warmup: f64,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    setup_time: Option<ContinuousDistribution>,
+    #[serde(default)]
+    breakdowns: Option<Breakdowns>,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::input_modeling::private_rng"
+    )]
+    rng: Option<DynRng>,
+    #[serde(default)]
+    state: State,
+}
+
+/// How a `Station` picks the next job to serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Priority {
+    /// First come, first served; job priorities are ignored.
+    #[default]
+    Fifo,
+    /// Higher-priority jobs are served first, first come, first served
+    /// within a priority. Jobs in service are never interrupted.
+    NonPreemptive,
+    /// Like `NonPreemptive`, but a job arriving while every server is busy
+    /// preempts the lowest-priority job in service if that job has a lower
+    /// priority. The preempted job is recorded as a `Preemption` and goes
+    /// back to the front of its priority in the queue, keeping its remaining
+    /// service time. Requeued jobs are never dropped, even over capacity.
+    Preemptive,
+}
+
+/// How long a `Station` runs between failures and how long each repair
+/// takes; see `Station::with_breakdowns`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Breakdowns {
+    pub time_to_failure: ContinuousDistribution,
+    pub time_to_repair: ContinuousDistribution,
+}
+
+/// The type of a job: the string `type` field of content that is a JSON
+/// object (see `payload::encode`), e.g. `drill` for `{"type":"drill"}`, with
+/// any creation time stamp ignored. Other content has no type.
+pub fn job_type(content: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(&payload::unstamped(content)).ok()?;
+    value.get("type")?.as_str().map(String::from)
+}
+
+/// The priority of a job: the integer before the first `:` of its content,
+/// e.g. `2` for `2:job-7`, with any creation time stamp ignored. Content
+/// without such a prefix has priority 0. Higher numbers are more urgent.
+pub fn job_priority(content: &str) -> i64 {
+    payload::unstamped(content)
+        .split_once(':')
+        .and_then(|(priority, _)| priority.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    processed_job: String,
+    #[serde(default)]
+    backpressure: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    until_next_event: f64,
+    queue: VecDeque<Queued>,
+    in_service: Vec<Service>,
+    dropped: usize,
+    preempted: usize,
+    /// Server-time spent serving, summed over servers.
+    busy_time: f64,
+    /// Integral of the queue length over time.
+    queue_area: f64,
+    /// Time since the start of the run.
+    clock: f64,
+    /// Time since the end of the warm-up period.
+    elapsed: f64,
+    /// Service times of the completed jobs, in order of completion.
+    #[serde(default)]
+    service_times: Vec<f64>,
+    /// The type of the last typed job to start service.
+    #[serde(default)]
+    last_job_type: Option<String>,
+    #[serde(default)]
+    setups: usize,
+    #[serde(default)]
+    setup_total: f64,
+    /// Arrivals by the queue length they found.
+    #[serde(default)]
+    queue_length_counts: Vec<usize>,
+    /// Whether `busy` was the last backpressure signal.
+    #[serde(default)]
+    signaled_busy: bool,
+    /// Backpressure signals not sent yet.
+    #[serde(default)]
+    signals: Vec<String>,
+    /// Time spent at each queue length.
+    #[serde(default)]
+    queue_length_times: Vec<f64>,
+    /// Whether the station is broken down.
+    #[serde(default)]
+    down: bool,
+    /// Time until the next failure or, while down, the end of the repair;
+    /// `None` until the first time to failure is drawn.
+    #[serde(default)]
+    until_transition: Option<f64>,
+    #[serde(default)]
+    failures: usize,
+    /// Time up and time down since the end of the warm-up period.
+    #[serde(default)]
+    uptime: f64,
+    #[serde(default)]
+    downtime: f64,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            until_next_event: f64::INFINITY,
+            queue: VecDeque::new(),
+            in_service: Vec::new(),
+            dropped: 0,
+            preempted: 0,
+            busy_time: 0.0,
+            queue_area: 0.0,
+            clock: 0.0,
+            elapsed: 0.0,
+            service_times: Vec::new(),
+            last_job_type: None,
+            setups: 0,
+            setup_total: 0.0,
+            queue_length_counts: Vec::new(),
+            signaled_busy: false,
+            signals: Vec::new(),
+            queue_length_times: Vec::new(),
+            down: false,
+            until_transition: None,
+            failures: 0,
+            uptime: 0.0,
+            downtime: 0.0,
+            records: Vec::new(),
+            records_stored: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Service {
+    remaining: f64,
+    /// The full service time drawn for the job.
+    #[serde(default)]
+    duration: f64,
+    content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Queued {
+    content: String,
+    /// The service time left for a preempted job; `None` draws a fresh one.
+    #[serde(default)]
+    remaining: Option<f64>,
+    /// The full service time drawn for a preempted job.
+    #[serde(default)]
+    duration: f64,
+}
+
+impl Station {
+    pub fn new(
+        service_time: ContinuousDistribution,
+        queue_capacity: Option<usize>,
+        servers: usize,
+        job_port: String,
+        processed_job_port: String,
+        store_records: bool,
+        rng: Option<DynRng>,
+    ) -> Self {
+        Self {
+            service_time,
+            queue_capacity,
+            servers,
+            warmup: 0.0,
+            priority: Priority::Fifo,
+            setup_time: None,
+            breakdowns: None,
+            ports_in: PortsIn { job: job_port },
+            ports_out: PortsOut {
+                processed_job: processed_job_port,
+                backpressure: None,
+            },
+            store_records,
+            max_records: None,
+            rng,
+            state: State::default(),
+        }
+    }
+
+    /// Excludes the first `time` units of the run from utilization and queue
+    /// length statistics.
+    pub fn with_warmup(mut self, time: f64) -> Self {
+        self.warmup = time;
+        self
+    }
+
+    /// Serves jobs in priority order, see `Priority`.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Draws a setup time from `setup_time` before serving a job whose type
+    /// differs from the previous one; see `Station`.
+    pub fn with_setup_time(mut self, setup_time: ContinuousDistribution) -> Self {
+        self.setup_time = Some(setup_time);
+        self
+    }
+
+    /// Signals `busy` and `ready` to the upstream model on `port`; see
+    /// `Station`.
+    pub fn with_backpressure(mut self, port: String) -> Self {
+        self.ports_out.backpressure = Some(port);
+        self
+    }
+
+    /// Fails and repairs the station at random; see `Station`.
+    pub fn with_breakdowns(
+        mut self,
+        time_to_failure: ContinuousDistribution,
+        time_to_repair: ContinuousDistribution,
+    ) -> Self {
+        self.breakdowns = Some(Breakdowns {
+            time_to_failure,
+            time_to_repair,
+        });
+        // the first time to failure is drawn on the first step.
+        self.state.until_next_event = 0.0;
+        self
+    }
+
+    /// Whether the station is broken down.
+    pub fn is_down(&self) -> bool {
+        self.state.down
+    }
+
+    /// Number of breakdowns so far.
+    pub fn failures(&self) -> usize {
+        self.state.failures
+    }
+
+    /// Time up since the end of the warm-up.
+    pub fn uptime(&self) -> f64 {
+        self.state.uptime
+    }
+
+    /// Time down for repair since the end of the warm-up.
+    pub fn downtime(&self) -> f64 {
+        self.state.downtime
+    }
+
+    /// Fraction of the time since the end of the warm-up the station was
+    /// up: 1.0 without breakdowns or before any time has passed.
+    pub fn availability(&self) -> f64 {
+        let total = self.state.uptime + self.state.downtime;
+        if total > 0.0 {
+            self.state.uptime / total
+        } else {
+            1.0
+        }
+    }
+
+    /// Number of servers currently serving a job.
+    pub fn busy_servers(&self) -> usize {
+        self.state.in_service.len()
+    }
+
+    /// Number of jobs waiting for a free server.
+    pub fn queue_length(&self) -> usize {
+        self.state.queue.len()
+    }
+
+    /// Number of jobs dropped because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.state.dropped
+    }
+
+    /// Number of times a job in service was preempted.
+    pub fn preempted(&self) -> usize {
+        self.state.preempted
+    }
+
+    /// Number of changeovers between job types so far.
+    pub fn setups(&self) -> usize {
+        self.state.setups
+    }
+
+    /// Time spent on changeovers between job types so far.
+    pub fn total_setup_time(&self) -> f64 {
+        self.state.setup_total
+    }
+
+    /// Average fraction of servers busy since the end of the warm-up, i.e.
+    /// busy server-time over `servers` times the elapsed time. Zero before
+    /// any time has passed.
+    pub fn utilization(&self) -> f64 {
+        if self.state.elapsed > 0.0 && self.servers > 0 {
+            self.state.busy_time / (self.state.elapsed * self.servers as f64)
+        } else {
+            0.0
+        }
+    }
+
+    /// Time-average number of jobs waiting in the queue (L_q).
+    pub fn mean_queue_length(&self) -> f64 {
+        if self.state.elapsed > 0.0 {
+            self.state.queue_area / self.state.elapsed
+        } else {
+            0.0
+        }
+    }
+
+    /// The number of arrivals that found each queue length: entry `n`
+    /// counts the jobs that arrived to `n` jobs waiting, whether they were
+    /// then served, queued or dropped.
+    pub fn queue_length_counts(&self) -> &[usize] {
+        &self.state.queue_length_counts
+    }
+
+    /// The fraction of the time since the end of the warm-up spent at each
+    /// queue length: entry `n` is the fraction with `n` jobs waiting. Empty
+    /// before any time has passed.
+    pub fn queue_length_fractions(&self) -> Vec<f64> {
+        self.state
+            .queue_length_times
+            .iter()
+            .map(|time| time / self.state.elapsed)
+            .collect()
+    }
+
+    /// The service time drawn for each completed job, in order of completion
+    /// and pooled over all servers. A preempted job counts once, with its
+    /// full service time. Only kept with `store_records`, and bounded like
+    /// the records by `with_max_records`.
+    pub fn service_times(&self) -> &[f64] {
+        &self.state.service_times
+    }
+
+    fn start_service(&mut self, job: Queued, services: &mut Services) -> Result<(), SimulationError> {
+        let (remaining, duration) = match job.remaining {
+            Some(remaining) => (remaining, job.duration),
+            None => {
+                let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+                let setup = self.setup(&job.content, services)?;
+                let duration = self.service_time.random_variate(rng)?;
+                (setup + duration, duration)
+            }
+        };
+        self.record(services.global_time(), "Processing Start", &job.content);
+        self.state.in_service.push(Service {
+            remaining,
+            duration,
+            content: job.content,
+        });
+        Ok(())
+    }
+
+    /// The setup time needed before serving `content`, recording it if there
+    /// is one.
+    fn setup(&mut self, content: &str, services: &mut Services) -> Result<f64, SimulationError> {
+        let (Some(setup_time), Some(job_type)) = (&self.setup_time, job_type(content)) else {
+            return Ok(0.0);
+        };
+        let previous = self.state.last_job_type.replace(job_type.clone());
+        if previous.is_none_or(|previous| previous == job_type) {
+            return Ok(0.0);
+        }
+        let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+        let setup = setup_time.random_variate(rng)?;
+        self.state.setups += 1;
+        self.state.setup_total += setup;
+        self.record(services.global_time(), "Setup", content);
+        Ok(setup)
+    }
+
+    /// Queues `job` behind every job of at least its priority or, for a
+    /// preempted job, behind every job of a strictly higher priority.
+    fn enqueue(&mut self, job: Queued) {
+        if self.priority == Priority::Fifo {
+            self.state.queue.push_back(job);
+            return;
+        }
+        let priority = job_priority(&job.content);
+        let preempted = job.remaining.is_some();
+        let position = self
+            .state
+            .queue
+            .iter()
+            .position(|queued| {
+                let queued_priority = job_priority(&queued.content);
+                queued_priority < priority || (preempted && queued_priority == priority)
+            })
+            .unwrap_or(self.state.queue.len());
+        self.state.queue.insert(position, job);
+    }
+
+    /// Preempts the lowest-priority job in service, most recently started
+    /// first among equals, if its priority is below `priority`.
+    fn preempt_below(&mut self, priority: i64, time: f64) -> bool {
+        let lowest = self
+            .state
+            .in_service
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, service)| (index, job_priority(&service.content)))
+            .min_by_key(|(_, service_priority)| *service_priority);
+        match lowest {
+            Some((index, service_priority)) if service_priority < priority => {
+                let service = self.state.in_service.remove(index);
+                self.state.preempted += 1;
+                self.record(time, "Preemption", &service.content);
+                self.enqueue(Queued {
+                    content: service.content,
+                    remaining: Some(service.remaining),
+                    duration: service.duration,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether an arriving job can start service right away.
+    fn has_free_server(&self) -> bool {
+        !self.state.down && self.state.in_service.len() < self.servers
+    }
+
+    /// Fails or repairs the station if that is due, drawing the time to the
+    /// next transition. The first call draws the first time to failure.
+    fn break_down_or_repair(&mut self, services: &mut Services) -> Result<(), SimulationError> {
+        let Some(breakdowns) = &self.breakdowns else {
+            return Ok(());
+        };
+        let rng = self.rng.clone().unwrap_or_else(|| services.global_rng());
+        let (down, until_transition) = match self.state.until_transition {
+            None => (false, breakdowns.time_to_failure.random_variate(rng)?),
+            Some(until_transition) if until_transition <= 0.0 => {
+                if self.state.down {
+                    (false, breakdowns.time_to_failure.random_variate(rng)?)
+                } else {
+                    (true, breakdowns.time_to_repair.random_variate(rng)?)
+                }
+            }
+            Some(_) => return Ok(()),
+        };
+        let time = services.global_time();
+        if down && !self.state.down {
+            self.state.failures += 1;
+            self.record(time, "Failure", "");
+        } else if !down && self.state.down {
+            self.record(time, "Repair", "");
+        }
+        self.state.down = down;
+        self.state.until_transition = Some(until_transition);
+        Ok(())
+    }
+
+    fn is_full(&self) -> bool {
+        self.state.in_service.len() >= self.servers
+            && self
+                .queue_capacity
+                .is_some_and(|capacity| self.state.queue.len() >= capacity)
+    }
+
+    /// Queues a backpressure signal if the station filled up or stopped
+    /// being full since the last one.
+    fn signal_backpressure(&mut self, time: f64) {
+        if self.ports_out.backpressure.is_none() || self.is_full() == self.state.signaled_busy {
+            return;
+        }
+        self.state.signaled_busy = !self.state.signaled_busy;
+        let signal = if self.state.signaled_busy { "busy" } else { "ready" };
+        self.record(time, "Backpressure", signal);
+        self.state.signals.push(signal.to_string());
+    }
+
+    fn refresh_until_next_event(&mut self) {
+        let transition = match (&self.breakdowns, self.state.until_transition) {
+            (None, _) => f64::INFINITY,
+            (Some(_), None) => 0.0,
+            (Some(_), Some(until_transition)) => until_transition,
+        };
+        self.state.until_next_event = if !self.state.signals.is_empty() {
+            0.0
+        } else if self.state.down {
+            transition
+        } else {
+            self.state
+                .in_service
+                .iter()
+                .fold(transition, |min, service| f64::min(min, service.remaining))
+        };
+    }
+}
+
+serializable_model!(Station);
+
+impl Validate for Station {
+    fn validate(&self) -> Result<(), String> {
+        if self.servers == 0 {
+            return Err(String::from("a station needs at least one server"));
+        }
+        if let Some(setup_time) = &self.setup_time {
+            setup_time
+                .validate()
+                .map_err(|reason| format!("setup time: {}", reason))?;
+        }
+        if let Some(breakdowns) = &self.breakdowns {
+            breakdowns
+                .time_to_failure
+                .validate()
+                .map_err(|reason| format!("time to failure: {}", reason))?;
+            breakdowns
+                .time_to_repair
+                .validate()
+                .map_err(|reason| format!("time to repair: {}", reason))?;
+        }
+        self.service_time.validate()
+    }
+}
+
+impl Ports for Station {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        std::iter::once(&self.ports_out.processed_job)
+            .chain(&self.ports_out.backpressure)
+            .cloned()
+            .collect()
+    }
+}
+
+impl DevsModel for Station {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        let time = services.global_time();
+        self.record(time, "Arrival", &incoming_message.content);
+        if time >= self.warmup {
+            let length = self.state.queue.len();
+            let counts = &mut self.state.queue_length_counts;
+            if counts.len() <= length {
+                counts.resize(length + 1, 0);
+            }
+            counts[length] += 1;
+        }
+        let job = Queued {
+            content: incoming_message.content.clone(),
+            remaining: None,
+            duration: 0.0,
+        };
+        if self.has_free_server()
+            || (self.priority == Priority::Preemptive
+                && !self.state.down
+                && self.preempt_below(job_priority(&job.content), time))
+        {
+            self.start_service(job, services)?;
+        } else if self.ports_out.backpressure.is_none()
+            && self
+                .queue_capacity
+                .is_some_and(|capacity| self.state.queue.len() >= capacity)
+        {
+            self.state.dropped += 1;
+            self.record(time, "Drop", &incoming_message.content);
+        } else {
+            self.enqueue(job);
+        }
+        self.signal_backpressure(time);
+        self.refresh_until_next_event();
+        Ok(())
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let (completed, in_service): (Vec<_>, Vec<_>) = self
+            .state
+            .in_service
+            .drain(..)
+            .partition(|service| service.remaining <= 0.0);
+        self.state.in_service = in_service;
+        let mut outgoing_messages: Vec<ModelMessage> = completed
+            .into_iter()
+            .map(|service| {
+                self.record(services.global_time(), "Departure", &service.content);
+                if self.store_records {
+                    super::push_record(
+                        &mut self.state.service_times,
+                        self.max_records,
+                        service.duration,
+                    );
+                }
+                ModelMessage {
+                    port_name: self.ports_out.processed_job.clone(),
+                    content: service.content,
+                }
+            })
+            .collect();
+        self.break_down_or_repair(services)?;
+        while self.has_free_server() {
+            match self.state.queue.pop_front() {
+                Some(job) => self.start_service(job, services)?,
+                None => break,
+            }
+        }
+        self.signal_backpressure(services.global_time());
+        if let Some(port) = &self.ports_out.backpressure {
+            outgoing_messages.extend(self.state.signals.drain(..).map(|signal| ModelMessage {
+                port_name: port.clone(),
+                content: signal,
+            }));
+        }
+        self.refresh_until_next_event();
+        Ok(outgoing_messages)
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        // the simulator advances by infinity once nothing is scheduled anywhere.
+        if time_delta.is_finite() {
+            let start = f64::max(self.state.clock, self.warmup);
+            self.state.clock += time_delta;
+            let counted = f64::max(self.state.clock - start, 0.0);
+            if self.state.down {
+                self.state.downtime += counted;
+            } else {
+                self.state.busy_time += self.state.in_service.len() as f64 * counted;
+                self.state.uptime += counted;
+            }
+            self.state.queue_area += self.state.queue.len() as f64 * counted;
+            self.state.elapsed += counted;
+            if counted > 0.0 {
+                let length = self.state.queue.len();
+                let times = &mut self.state.queue_length_times;
+                if times.len() <= length {
+                    times.resize(length + 1, 0.0);
+                }
+                times[length] += counted;
+            }
+        }
+        if !self.state.down {
+            self.state
+                .in_service
+                .iter_mut()
+                .for_each(|service| service.remaining -= time_delta);
+        }
+        if let Some(until_transition) = &mut self.state.until_transition {
+            *until_transition -= time_delta;
+        }
+        self.refresh_until_next_event();
+    }
+
+    fn until_next_event(&self) -> f64 {
+        self.state.until_next_event
+    }
+}
+
+impl Reportable for Station {
+    fn status(&self) -> String {
+        let mut status = format!(
+            "Serving {}/{} jobs, {} queued, {} dropped, {} preempted, mean queue {:.4}, utilization {:.4}",
+            self.state.in_service.len(),
+            self.servers,
+            self.state.queue.len(),
+            self.state.dropped,
+            self.state.preempted,
+            self.mean_queue_length(),
+            self.utilization()
+        );
+        if self.setup_time.is_some() {
+            status.push_str(&format!(
+                ", {} setups, setup time {:.4}",
+                self.state.setups, self.state.setup_total
+            ));
+        }
+        if self.breakdowns.is_some() {
+            status.push_str(&format!(
+                ", {} failures, availability {:.4}",
+                self.state.failures,
+                self.availability()
+            ));
+            if self.state.down {
+                status.push_str(", down");
+            }
+        }
+        status
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Station {}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use sim::models::{DevsModel, ModelMessage, ModelRecord, Reportable, ReportableModel};
+use sim::simulator::Services;
+use sim::utils::errors::SimulationError;
+
+use super::{Ports, Validate};
+
+/// The throttle passes jobs on unchanged, at most `rate` of them per time
+/// unit. A job arriving at least `1 / rate` after the previous release
+/// passes at once; otherwise it waits in a FIFO queue and jobs are released
+/// one every `1 / rate` until the queue is empty again. The queue is
+/// unbounded and its length is reported by `queue_length` and the status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Throttle {
+    rate: f64,
+    ports_in: PortsIn,
+    ports_out: PortsOut,
+    #[serde(default)]
+    store_records: bool,
+    #[serde(default)]
+    max_records: Option<usize>,
+    #[serde(default)]
+    state: State,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsIn {
+    job: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortsOut {
+    released_job: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    queue: VecDeque<String>,
+    /// Time left until the next job may be released.
+    cooldown: f64,
+    released: usize,
+    records: Vec<ModelRecord>,
+    #[serde(default)]
+    records_stored: usize,
+}
+
+impl Throttle {
+    pub fn new(rate: f64, job_port: String, released_job_port: String, store_records: bool) -> Self {
+        Self {
+            rate,
+            ports_in: PortsIn { job: job_port },
+            ports_out: PortsOut {
+                released_job: released_job_port,
+            },
+            store_records,
+            max_records: None,
+            state: State::default(),
+        }
+    }
+
+    /// Number of jobs waiting to be released.
+    pub fn queue_length(&self) -> usize {
+        self.state.queue.len()
+    }
+
+    /// Number of jobs released so far.
+    pub fn released(&self) -> usize {
+        self.state.released
+    }
+}
+
+serializable_model!(Throttle);
+
+impl Validate for Throttle {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.rate.is_finite() && self.rate > 0.0) {
+            return Err(format!("throttle rate must be positive, got {}", self.rate));
+        }
+        Ok(())
+    }
+}
+
+impl Ports for Throttle {
+    fn ports_in(&self) -> Vec<String> {
+        vec![self.ports_in.job.clone()]
+    }
+
+    fn ports_out(&self) -> Vec<String> {
+        vec![self.ports_out.released_job.clone()]
+    }
+}
+
+impl DevsModel for Throttle {
+    fn events_ext(
+        &mut self,
+        incoming_message: &ModelMessage,
+        services: &mut Services,
+    ) -> Result<(), SimulationError> {
+        if incoming_message.port_name != self.ports_in.job {
+            return Err(SimulationError::PortNotFound);
+        }
+        self.state.queue.push_back(incoming_message.content.clone());
+        self.record(services.global_time(), "Arrival", &incoming_message.content);
+        Ok(())
+    }
+
+    fn events_int(&mut self, services: &mut Services) -> Result<Vec<ModelMessage>, SimulationError> {
+        let Some(content) = self.state.queue.pop_front() else {
+            return Ok(Vec::new());
+        };
+        self.state.cooldown = 1.0 / self.rate;
+        self.state.released += 1;
+        self.record(services.global_time(), "Departure", &content);
+        Ok(vec![ModelMessage {
+            port_name: self.ports_out.released_job.clone(),
+            content,
+        }])
+    }
+
+    fn time_advance(&mut self, time_delta: f64) {
+        self.state.cooldown = f64::max(self.state.cooldown - time_delta, 0.0);
+    }
+
+    fn until_next_event(&self) -> f64 {
+        if self.state.queue.is_empty() {
+            f64::INFINITY
+        } else {
+            self.state.cooldown
+        }
+    }
+}
+
+impl Reportable for Throttle {
+    fn status(&self) -> String {
+        format!(
+            "Released {} jobs, {} queued",
+            self.state.released,
+            self.state.queue.len()
+        )
+    }
+
+    fn records(&self) -> &Vec<ModelRecord> {
+        &self.state.records
+    }
+}
+
+impl ReportableModel for Throttle {}
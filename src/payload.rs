@@ -0,0 +1,138 @@
+//! Structured message payloads.
+//!
+//! The simulator carries every payload as a `String` (`Message::content`,
+//! `ModelMessage::content`), and the models in this crate forward that
+//! string unchanged. A typed payload is stored as its JSON encoding, so any
+//! `Serialize` type can travel through a topology and be decoded again where
+//! it is consumed, without every model inventing its own string format.
+//!
+//! Plain strings are stored as-is rather than as quoted JSON, so messages
+//! such as `"Ball"` keep working: `decode::<String>` accepts any content, and
+//! typed and untyped payloads can share one simulation.
+
+use std::borrow::Cow;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sim::simulator::Message;
+use sim::utils::errors::SimulationError;
+
+/// Encodes `payload` as message content.
+pub fn encode<T: Serialize>(payload: &T) -> Result<String, SimulationError> {
+    match serde_json::to_value(payload).map_err(|_| SimulationError::InvalidMessage)? {
+        Value::String(content) => Ok(content),
+        value => Ok(value.to_string()),
+    }
+}
+
+/// Decodes message content produced by `encode`, with or without a creation
+/// time stamped on it (see `stamp`). Content that is not JSON (any plain
+/// string) decodes as a string payload.
+pub fn decode<T: DeserializeOwned>(content: &str) -> Result<T, SimulationError> {
+    let content = unstamped(content);
+    serde_json::from_str(&content)
+        .or_else(|_| serde_json::from_value(Value::String(content.into_owned())))
+        .map_err(|_| SimulationError::InvalidMessage)
+}
+
+/// Builds a message whose content is the encoded `payload`, with the same
+/// arguments as `Message::new`.
+pub fn typed_message<T: Serialize>(
+    source_id: String,
+    source_port: String,
+    target_id: String,
+    target_port: String,
+    time: f64,
+    payload: &T,
+) -> Result<Message, SimulationError> {
+    Ok(Message::new(
+        source_id,
+        source_port,
+        target_id,
+        target_port,
+        time,
+        encode(payload)?,
+    ))
+}
+
+/// Typed access to the content of a `Message`.
+pub trait Payload {
+    /// Decodes the message content as a `T`; see `decode`.
+    fn payload<T: DeserializeOwned>(&self) -> Result<T, SimulationError>;
+}
+
+impl Payload for Message {
+    fn payload<T: DeserializeOwned>(&self) -> Result<T, SimulationError> {
+        decode(self.content())
+    }
+}
+
+/// Content stamped with its creation time, as encoded by `stamp`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Stamped {
+    content: Value,
+    created_at: f64,
+}
+
+/// Stamps `content` with a creation time, as the JSON object
+/// `{"content":…,"createdAt":time}`. Encoded content is kept as JSON, e.g.
+/// `{"content":{"id":7},"createdAt":12.5}`, and anything else as a string,
+/// e.g. `{"content":"job 3","createdAt":12.5}`. Sources stamp every job they
+/// emit so that sinks can measure its time in the system.
+pub fn stamp(content: &str, time: f64) -> String {
+    let content = match serde_json::from_str::<Value>(content) {
+        Ok(value) if !value.is_string() && value.to_string() == content => value,
+        _ => Value::String(content.to_string()),
+    };
+    let stamped = Stamped {
+        content,
+        created_at: time,
+    };
+    serde_json::to_string(&stamped).unwrap_or_default()
+}
+
+fn stamped(content: &str) -> Option<Stamped> {
+    serde_json::from_str(content).ok()
+}
+
+/// The creation time stamped on `content` by `stamp`, if any.
+pub fn creation_time(content: &str) -> Option<f64> {
+    stamped(content)
+        .map(|stamped| stamped.created_at)
+        .filter(|time| time.is_finite())
+}
+
+/// `content` without the creation time stamped on it, if any: exactly the
+/// content that was passed to `stamp`.
+pub fn unstamped(content: &str) -> Cow<'_, str> {
+    match stamped(content) {
+        Some(Stamped {
+            content: Value::String(content),
+            ..
+        }) => Cow::Owned(content),
+        Some(stamped) => Cow::Owned(stamped.content.to_string()),
+        None => Cow::Borrowed(content),
+    }
+}
+
+/// The value of `key` in `content`, as text, ignoring any creation time
+/// stamped on it. The content is either an encoded JSON object, whose
+/// top-level field `key` is looked up, or a plain string of
+/// whitespace-separated `key=value` pairs such as `priority=high job 3`.
+pub fn field(content: &str, key: &str) -> Option<String> {
+    let content = unstamped(content);
+    match serde_json::from_str::<Value>(&content) {
+        Ok(Value::Object(object)) => match object.get(key)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Null | Value::Array(_) | Value::Object(_) => None,
+            value => Some(value.to_string()),
+        },
+        _ => content
+            .split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.to_string()),
+    }
+}
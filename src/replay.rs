@@ -0,0 +1,174 @@
+//! Event logs that reproduce a run step by step.
+//!
+//! `SimulationExt::record_events` writes one line of JSON per step after a
+//! header line: the header holds the state of the global RNG the run started
+//! from, and each step line the clock and the messages of the step. The log
+//! grows with the messages alone, never with the models' state.
+//! `SimulationExt::replay_events` restores the global RNG from the header
+//! and steps the simulation once per step line, checking that each step ends
+//! at the recorded time with the recorded messages. A simulation built the
+//! same way as the recorded one, in the state the recording started from,
+//! thus replays it exactly whatever its seed; once a model behaves
+//! differently, because its logic changed or it draws from a private RNG
+//! that started elsewhere, the replay stops at the first step that diverges
+//! and says how.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+use sim::simulator::{Message, Simulation};
+use sim::utils::errors::SimulationError;
+
+use crate::input_modeling::RngState;
+use crate::simulation::{SimulationExt, StopCondition};
+
+/// Why recording or replaying an event log failed.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The log could not be written or read.
+    Io(io::Error),
+    /// A model failed to process an event.
+    Model(SimulationError),
+    /// Line `line` (counting from 1) of the log is not a header or a
+    /// recorded step.
+    Parse { line: usize, reason: String },
+    /// The simulation stepped differently from the step recorded on line
+    /// `line`, typically because a model's logic or RNG differs from the
+    /// recorded one.
+    Diverged { line: usize, reason: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "cannot access the event log: {}", err),
+            ReplayError::Model(err) => write!(f, "a model failed: {}", err),
+            ReplayError::Parse { line, reason } => {
+                write!(f, "line {} of the event log is invalid: {}", line, reason)
+            }
+            ReplayError::Diverged { line, reason } => {
+                write!(f, "line {} of the event log diverged: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// The first line of a log.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    /// The global RNG the run started from; `None` if it could not be
+    /// captured, in which case the replay draws from its own.
+    rng: Option<RngState>,
+}
+
+/// One recorded step.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Step {
+    time: f64,
+    messages: Vec<Message>,
+}
+
+pub(crate) fn record<W: Write>(
+    simulation: &mut Simulation,
+    stop: &StopCondition,
+    mut writer: W,
+) -> Result<Vec<Message>, ReplayError> {
+    let header = Header {
+        rng: RngState::capture(&simulation.get_rng()),
+    };
+    write_line(&mut writer, &header)?;
+    let mut messages = Vec::new();
+    let mut iterations = 0;
+    while !simulation.is_exhausted() && !stop.reached(simulation, iterations) {
+        let stepped = simulation.step().map_err(ReplayError::Model)?;
+        let step = Step {
+            time: simulation.time(),
+            messages: stepped,
+        };
+        write_line(&mut writer, &step)?;
+        messages.extend(step.messages);
+        iterations += 1;
+    }
+    writer.flush().map_err(ReplayError::Io)?;
+    Ok(messages)
+}
+
+pub(crate) fn replay<R: BufRead>(
+    simulation: &mut Simulation,
+    reader: R,
+) -> Result<Vec<Message>, ReplayError> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| line.map(|line| (index + 1, line)))
+        .filter(|line| !line.as_ref().is_ok_and(|(_, line)| line.trim().is_empty()));
+    let header: Header = match lines.next() {
+        Some(line) => {
+            let (number, line) = line.map_err(ReplayError::Io)?;
+            parse(number, &line)?
+        }
+        None => {
+            return Err(ReplayError::Parse {
+                line: 1,
+                reason: String::from("the log has no header"),
+            });
+        }
+    };
+    if let Some(rng) = header.rng {
+        simulation.set_rng(rng.generator());
+    }
+    let mut messages = Vec::new();
+    for line in lines {
+        let (number, line) = line.map_err(ReplayError::Io)?;
+        let recorded: Step = parse(number, &line)?;
+        let stepped = Step {
+            messages: simulation.step().map_err(ReplayError::Model)?,
+            time: simulation.time(),
+        };
+        if let Some(reason) = divergence(&recorded, &stepped) {
+            return Err(ReplayError::Diverged {
+                line: number,
+                reason,
+            });
+        }
+        messages.extend(stepped.messages);
+    }
+    Ok(messages)
+}
+
+fn write_line<W: Write, T: Serialize>(writer: &mut W, line: &T) -> Result<(), ReplayError> {
+    serde_json::to_writer(&mut *writer, line).map_err(|err| ReplayError::Io(err.into()))?;
+    writeln!(writer).map_err(ReplayError::Io)
+}
+
+fn parse<'a, T: Deserialize<'a>>(number: usize, line: &'a str) -> Result<T, ReplayError> {
+    serde_json::from_str(line).map_err(|err| ReplayError::Parse {
+        line: number,
+        reason: err.to_string(),
+    })
+}
+
+/// How `stepped` differs from `recorded`, or `None` if it does not.
+fn divergence(recorded: &Step, stepped: &Step) -> Option<String> {
+    if recorded.time != stepped.time {
+        return Some(format!(
+            "the step was recorded at time {} but ended at {}",
+            recorded.time, stepped.time
+        ));
+    }
+    let (recorded, stepped) = (
+        serde_json::to_value(&recorded.messages).unwrap_or_default(),
+        serde_json::to_value(&stepped.messages).unwrap_or_default(),
+    );
+    (recorded != stepped).then(|| {
+        format!(
+            "the step was recorded with the messages {} but emitted {}",
+            recorded, stepped
+        )
+    })
+}
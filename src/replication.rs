@@ -0,0 +1,272 @@
+//! Independent replications of one simulation configuration.
+//!
+//! With the `parallel` feature, `replicate_parallel` runs the replications of
+//! a batch across threads. `sweep` replicates a series of configurations
+//! that differ in one parameter.
+
+use std::collections::BTreeMap;
+
+use sim::models::Reportable;
+use sim::simulator::Simulation;
+use sim::utils::errors::SimulationError;
+
+use crate::introspection;
+use crate::simulation::{SimulationExt, StopCondition};
+use crate::stats;
+
+/// One metric summarized across replications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSummary {
+    /// The metric's value in each replication, in replication order.
+    pub values: Vec<f64>,
+    pub mean: f64,
+    /// Sample standard deviation; 0.0 for a single replication.
+    pub std_dev: f64,
+}
+
+impl MetricSummary {
+    fn new(values: Vec<f64>) -> Self {
+        Self {
+            mean: stats::mean(&values).unwrap_or(0.0),
+            std_dev: stats::sample_std_dev(&values).unwrap_or(0.0),
+            values,
+        }
+    }
+
+    /// The two-sided confidence interval for the mean at `level` (e.g. 0.95),
+    /// `mean ± t * std_dev / sqrt(n)` with the Student's t quantile for
+    /// `n - 1` degrees of freedom, so it stays honest for a handful of
+    /// replications. `None` for fewer than two replications or a level
+    /// outside (0, 1).
+    pub fn confidence_interval(&self, level: f64) -> Option<(f64, f64)> {
+        let count = self.values.len();
+        if count < 2 || !(level > 0.0 && level < 1.0) {
+            return None;
+        }
+        let t = stats::student_t_quantile((1.0 + level) / 2.0, (count - 1) as f64);
+        let half_width = t * self.std_dev / (count as f64).sqrt();
+        Some((self.mean - half_width, self.mean + half_width))
+    }
+}
+
+/// Metrics summarized across a batch of replications, keyed by metric name.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Replications {
+    pub metrics: BTreeMap<String, MetricSummary>,
+}
+
+impl Replications {
+    pub fn metric(&self, name: &str) -> Option<&MetricSummary> {
+        self.metrics.get(name)
+    }
+}
+
+/// One point of a `sweep`: the parameter value and the metrics of its
+/// replications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint<V> {
+    pub value: V,
+    pub replications: Replications,
+}
+
+impl<V> SweepPoint<V> {
+    /// The mean of the metric `name` over the point's replications.
+    pub fn mean(&self, name: &str) -> Option<f64> {
+        self.replications.metric(name).map(|metric| metric.mean)
+    }
+}
+
+pub trait Replicate {
+    /// Runs `n` replications of this (unstepped) simulation to `stop`.
+    /// Replication `i` runs on a copy seeded with `seed_base + i`, so a batch
+    /// is reproducible. The metric for each model is its record count, keyed
+    /// by model id.
+    fn replicate(
+        &self,
+        n: usize,
+        seed_base: u64,
+        stop: &StopCondition,
+    ) -> Result<Replications, SimulationError>;
+
+    /// Like `replicate`, with the metrics of each finished replication
+    /// computed by `metrics`.
+    fn replicate_with<F>(
+        &self,
+        n: usize,
+        seed_base: u64,
+        stop: &StopCondition,
+        metrics: F,
+    ) -> Result<Replications, SimulationError>
+    where
+        F: Fn(&Simulation) -> BTreeMap<String, f64>;
+}
+
+impl Replicate for Simulation {
+    fn replicate(
+        &self,
+        n: usize,
+        seed_base: u64,
+        stop: &StopCondition,
+    ) -> Result<Replications, SimulationError> {
+        self.replicate_with(n, seed_base, stop, record_counts)
+    }
+
+    fn replicate_with<F>(
+        &self,
+        n: usize,
+        seed_base: u64,
+        stop: &StopCondition,
+        metrics: F,
+    ) -> Result<Replications, SimulationError>
+    where
+        F: Fn(&Simulation) -> BTreeMap<String, f64>,
+    {
+        let runs = (0..n)
+            .map(|i| {
+                let mut replication = self.clone();
+                replication.reseed(seed_base.wrapping_add(i as u64));
+                replication.run(stop)?;
+                Ok(metrics(&replication))
+            })
+            .collect::<Result<Vec<_>, SimulationError>>()?;
+        Ok(aggregate(runs))
+    }
+}
+
+/// Runs `n` replications, each on a simulation returned by `build` for the
+/// replication's master seed, `seed_base + i`, with its metrics computed by
+/// `metrics`.
+///
+/// Unlike `Replicate::replicate`, which reseeds copies of one simulation,
+/// this lets every replication give its models their own RNG streams,
+/// e.g. with `input_modeling::stream_rng(seed, id)`. Two configurations
+/// replicated this way with the same `seed_base` see common random numbers:
+/// replication `i` of each draws the same numbers for models with the same
+/// id, so their difference is not blurred by unrelated noise.
+pub fn replicate_seeded<B, F>(
+    build: B,
+    n: usize,
+    seed_base: u64,
+    stop: &StopCondition,
+    metrics: F,
+) -> Result<Replications, SimulationError>
+where
+    B: Fn(u64) -> Simulation,
+    F: Fn(&Simulation) -> BTreeMap<String, f64>,
+{
+    let runs = (0..n)
+        .map(|i| {
+            let seed = seed_base.wrapping_add(i as u64);
+            let mut replication = build(seed);
+            replication.reseed(seed);
+            replication.run(stop)?;
+            Ok(metrics(&replication))
+        })
+        .collect::<Result<Vec<_>, SimulationError>>()?;
+    Ok(aggregate(runs))
+}
+
+/// Runs `n` replications across threads, each on a simulation returned by
+/// `build` and seeded with `seed_base + i`, with the default metrics of
+/// `Replicate::replicate`.
+///
+/// A `Simulation` cannot be sent between threads (its RNG is reference
+/// counted), so each replication builds its own from scratch rather than
+/// copying a shared one; nothing is shared between replications. As long as
+/// `build` returns the simulation `replicate` is called on, the result is
+/// identical to the serial `replicate` for the same `seed_base`.
+#[cfg(feature = "parallel")]
+pub fn replicate_parallel<B>(
+    build: B,
+    n: usize,
+    seed_base: u64,
+    stop: &StopCondition,
+) -> Result<Replications, SimulationError>
+where
+    B: Fn() -> Simulation + Sync,
+{
+    replicate_parallel_with(build, n, seed_base, stop, record_counts)
+}
+
+/// Like `replicate_parallel`, with the metrics of each finished replication
+/// computed by `metrics`.
+#[cfg(feature = "parallel")]
+pub fn replicate_parallel_with<B, F>(
+    build: B,
+    n: usize,
+    seed_base: u64,
+    stop: &StopCondition,
+    metrics: F,
+) -> Result<Replications, SimulationError>
+where
+    B: Fn() -> Simulation + Sync,
+    F: Fn(&Simulation) -> BTreeMap<String, f64> + Sync,
+{
+    use rayon::prelude::*;
+
+    let runs = (0..n)
+        .into_par_iter()
+        .map(|i| {
+            let mut replication = build();
+            replication.reseed(seed_base.wrapping_add(i as u64));
+            replication.run(stop)?;
+            Ok(metrics(&replication))
+        })
+        .collect::<Result<Vec<_>, SimulationError>>()?;
+    Ok(aggregate(runs))
+}
+
+/// Runs `n` replications for each of `values` and returns one `SweepPoint`
+/// per value in order. Each replication runs on a simulation returned by
+/// `build` for the replication's master seed and the value, as in
+/// `replicate_seeded`, so `build` sets the parameter wherever it is
+/// configured, e.g. when loading a configuration document. Every point is
+/// replicated with the same seeds, `seed_base + i`, so the points differ by
+/// the parameter rather than by their random numbers, as far as the models
+/// draw them in the same order.
+pub fn sweep<V, B, F>(
+    build: B,
+    values: Vec<V>,
+    n: usize,
+    seed_base: u64,
+    stop: &StopCondition,
+    metrics: F,
+) -> Result<Vec<SweepPoint<V>>, SimulationError>
+where
+    B: Fn(u64, &V) -> Simulation,
+    F: Fn(&Simulation) -> BTreeMap<String, f64>,
+{
+    values
+        .into_iter()
+        .map(|value| {
+            let replications =
+                replicate_seeded(|seed| build(seed, &value), n, seed_base, stop, &metrics)?;
+            Ok(SweepPoint {
+                value,
+                replications,
+            })
+        })
+        .collect()
+}
+
+/// The default replication metrics: each model's record count.
+pub fn record_counts(simulation: &Simulation) -> BTreeMap<String, f64> {
+    introspection::models(simulation)
+        .iter()
+        .map(|model| (model.id().to_string(), model.records().len() as f64))
+        .collect()
+}
+
+pub(crate) fn aggregate(runs: Vec<BTreeMap<String, f64>>) -> Replications {
+    let mut values: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    runs.into_iter().for_each(|run| {
+        run.into_iter()
+            .for_each(|(name, value)| values.entry(name).or_default().push(value))
+    });
+    Replications {
+        metrics: values
+            .into_iter()
+            .map(|(name, values)| (name, MetricSummary::new(values)))
+            .collect(),
+    }
+}
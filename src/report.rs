@@ -0,0 +1,526 @@
+//! Reporting over the records collected by a finished (or paused) run.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sim::models::{Model, ModelRecord, Reportable};
+use sim::simulator::Simulation;
+
+use crate::introspection;
+use crate::models::{Concrete, Station};
+use crate::stats::{self, Summary};
+
+/// The percentiles `summarize_numeric` reports.
+pub const DEFAULT_PERCENTILES: [f64; 3] = [50.0, 95.0, 99.0];
+
+/// The `version` of the document `Report::to_json` produces. It changes only
+/// when a field is removed or changes meaning; new fields may be added
+/// without a version change.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// The `version` of the files `Report::save` writes. `Report::load` reads
+/// this version and older ones only.
+pub const SAVED_SCHEMA_VERSION: u32 = 1;
+
+/// What one unit of simulation clock stands for; see
+/// `Report::with_time_unit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl TimeUnit {
+    /// The length of one unit in seconds.
+    pub fn seconds(self) -> f64 {
+        match self {
+            TimeUnit::Seconds => 1.0,
+            TimeUnit::Minutes => 60.0,
+            TimeUnit::Hours => 3_600.0,
+            TimeUnit::Days => 86_400.0,
+        }
+    }
+
+    /// The name of a single unit, e.g. `minute`.
+    pub fn singular(self) -> &'static str {
+        match self {
+            TimeUnit::Seconds => "second",
+            TimeUnit::Minutes => "minute",
+            TimeUnit::Hours => "hour",
+            TimeUnit::Days => "day",
+        }
+    }
+
+    /// `duration` units of this unit in units of `to`.
+    pub fn convert(self, duration: f64, to: TimeUnit) -> f64 {
+        duration * self.seconds() / to.seconds()
+    }
+}
+
+/// The plural name, e.g. `minutes`.
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.singular())
+    }
+}
+
+/// A read-only view of a simulation for reporting.
+///
+/// A warm-up period set with `with_warmup` excludes the transient start of a
+/// run from everything the report computes: records stamped before the
+/// warm-up time are ignored. The simulation itself is untouched, so messages
+/// in flight at the boundary are not lost and `step_until`/`step_n` still
+/// return every message, including those from the warm-up period.
+///
+/// The clock is unitless. `with_time_unit` says what one unit stands for,
+/// which only changes how rates and durations are presented: `throughput`
+/// stays per clock unit, and `throughput_per` and the `format_` methods
+/// convert.
+pub struct Report<'a> {
+    simulation: &'a Simulation,
+    warmup: f64,
+    seed: Option<u64>,
+    events: Option<usize>,
+    time_unit: Option<TimeUnit>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(simulation: &'a Simulation) -> Self {
+        Self {
+            simulation,
+            warmup: 0.0,
+            seed: None,
+            events: None,
+            time_unit: None,
+        }
+    }
+
+    /// Ignores records stamped before `time`.
+    pub fn with_warmup(mut self, time: f64) -> Self {
+        self.warmup = time;
+        self
+    }
+
+    /// Records the seed the run was posted with in the JSON report; the
+    /// simulation itself does not remember it.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Records the number of events the run processed in the JSON report.
+    pub fn with_events(mut self, events: usize) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Declares that one unit of simulation clock is one `unit`; see
+    /// `Report`.
+    pub fn with_time_unit(mut self, unit: TimeUnit) -> Self {
+        self.time_unit = Some(unit);
+        self
+    }
+
+    /// The unit given to `with_time_unit`, if any.
+    pub fn time_unit(&self) -> Option<TimeUnit> {
+        self.time_unit
+    }
+
+    /// The post-warm-up records of `model_id`, or `None` if there is no such
+    /// model.
+    pub fn records(&self, model_id: &str) -> Option<Vec<&'a ModelRecord>> {
+        self.simulation
+            .get_models()
+            .get(model_id)
+            .map(|model| self.model_records(model))
+    }
+
+    fn model_records(&self, model: &'a Model) -> Vec<&'a ModelRecord> {
+        model
+            .records()
+            .iter()
+            .filter(|record| record.time >= self.warmup)
+            .collect()
+    }
+
+    /// Jobs completed by `model_id` per unit of post-warm-up time, up to the
+    /// current simulation clock. Completed jobs are the model's `Departure`
+    /// records or, for a model that records no departures (such as a storage
+    /// or a sink, which keep one record per job absorbed), all its records.
+    /// Returns `None` if there is no such model or no time has elapsed since
+    /// the warm-up.
+    pub fn throughput(&self, model_id: &str) -> Option<f64> {
+        let records = self.records(model_id)?;
+        throughput(records, self.simulation.get_global_time() - self.warmup)
+    }
+
+    /// `throughput` converted to jobs per `unit`, e.g. per hour for a clock
+    /// in minutes. `None` also without a `with_time_unit`.
+    pub fn throughput_per(&self, model_id: &str, unit: TimeUnit) -> Option<f64> {
+        let clock = self.time_unit?;
+        self.throughput(model_id)
+            .map(|throughput| throughput / clock.convert(1.0, unit))
+    }
+
+    /// `throughput_per` for display, e.g. `12.5000 jobs/hour`.
+    pub fn format_throughput(&self, model_id: &str, unit: TimeUnit) -> Option<String> {
+        self.throughput_per(model_id, unit)
+            .map(|throughput| format!("{:.4} jobs/{}", throughput, unit.singular()))
+    }
+
+    /// The post-warm-up window that throughput and utilization are measured
+    /// over, for display, e.g. `480.0000 minutes`. `None` without a
+    /// `with_time_unit` or once the clock is infinite.
+    pub fn format_elapsed(&self) -> Option<String> {
+        let elapsed = self.simulation.get_global_time() - self.warmup;
+        let unit = self.time_unit?;
+        elapsed
+            .is_finite()
+            .then(|| format!("{:.4} {}", elapsed, unit))
+    }
+
+    /// Summarizes the numeric `field` of `model_id`'s post-warm-up records,
+    /// with the `DEFAULT_PERCENTILES`. See `summarize_numeric_with`.
+    pub fn summarize_numeric(&self, model_id: &str, field: &str) -> Option<Summary> {
+        self.summarize_numeric_with(model_id, field, &DEFAULT_PERCENTILES)
+    }
+
+    /// Summarizes the numeric `field` (e.g. `time`, or a `subject` holding a
+    /// number) of `model_id`'s post-warm-up records, with the given
+    /// `percentiles` interpolated as described in `stats::percentile`.
+    /// Records whose field is missing or not a number are skipped. Returns
+    /// `None` if there is no such model or no numeric value.
+    pub fn summarize_numeric_with(
+        &self,
+        model_id: &str,
+        field: &str,
+        percentiles: &[f64],
+    ) -> Option<Summary> {
+        summarize_field(self.records(model_id)?, field, percentiles)
+    }
+
+    /// Writes every model's post-warm-up records as CSV, one row per record. The columns
+    /// are `model_id` followed by the union of all record fields; a record
+    /// without a given field leaves that cell blank. Rows are streamed to
+    /// `writer` one at a time.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let models = introspection::models(self.simulation);
+        let mut columns: Vec<String> = Vec::new();
+        models
+            .iter()
+            .flat_map(|model| self.model_records(*model))
+            .for_each(|record| {
+                if let Value::Object(fields) = record_fields(record) {
+                    fields.keys().for_each(|key| {
+                        if !columns.contains(key) {
+                            columns.push(key.clone());
+                        }
+                    });
+                }
+            });
+
+        let header: Vec<String> = std::iter::once(String::from("model_id"))
+            .chain(columns.iter().cloned())
+            .collect();
+        write_csv_row(&mut writer, &header)?;
+        for model in models {
+            for record in self.model_records(model) {
+                let fields = match record_fields(record) {
+                    Value::Object(fields) => fields,
+                    _ => Map::new(),
+                };
+                let row: Vec<String> = std::iter::once(model.id().to_string())
+                    .chain(columns.iter().map(|column| match fields.get(column) {
+                        None | Some(Value::Null) => String::new(),
+                        Some(Value::String(value)) => value.clone(),
+                        Some(value) => value.to_string(),
+                    }))
+                    .collect();
+                write_csv_row(&mut writer, &row)?;
+            }
+        }
+        writer.flush()
+    }
+
+    /// Writes the CSV report to the file at `path`, replacing it if it exists.
+    pub fn write_csv_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_csv(BufWriter::new(File::create(path)?))
+    }
+
+    /// The report as a JSON document with a stable schema, independent of
+    /// how models store their state:
+    ///
+    /// - `version`: `JSON_SCHEMA_VERSION`.
+    /// - `seed`: the seed given to `with_seed`, or `null`.
+    /// - `time`: the simulation clock, or `null` once it is infinite.
+    /// - `warmup`: the warm-up time, 0 without one.
+    /// - `elapsed`: `time` minus `warmup`, or `null` when `time` is.
+    /// - `events`: the count given to `with_events`, or `null`.
+    /// - `timeUnit`: the unit given to `with_time_unit`, e.g. `minutes`, or
+    ///   `null`. Times and rates are per clock unit either way.
+    /// - `models`: one object per model, sorted by `id`, with its `id`,
+    ///   `type`, `status`, post-warm-up `records` count, `throughput` (see
+    ///   `throughput`, `null` when undefined) and `utilization` (the busy
+    ///   fraction of a `Station`'s servers, `null` for other models).
+    pub fn to_json(&self) -> Value {
+        let time = self.simulation.get_global_time();
+        let time = time.is_finite().then_some(time);
+        let models: Vec<Value> = introspection::models(self.simulation)
+            .into_iter()
+            .map(|model| {
+                let serialized = introspection::serialized(model);
+                let utilization = model
+                    .as_concrete::<Station>()
+                    .map(|station| station.utilization());
+                json!({
+                    "id": model.id(),
+                    "type": introspection::model_type(&serialized),
+                    "status": model.status(),
+                    "records": self.model_records(model).len(),
+                    "throughput": self.throughput(model.id()),
+                    "utilization": utilization,
+                })
+            })
+            .collect();
+        json!({
+            "version": JSON_SCHEMA_VERSION,
+            "seed": self.seed,
+            "time": time,
+            "warmup": self.warmup,
+            "elapsed": time.map(|time| time - self.warmup),
+            "events": self.events,
+            "timeUnit": self.time_unit,
+            "models": models,
+        })
+    }
+
+    /// A self-contained copy of the report: everything `to_json` reports,
+    /// plus every model's post-warm-up records. See `SavedReport`.
+    pub fn to_saved(&self) -> SavedReport {
+        let time = self.simulation.get_global_time();
+        let models = introspection::models(self.simulation)
+            .into_iter()
+            .map(|model| SavedModel {
+                id: model.id().to_string(),
+                model_type: introspection::model_type(&introspection::serialized(model)).to_string(),
+                status: model.status(),
+                utilization: model
+                    .as_concrete::<Station>()
+                    .map(|station| station.utilization()),
+                records: self.model_records(model).into_iter().cloned().collect(),
+            })
+            .collect();
+        SavedReport {
+            version: SAVED_SCHEMA_VERSION,
+            seed: self.seed,
+            time: time.is_finite().then_some(time),
+            warmup: self.warmup,
+            events: self.events,
+            time_unit: self.time_unit,
+            models,
+        }
+    }
+
+    /// Writes `to_saved` as JSON to the file at `path`, replacing it if it
+    /// exists. `Report::load` reads it back.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &self.to_saved())?;
+        writer.flush()
+    }
+
+    /// Reads a report written by `save`, for analysis without the simulation
+    /// it was collected from.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<SavedReport, LoadError> {
+        let reader = BufReader::new(File::open(path).map_err(LoadError::Io)?);
+        let document: Value =
+            serde_json::from_reader(reader).map_err(|err| LoadError::Parse(err.to_string()))?;
+        // checked before the rest, whose layout a newer version may change.
+        let version = document["version"]
+            .as_u64()
+            .ok_or_else(|| LoadError::Parse(String::from("missing schema version")))?;
+        if version > u64::from(SAVED_SCHEMA_VERSION) {
+            return Err(LoadError::UnsupportedVersion { version });
+        }
+        serde_json::from_value(document).map_err(|err| LoadError::Parse(err.to_string()))
+    }
+}
+
+/// A report loaded with `Report::load`, detached from any simulation. It
+/// answers the same questions as the `Report` it was saved from, as of the
+/// time it was saved, and its records are already cut at the warm-up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedReport {
+    version: u32,
+    seed: Option<u64>,
+    /// `None` once the clock is infinite, which JSON cannot represent.
+    time: Option<f64>,
+    warmup: f64,
+    events: Option<usize>,
+    #[serde(default)]
+    time_unit: Option<TimeUnit>,
+    /// Sorted by id.
+    models: Vec<SavedModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedModel {
+    id: String,
+    #[serde(rename = "type")]
+    model_type: String,
+    status: String,
+    utilization: Option<f64>,
+    records: Vec<ModelRecord>,
+}
+
+impl SavedReport {
+    /// The schema version the report was saved with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The seed given to `Report::with_seed`, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// The simulation clock when the report was saved.
+    pub fn time(&self) -> f64 {
+        self.time.unwrap_or(f64::INFINITY)
+    }
+
+    /// The warm-up time, 0 without one.
+    pub fn warmup(&self) -> f64 {
+        self.warmup
+    }
+
+    /// The event count given to `Report::with_events`, if any.
+    pub fn events(&self) -> Option<usize> {
+        self.events
+    }
+
+    /// The unit given to `Report::with_time_unit`, if any.
+    pub fn time_unit(&self) -> Option<TimeUnit> {
+        self.time_unit
+    }
+
+    /// The ids of the models in the report, sorted.
+    pub fn model_ids(&self) -> Vec<&str> {
+        self.models.iter().map(|model| model.id.as_str()).collect()
+    }
+
+    /// The status of `model_id` when the report was saved.
+    pub fn status(&self, model_id: &str) -> Option<&str> {
+        self.model(model_id).map(|model| model.status.as_str())
+    }
+
+    /// The post-warm-up records of `model_id`, or `None` if there is no such
+    /// model.
+    pub fn records(&self, model_id: &str) -> Option<&[ModelRecord]> {
+        self.model(model_id).map(|model| model.records.as_slice())
+    }
+
+    /// As `Report::throughput`.
+    pub fn throughput(&self, model_id: &str) -> Option<f64> {
+        throughput(self.records(model_id)?, self.time() - self.warmup)
+    }
+
+    /// As `Report::summarize_numeric_with`.
+    pub fn summarize_numeric_with(
+        &self,
+        model_id: &str,
+        field: &str,
+        percentiles: &[f64],
+    ) -> Option<Summary> {
+        summarize_field(self.records(model_id)?, field, percentiles)
+    }
+
+    fn model(&self, model_id: &str) -> Option<&SavedModel> {
+        self.models.iter().find(|model| model.id == model_id)
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not a saved report.
+    Parse(String),
+    /// The file was saved with a newer schema than `SAVED_SCHEMA_VERSION`.
+    UnsupportedVersion { version: u64 },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "failed to read report: {}", err),
+            LoadError::Parse(msg) => write!(f, "invalid report: {}", msg),
+            LoadError::UnsupportedVersion { version } => write!(
+                f,
+                "report schema version {} is newer than the supported version {}",
+                version, SAVED_SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Completed jobs per unit of `elapsed` time; see `Report::throughput`.
+fn throughput<'r>(
+    records: impl IntoIterator<Item = &'r ModelRecord>,
+    elapsed: f64,
+) -> Option<f64> {
+    if !elapsed.is_finite() || elapsed <= 0.0 {
+        return None;
+    }
+    let records: Vec<&ModelRecord> = records.into_iter().collect();
+    let departures = records
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .count();
+    let completed = if departures > 0 { departures } else { records.len() };
+    Some(completed as f64 / elapsed)
+}
+
+fn summarize_field<'r>(
+    records: impl IntoIterator<Item = &'r ModelRecord>,
+    field: &str,
+    percentiles: &[f64],
+) -> Option<Summary> {
+    let values: Vec<f64> = records
+        .into_iter()
+        .filter_map(|record| match record_fields(record).get(field) {
+            Some(Value::Number(value)) => value.as_f64(),
+            Some(Value::String(value)) => value.trim().parse().ok(),
+            _ => None,
+        })
+        .collect();
+    stats::summarize(&values, percentiles)
+}
+
+fn record_fields<T: serde::Serialize>(record: &T) -> Value {
+    serde_json::to_value(record).unwrap_or(Value::Null)
+}
+
+fn write_csv_row<W: Write>(writer: &mut W, cells: &[String]) -> io::Result<()> {
+    let line: Vec<String> = cells.iter().map(|cell| escape_csv(cell)).collect();
+    writeln!(writer, "{}", line.join(","))
+}
+
+fn escape_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,788 @@
+//! A `Simulation` together with run-time instrumentation.
+//!
+//! # Event ordering
+//!
+//! Messages that are due at the same time are delivered in a fixed order,
+//! independent of how the simulator happens to iterate its models: by time,
+//! then source model id, then target model id, then target port. Messages
+//! that tie on all of these keep the order they were emitted or injected in.
+//! A session applies this order to its pending messages after every step and
+//! on every injection, so simultaneous events always resolve the same way.
+//!
+//! Times that round to the same multiple of the session's time epsilon
+//! (`DEFAULT_TIME_EPSILON` unless changed with `set_time_epsilon`) count as
+//! the same time, so two events meant to be simultaneous tie-break the same
+//! way even when rounding puts them an ULP apart. Only the delivery order of
+//! pending messages is affected: the clock still advances to each distinct
+//! time the simulator reaches. Grouping by multiples rather than by distance
+//! keeps the order total, at the price of splitting two close times that
+//! straddle a half-epsilon boundary, so the epsilon should stay well below
+//! the smallest real gap between events.
+//!
+//! # Stepping
+//!
+//! One step is one time advance, not one event: the clock jumps to the
+//! earliest pending event and every model due at that time runs its events,
+//! so a step can emit many messages, all stamped with the new time. The
+//! messages returned by `step` are in event order, and since the clock never
+//! goes back, the messages returned by `step_n` and the other multi-step
+//! methods are in event order too: non-decreasing in time, with ties in the
+//! order they were delivered. Two runs with the same seed return identical
+//! vectors.
+//!
+//! # Logging
+//!
+//! A session logs each transfer of a message to a model at debug level under
+//! the `sim_dag::session` target, as `key=value` pairs with the simulation
+//! `time`, the `model` id and `kind=transfer`. The models of this crate log
+//! their own transitions at trace level, under the target of their module
+//! (e.g. `sim_dag::models::station`), with the `time`, the model `type` and
+//! the transition as the kind: `kind=Arrival`, `kind=Processing Start`,
+//! `kind=Departure`, `kind=Drop` and so on, the same actions their records
+//! use, whether or not they store records. A model does not know its own id;
+//! the transfer routed to it at the same time names it. With both levels
+//! disabled, as by default, logging costs a level check per step and
+//! transition.
+//!
+//! # Hop limit
+//!
+//! `enable_hop_limit` guards cyclic topologies against a message that
+//! circulates forever. `Message` has no room for a hop count, so the session
+//! tracks it by content: a message is one hop further than the message with
+//! the same content routed most recently, and an injected message starts at
+//! zero. A job thus keeps its count through the models that pass its
+//! content on, however many other jobs are in the run, while a message with
+//! new content, such as a job a generator creates or one a model rewrites,
+//! starts at one hop. Two jobs with the same content share a count. A
+//! message that would exceed the limit is dropped instead of delivered,
+//! logged as a warning and kept in `expired`.
+//!
+//! # Dead letters
+//!
+//! The simulator drops a message emitted on a port that no connector leaves
+//! from without a trace. `enable_dead_letters` has the session catch these
+//! messages without touching the topology. Before each step in which a
+//! model with an unconnected port may emit, because a message is routed to
+//! it or its next event is due, the session steps a copy of the simulation
+//! whose unconnected ports lead to a `Sink`, and keeps what arrives there.
+//! The copy carries on with the RNG streams of the simulation, as a
+//! snapshot does, so it emits what the simulation itself emits. Each dead
+//! letter is logged as a warning and kept in `dead_letters`. In strict mode
+//! the step that emits it fails as well, once its other messages have been
+//! counted, observed and traced like those of any step, since they are
+//! delivered. Such a step costs a copy of the simulation, and the models
+//! stepped in the copy log their transitions a second time.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use log::{Level, debug, error, log_enabled, warn};
+use serde_json::json;
+use serde_yaml::Value;
+use sim::models::{DevsModel, Model, ModelRecord, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim::utils::errors::SimulationError;
+
+use crate::checker::{self, CheckError};
+use crate::event_queue::EventQueue;
+use crate::introspection;
+use crate::models::{self, Ports, Sink};
+use crate::simulation::{SimulationExt, SimulationState};
+
+/// Owns a simulation and steps it, keeping whatever instrumentation has been
+/// enabled up to date. Instrumentation that is not enabled costs nothing.
+pub struct Session {
+    simulation: Simulation,
+    trace: Option<Trace>,
+    traffic: Option<Traffic>,
+    hop_limit: Option<HopLimit>,
+    dead_letters: Option<DeadLetters>,
+    observers: Vec<Box<dyn FnMut(&Message, f64)>>,
+    events_processed: usize,
+    started: Instant,
+    /// Orders the messages of each step and injection; empty in between.
+    queue: EventQueue,
+    reset_point: Option<ResetPoint>,
+}
+
+/// The state `Session::reset` returns to.
+struct ResetPoint {
+    state: SimulationState,
+}
+
+/// Newline-delimited JSON trace of everything that happens in a run.
+struct Trace {
+    writer: Box<dyn Write>,
+    /// The records of each model that have already been traced.
+    traced_records: HashMap<String, Traced>,
+}
+
+/// How many messages crossed each connector.
+struct Traffic {
+    /// The connectors leaving each model.
+    routes: HashMap<String, Vec<Connector>>,
+    counts: HashMap<String, usize>,
+}
+
+/// The hop counts of the messages routed so far.
+struct HopLimit {
+    max_hops: usize,
+    /// The hops of the message most recently routed, by content.
+    hops: HashMap<String, usize>,
+    expired: Vec<Message>,
+}
+
+/// The messages emitted on unconnected ports so far, and the sink a copy of
+/// the simulation routes them to.
+struct DeadLetters {
+    strict: bool,
+    messages: Vec<Message>,
+    /// The models with an unconnected port.
+    models: Vec<String>,
+    sink_id: String,
+    /// The serialized sink.
+    sink: Value,
+    /// The serialized connectors from the unconnected ports to the sink.
+    connectors: Vec<Value>,
+}
+
+/// How far the records of one model have been traced.
+#[derive(Clone, Default)]
+struct Traced {
+    stored: usize,
+}
+
+impl Traffic {
+    fn count(&mut self, message: &Message) {
+        let connector = self.routes.get(message.source_id()).and_then(|connectors| {
+            connectors.iter().find(|connector| {
+                connector.source_port() == message.source_port()
+                    && connector.target_id() == message.target_id()
+                    && connector.target_port() == message.target_port()
+            })
+        });
+        if let Some(count) = connector.and_then(|connector| self.counts.get_mut(connector.id())) {
+            *count += 1;
+        }
+    }
+}
+
+impl HopLimit {
+    /// Splits the messages of one step into those within the limit, whose
+    /// hops it counts, and those exceeding it. Every hop is counted from the
+    /// counts before the step, so the copies of a job sent to several models
+    /// at once are all one hop further, not each one further than the last.
+    fn admit(&mut self, messages: Vec<Message>) -> (Vec<Message>, Vec<Message>) {
+        let before = self.hops.clone();
+        messages.into_iter().partition(|message| {
+            let hops = before.get(message.content()).copied().unwrap_or(0) + 1;
+            if hops > self.max_hops {
+                return false;
+            }
+            self.hops.insert(message.content().to_string(), hops);
+            true
+        })
+    }
+
+    /// Starts the injected `message` at zero hops.
+    fn inject(&mut self, message: &Message) {
+        self.hops.insert(message.content().to_string(), 0);
+    }
+}
+
+impl DeadLetters {
+    /// Whether a model with an unconnected port may emit in the next step of
+    /// `simulation`: a message is pending for it, or its next event is due
+    /// at the time the simulator advances to, which is the current time
+    /// while messages are pending.
+    fn due(&self, simulation: &Simulation) -> bool {
+        let pending = simulation.get_messages();
+        let models = simulation.get_models();
+        let advance = if pending.is_empty() {
+            models
+                .values()
+                .map(|model| model.until_next_event())
+                .fold(f64::INFINITY, f64::min)
+        } else {
+            0.0
+        };
+        self.models.iter().filter_map(|id| models.get(id)).any(|model| {
+            (advance.is_finite() && model.until_next_event() == advance)
+                || pending.iter().any(|message| message.target_id() == model.id())
+        })
+    }
+
+    /// A copy of `simulation` whose unconnected ports lead to the sink.
+    fn copy(&self, simulation: &Simulation) -> Result<Simulation, String> {
+        crate::simulation::edited_copy(simulation, |value| {
+            introspection::serialized_models_mut(value)
+                .ok_or_else(|| String::from("the serialized simulation has no models"))?
+                .insert(Value::from(self.sink_id.as_str()), self.sink.clone());
+            match value.get_mut("connectors") {
+                Some(Value::Sequence(connectors)) => {
+                    connectors.extend(self.connectors.iter().cloned());
+                    Ok(())
+                }
+                _ => Err(String::from("the serialized simulation has no connectors")),
+            }
+        })
+    }
+
+    /// The messages the next step of `simulation` emits on unconnected
+    /// ports. Their target id and port are empty.
+    fn probe(&self, simulation: &Simulation) -> Result<Vec<Message>, String> {
+        // a model that fails in the copy fails in the step itself, which
+        // reports it.
+        let Ok(messages) = self.copy(simulation)?.step() else {
+            return Ok(Vec::new());
+        };
+        Ok(messages
+            .into_iter()
+            .filter(|message| message.target_id() == self.sink_id)
+            .map(|message| {
+                Message::new(
+                    message.source_id().to_string(),
+                    message.source_port().to_string(),
+                    String::new(),
+                    String::new(),
+                    message.time(),
+                    message.content().to_string(),
+                )
+            })
+            .collect())
+    }
+}
+
+impl Traced {
+    fn of(model: &Model) -> Self {
+        Self {
+            stored: models::records_stored(model),
+        }
+    }
+
+    /// The records of `model` not traced yet: those it stored since, as far
+    /// as it still holds them after `with_max_records` dropped old ones.
+    fn untraced<'a>(&self, model: &'a Model) -> &'a [ModelRecord] {
+        let records = model.records();
+        let new = models::records_stored(model).saturating_sub(self.stored);
+        &records[records.len() - new.min(records.len())..]
+    }
+}
+
+impl Session {
+    pub fn new(simulation: Simulation) -> Self {
+        Self {
+            simulation,
+            trace: None,
+            traffic: None,
+            hop_limit: None,
+            dead_letters: None,
+            observers: Vec::new(),
+            events_processed: 0,
+            started: Instant::now(),
+            queue: EventQueue::with_time_epsilon(DEFAULT_TIME_EPSILON),
+            reset_point: None,
+        }
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    pub fn simulation_mut(&mut self) -> &mut Simulation {
+        &mut self.simulation
+    }
+
+    pub fn into_simulation(self) -> Simulation {
+        self.simulation
+    }
+
+    /// The simulation clock; see `SimulationExt::time`.
+    pub fn time(&self) -> f64 {
+        self.simulation.get_global_time()
+    }
+
+    /// Number of events processed by this session: every message returned
+    /// by its steps counts as one. Kept as a running count, so it is cheap
+    /// to poll, e.g. for a progress bar.
+    pub fn events_processed(&self) -> usize {
+        self.events_processed
+    }
+
+    /// The tolerance within which times count as simultaneous; see the
+    /// module documentation.
+    pub fn time_epsilon(&self) -> f64 {
+        self.queue.time_epsilon()
+    }
+
+    /// Sets the tolerance within which times count as simultaneous; see the
+    /// module documentation. It applies from the next step or injection on.
+    pub fn set_time_epsilon(&mut self, epsilon: f64) {
+        self.queue.set_time_epsilon(epsilon);
+    }
+
+    /// Wall-clock time since the session was created.
+    pub fn wall_time(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Writes one JSON object per line to `writer` for every event from now
+    /// on. Each line has the simulation `time`, the `modelId` concerned and a
+    /// `kind`: `transfer` for a message routed to `modelId` (with its source
+    /// and ports), or the action of a record the model stored, such as
+    /// `Arrival` or `Departure`. If writing fails the error is logged and
+    /// tracing stops; the run itself carries on.
+    pub fn enable_trace<W: Write + 'static>(&mut self, writer: W) {
+        let traced_records = introspection::models(&self.simulation)
+            .iter()
+            .map(|model| (model.id().to_string(), Traced::of(model)))
+            .collect();
+        self.trace = Some(Trace {
+            writer: Box::new(writer),
+            traced_records,
+        });
+    }
+
+    /// Counts the messages crossing each connector from now on, see
+    /// `traffic`. Only the connectors present when this is called are
+    /// counted.
+    pub fn enable_traffic(&mut self) {
+        let connectors = introspection::connectors(&self.simulation);
+        let counts = connectors
+            .iter()
+            .map(|connector| (connector.id().to_string(), 0))
+            .collect();
+        let mut routes: HashMap<String, Vec<Connector>> = HashMap::new();
+        connectors.into_iter().for_each(|connector| {
+            routes
+                .entry(connector.source_id().to_string())
+                .or_default()
+                .push(connector)
+        });
+        self.traffic = Some(Traffic { routes, counts });
+    }
+
+    /// The number of messages that crossed each connector, by connector id,
+    /// since `enable_traffic`. Connectors nothing crossed are listed with a
+    /// count of zero. Injected messages do not cross a connector and are not
+    /// counted. `None` if `enable_traffic` was not called.
+    pub fn traffic(&self) -> Option<&HashMap<String, usize>> {
+        self.traffic.as_ref().map(|traffic| &traffic.counts)
+    }
+
+    /// Drops every message from now on that would take more than
+    /// `max_hops` hops; see the module documentation.
+    pub fn enable_hop_limit(&mut self, max_hops: usize) {
+        self.hop_limit = Some(HopLimit {
+            max_hops,
+            hops: HashMap::new(),
+            expired: Vec::new(),
+        });
+    }
+
+    /// The messages dropped for exceeding the hop limit, in the order they
+    /// were emitted. Empty if `enable_hop_limit` was not called.
+    pub fn expired(&self) -> &[Message] {
+        self.hop_limit
+            .as_ref()
+            .map_or(&[], |hop_limit| hop_limit.expired.as_slice())
+    }
+
+    /// Collects every message emitted from now on on a port that no
+    /// connector leaves from, on models of this crate and `sim`'s built-in
+    /// models alike; see the module documentation. With `strict`, the step
+    /// that emits a dead letter returns `SimulationError::PortNotFound`,
+    /// after keeping the dead letter and scheduling the step's other
+    /// messages. The simulation is left as it is; only the ports unconnected
+    /// when this is called are watched. Fails if the simulation cannot be
+    /// copied, i.e. a model in it is not deserializable, as for
+    /// `SimulationExt::add_model`.
+    pub fn enable_dead_letters(&mut self, strict: bool) -> Result<(), CheckError> {
+        let connectors = introspection::connectors(&self.simulation);
+        let unconnected: Vec<(String, String)> = introspection::models(&self.simulation)
+            .iter()
+            .flat_map(|model| {
+                model
+                    .ports_out()
+                    .into_iter()
+                    .map(|port| (model.id().to_string(), port))
+            })
+            .filter(|(model_id, port)| {
+                !connectors.iter().any(|connector| {
+                    connector.source_id() == model_id && connector.source_port() == port
+                })
+            })
+            .collect();
+        let models = self.simulation.get_models();
+        let sink_id = (1..)
+            .map(|n| format!("dead letters {}", n))
+            .find(|id| !models.contains_key(id))
+            .unwrap_or_default();
+        let rebuild_error = |err: serde_yaml::Error| CheckError::Rebuild {
+            reason: err.to_string(),
+        };
+        let sink = Model::new(
+            sink_id.clone(),
+            Box::new(Sink::new(String::from(DEAD_LETTER_PORT), false)),
+        );
+        let mut unconnected_models: Vec<String> =
+            unconnected.iter().map(|(model_id, _)| model_id.clone()).collect();
+        unconnected_models.dedup();
+        let dead_letters = DeadLetters {
+            strict,
+            messages: Vec::new(),
+            models: unconnected_models,
+            sink: serde_yaml::to_value(&sink).map_err(rebuild_error)?,
+            connectors: unconnected
+                .into_iter()
+                .map(|(model_id, port)| {
+                    serde_yaml::to_value(Connector::new(
+                        format!("{} {} to {}", model_id, port, sink_id),
+                        model_id,
+                        sink_id.clone(),
+                        port,
+                        String::from(DEAD_LETTER_PORT),
+                    ))
+                })
+                .collect::<Result<_, _>>()
+                .map_err(rebuild_error)?,
+            sink_id,
+        };
+        dead_letters
+            .copy(&self.simulation)
+            .map_err(|reason| CheckError::Rebuild { reason })?;
+        self.dead_letters = Some(dead_letters);
+        Ok(())
+    }
+
+    /// The messages emitted on unconnected ports, in the order they were
+    /// emitted. Each keeps its source model and port, time and content; its
+    /// target id and port are empty, as nothing receives it. Empty if
+    /// `enable_dead_letters` was not called.
+    pub fn dead_letters(&self) -> &[Message] {
+        self.dead_letters
+            .as_ref()
+            .map_or(&[], |dead_letters| dead_letters.messages.as_slice())
+    }
+
+    /// Calls `observer` with every message routed from now on and the
+    /// simulation clock at the time, after each step and in event order.
+    /// Observers run in the order they were registered.
+    pub fn on_message<F: FnMut(&Message, f64) + 'static>(&mut self, observer: F) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Makes the current state the one `reset` returns to, RNGs included.
+    /// Called before the first step, `reset` goes back to the simulation as
+    /// constructed, so every run from there draws from its configured seeds
+    /// again: the global seed and the private seeds of the models of this
+    /// crate. Costs one snapshot of the simulation.
+    pub fn enable_reset(&mut self) {
+        self.reset_point = Some(ResetPoint {
+            state: self.simulation.snapshot(),
+        });
+    }
+
+    /// Returns the models, pending messages, clock and RNGs to their state
+    /// at `enable_reset`, without rebuilding the topology by hand: the
+    /// simulation is restored from its snapshot, see
+    /// `SimulationExt::restore`. The event count, traffic counts and expired
+    /// messages start over; observers and the trace stay registered. Returns
+    /// `Ok(false)`, changing nothing, if `enable_reset` was not called, and
+    /// fails, changing nothing either, if the snapshot cannot be restored.
+    pub fn reset(&mut self) -> Result<bool, CheckError> {
+        let Some(reset_point) = &self.reset_point else {
+            return Ok(false);
+        };
+        self.simulation.restore(reset_point.state.clone())?;
+        self.events_processed = 0;
+        if let Some(trace) = &mut self.trace {
+            trace.traced_records = introspection::models(&self.simulation)
+                .iter()
+                .map(|model| (model.id().to_string(), Traced::of(model)))
+                .collect();
+        }
+        if let Some(traffic) = &mut self.traffic {
+            traffic.counts.values_mut().for_each(|count| *count = 0);
+        }
+        if let Some(hop_limit) = &mut self.hop_limit {
+            hop_limit.hops.clear();
+            hop_limit.expired.clear();
+        }
+        if let Some(dead_letters) = &mut self.dead_letters {
+            dead_letters.messages.clear();
+        }
+        Ok(true)
+    }
+
+    /// Schedules `message`, keeping the pending messages in event order.
+    pub fn inject_input(&mut self, message: Message) {
+        if let Some(hop_limit) = &mut self.hop_limit {
+            hop_limit.inject(&message);
+        }
+        let in_order = self
+            .simulation
+            .get_messages()
+            .last()
+            .is_none_or(|last| self.order(last, &message) != Ordering::Greater);
+        if in_order {
+            self.simulation.inject_input(message);
+        } else {
+            self.inject_ordered(std::iter::once(message));
+        }
+    }
+
+    /// Schedules every message in `messages` in event order, or none of them
+    /// if any cannot be delivered; see `SimulationExt::inject_inputs`.
+    pub fn inject_inputs<I>(&mut self, messages: I) -> Result<(), CheckError>
+    where
+        I: IntoIterator<Item = Message>,
+    {
+        let messages: Vec<Message> = messages.into_iter().collect();
+        checker::check_messages(&self.simulation, &messages)?;
+        if let Some(hop_limit) = &mut self.hop_limit {
+            messages.iter().for_each(|message| hop_limit.inject(message));
+        }
+        self.inject_ordered(messages);
+        Ok(())
+    }
+
+    /// Adds `messages` to the pending messages, keeping them in event order.
+    fn inject_ordered<I: IntoIterator<Item = Message>>(&mut self, messages: I) {
+        self.queue
+            .extend(self.simulation.get_messages().iter().cloned());
+        self.queue.extend(messages);
+        let ordered = self.queue.drain_ordered();
+        self.schedule(ordered);
+    }
+
+    fn order(&self, a: &Message, b: &Message) -> Ordering {
+        event_order_within(a, b, self.queue.time_epsilon())
+    }
+
+    /// Replaces the pending messages with `ordered`, which is in event order.
+    fn schedule(&mut self, ordered: Vec<Message>) {
+        self.simulation.reset_messages();
+        ordered
+            .into_iter()
+            .for_each(|message| self.simulation.inject_input(message));
+    }
+
+    /// Steps once, returning the messages emitted in event order.
+    ///
+    /// The emitted messages are already pending in the simulation, in the
+    /// order returned. They are only rescheduled, at the cost of copying
+    /// them, when that order is not event order or the hop limit drops some.
+    pub fn step(&mut self) -> Result<Vec<Message>, SimulationError> {
+        let dead = match &self.dead_letters {
+            Some(dead_letters) if dead_letters.due(&self.simulation) => dead_letters
+                .probe(&self.simulation)
+                .unwrap_or_else(|reason| {
+                    error!("Failed to look for dead letters: {}", reason);
+                    Vec::new()
+                }),
+            _ => Vec::new(),
+        };
+        let emitted = self.simulation.step()?;
+        let mut messages = if emitted.is_sorted_by(|a, b| self.order(a, b) != Ordering::Greater) {
+            emitted
+        } else {
+            self.queue.extend(emitted);
+            let ordered = self.queue.drain_ordered();
+            self.schedule(ordered.clone());
+            ordered
+        };
+        if let Some(hop_limit) = &mut self.hop_limit {
+            let (admitted, expired) = hop_limit.admit(messages);
+            if !expired.is_empty() {
+                let time = self.simulation.get_global_time();
+                expired.iter().for_each(|message| {
+                    warn!(
+                        "time={} model={} kind=expired port={} source={} content={}",
+                        time,
+                        message.target_id(),
+                        message.target_port(),
+                        message.source_id(),
+                        message.content()
+                    )
+                });
+                hop_limit.expired.extend(expired);
+                self.schedule(admitted.clone());
+            }
+            messages = admitted;
+        }
+        let mut strict_failure = false;
+        if let Some(dead_letters) = &mut self.dead_letters
+            && !dead.is_empty()
+        {
+            let time = self.simulation.get_global_time();
+            dead.iter().for_each(|message| {
+                warn!(
+                    "time={} model={} kind=deadLetter port={} content={}",
+                    time,
+                    message.source_id(),
+                    message.source_port(),
+                    message.content()
+                )
+            });
+            strict_failure = dead_letters.strict;
+            dead_letters.messages.extend(dead);
+        }
+        self.events_processed += messages.len();
+        if let Some(traffic) = &mut self.traffic {
+            messages.iter().for_each(|message| traffic.count(message));
+        }
+        if !self.observers.is_empty() {
+            let time = self.simulation.get_global_time();
+            for message in &messages {
+                self.observers
+                    .iter_mut()
+                    .for_each(|observer| observer(message, time));
+            }
+        }
+        if log_enabled!(Level::Debug) {
+            self.log_step(&messages);
+        }
+        if let Some(trace) = &mut self.trace
+            && let Err(err) = trace.write_step(&self.simulation, &messages)
+        {
+            error!("Failed to write trace, tracing disabled: {}", err);
+            self.trace = None;
+        }
+        // the routed messages are delivered all the same, so they are
+        // counted before the step fails.
+        if strict_failure {
+            return Err(SimulationError::PortNotFound);
+        }
+        Ok(messages)
+    }
+
+    /// Logs the transfers of a step; see the module documentation.
+    fn log_step(&self, messages: &[Message]) {
+        messages.iter().for_each(|message| {
+            debug!(
+                "time={} model={} kind=transfer port={} source={} sourcePort={} content={}",
+                message.time(),
+                message.target_id(),
+                message.target_port(),
+                message.source_id(),
+                message.source_port(),
+                message.content()
+            )
+        });
+    }
+
+    /// Steps `n` times (`n` time advances, see the module documentation),
+    /// returning the messages of every step in event order.
+    pub fn step_n(&mut self, n: usize) -> Result<Vec<Message>, SimulationError> {
+        let mut messages = Vec::new();
+        for _ in 0..n {
+            messages.extend(self.step()?);
+        }
+        Ok(messages)
+    }
+
+    /// Steps until the simulation clock reaches `until`, returning the
+    /// messages of every step.
+    pub fn step_until(&mut self, until: f64) -> Result<Vec<Message>, SimulationError> {
+        let mut messages = Vec::new();
+        loop {
+            messages.extend(self.step()?);
+            if self.simulation.get_global_time() >= until {
+                break;
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Steps until `predicate` returns true, checking it after every step;
+    /// see `SimulationExt::step_until_predicate`.
+    pub fn step_until_predicate<F>(&mut self, mut predicate: F) -> Result<Vec<Message>, SimulationError>
+    where
+        F: FnMut(&Simulation) -> bool,
+    {
+        let mut messages = Vec::new();
+        loop {
+            messages.extend(self.step()?);
+            if predicate(&self.simulation) || self.simulation.get_global_time().is_infinite() {
+                break;
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Steps until nothing is left to happen; see
+    /// `SimulationExt::run_to_completion`.
+    pub fn run_to_completion(&mut self) -> Result<Vec<Message>, SimulationError> {
+        let mut messages = Vec::new();
+        while !self.simulation.is_exhausted() {
+            messages.extend(self.step()?);
+        }
+        Ok(messages)
+    }
+}
+
+const DEAD_LETTER_PORT: &str = "dead letter";
+
+/// The tolerance within which a session treats times as simultaneous unless
+/// told otherwise.
+pub const DEFAULT_TIME_EPSILON: f64 = 1e-9;
+
+/// The order in which simultaneous messages are delivered, with the
+/// `DEFAULT_TIME_EPSILON`; see the module documentation.
+pub fn event_order(a: &Message, b: &Message) -> Ordering {
+    event_order_within(a, b, DEFAULT_TIME_EPSILON)
+}
+
+/// `event_order`, with times that round to the same multiple of `epsilon`
+/// counting as the same time.
+pub fn event_order_within(a: &Message, b: &Message, epsilon: f64) -> Ordering {
+    time_bucket(a.time(), epsilon)
+        .total_cmp(&time_bucket(b.time(), epsilon))
+        .then_with(|| a.source_id().cmp(b.source_id()))
+        .then_with(|| a.target_id().cmp(b.target_id()))
+        .then_with(|| a.target_port().cmp(b.target_port()))
+}
+
+/// `time` as the nearest multiple of `epsilon`, counted in epsilons; `time`
+/// itself for a non-positive `epsilon`.
+fn time_bucket(time: f64, epsilon: f64) -> f64 {
+    if epsilon > 0.0 {
+        (time / epsilon).round()
+    } else {
+        time
+    }
+}
+
+impl Trace {
+    fn write_step(&mut self, simulation: &Simulation, messages: &[Message]) -> std::io::Result<()> {
+        for model in introspection::models(simulation) {
+            let traced = self.traced_records.entry(model.id().to_string()).or_default();
+            for record in traced.untraced(model) {
+                let line = json!({
+                    "time": record.time,
+                    "modelId": model.id(),
+                    "kind": record.action,
+                    "subject": record.subject,
+                });
+                writeln!(self.writer, "{}", line)?;
+            }
+            *traced = Traced::of(model);
+        }
+        for message in messages {
+            let line = json!({
+                "time": message.time(),
+                "modelId": message.target_id(),
+                "kind": "transfer",
+                "sourceId": message.source_id(),
+                "sourcePort": message.source_port(),
+                "targetPort": message.target_port(),
+                "content": message.content(),
+            });
+            writeln!(self.writer, "{}", line)?;
+        }
+        self.writer.flush()
+    }
+}
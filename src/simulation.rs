@@ -0,0 +1,724 @@
+//! Extensions to `sim::simulator::Simulation`.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use sim::models::{DevsModel, Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim::utils::errors::SimulationError;
+
+use crate::checker::{self, CheckError, InputPorts, TopologyChecker};
+use crate::diff::{self, StateDifference};
+use crate::input_modeling::RngState;
+use crate::introspection;
+use crate::models::register_models;
+use crate::replay::{self, ReplayError};
+use crate::session;
+
+/// When a run should stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopCondition {
+    /// Step until the simulation clock reaches the given time.
+    EndTime(f64),
+    /// Step the given number of times.
+    Iterations(usize),
+    /// Step until nothing is left to happen; see
+    /// `SimulationExt::run_to_completion`.
+    Completion,
+}
+
+impl StopCondition {
+    /// Whether a run that has taken `iterations` steps so far is done.
+    pub(crate) fn reached(&self, simulation: &Simulation, iterations: usize) -> bool {
+        match self {
+            StopCondition::EndTime(end_time) => simulation.get_global_time() >= *end_time,
+            StopCondition::Iterations(max_iterations) => iterations >= *max_iterations,
+            StopCondition::Completion => false,
+        }
+    }
+}
+
+/// Why `SimulationExt::step_until_any` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The simulation clock reached the end time.
+    EndTime,
+    /// The maximum number of iterations was taken.
+    Iterations,
+    /// Nothing was left to happen; see `SimulationExt::is_exhausted`.
+    Exhausted,
+}
+
+/// Why a checked run (`SimulationExt::try_step`, `SimulationExt::try_run`)
+/// failed.
+#[derive(Debug)]
+pub enum RunError {
+    /// A pending message targets a model that does not exist or an input
+    /// port its model does not declare, so it would be lost.
+    UnroutableMessage {
+        target_id: String,
+        target_port: String,
+    },
+    /// A model failed to process an event. `sim` reports the failure
+    /// without naming the model.
+    Model(SimulationError),
+    /// A step was requested but nothing is left to happen.
+    EmptyQueue,
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::UnroutableMessage {
+                target_id,
+                target_port,
+            } => write!(
+                f,
+                "a message to port '{}' of model '{}' cannot be delivered",
+                target_port, target_id
+            ),
+            RunError::Model(err) => write!(f, "a model failed: {}", err),
+            RunError::EmptyQueue => write!(f, "no events are left to process"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<SimulationError> for RunError {
+    fn from(err: SimulationError) -> Self {
+        RunError::Model(err)
+    }
+}
+
+/// The messages of a simulation, one at a time, as returned by
+/// `SimulationExt::events`.
+pub struct Events<'a> {
+    simulation: &'a mut Simulation,
+    /// Messages of the last step not yielded yet.
+    buffered: VecDeque<Message>,
+    failed: bool,
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<Message, SimulationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffered.is_empty() {
+            if self.failed || self.simulation.is_exhausted() {
+                return None;
+            }
+            match self.simulation.step() {
+                Ok(messages) => self.buffered.extend(messages),
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.buffered.pop_front().map(Ok)
+    }
+}
+
+/// The state of every model at one point in simulated time, as produced by
+/// `SimulationExt::run_with_frames`. Serializes to JSON for animation
+/// front-ends.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frame {
+    pub time: f64,
+    /// Sorted by model id.
+    pub models: Vec<ModelFrame>,
+}
+
+/// The summary state of one model in a `Frame`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFrame {
+    pub id: String,
+    pub status: String,
+    /// The number of records the model holds.
+    pub records: usize,
+}
+
+/// A captured copy of a simulation: event queue, model state, clock and RNG
+/// streams.
+///
+/// The snapshot keeps the serialized simulation and the `RngState` of its
+/// global RNG, and the private RNG of every model of this crate serializes
+/// with its model, so a restored simulation draws exactly what the original
+/// drew after the snapshot, however often it is restored. Taking a snapshot
+/// changes nothing. Generators `RngState::capture` cannot read are not
+/// carried over: a model draws from the global RNG instead of such a private
+/// one after a restore (this includes the private RNGs of `sim`'s built-in
+/// models, which do not serialize them), and such a global RNG gives way to
+/// `sim`'s default one.
+#[derive(Clone)]
+pub struct SimulationState {
+    simulation: Value,
+    rng: Option<RngState>,
+}
+
+/// Convenience methods on `Simulation`, in the same spirit as
+/// `sim::checker::Checker`.
+pub trait SimulationExt {
+    /// Posts a simulation whose global RNG is seeded with `seed`. Every model
+    /// constructed with `rng: None` draws from this generator, so two
+    /// simulations built from the same seed, models and connectors produce
+    /// identical event streams.
+    fn post_with_seed(models: Vec<Model>, connectors: Vec<Connector>, seed: u64) -> Self;
+
+    /// Replaces the global RNG with one seeded from `seed`.
+    fn reseed(&mut self, seed: u64);
+
+    /// The simulation clock: 0.0 before the first step, then the time of the
+    /// most recently processed event. It never decreases, and reading it
+    /// does not advance it. Once nothing at all is scheduled, the next step
+    /// moves it to infinity.
+    fn time(&self) -> f64;
+
+    /// Schedules every message in `messages`, after checking them all with
+    /// `checker::check_messages`. If any message cannot be delivered none
+    /// are scheduled, and the error names the first bad one.
+    fn inject_inputs<I>(&mut self, messages: I) -> Result<(), CheckError>
+    where
+        I: IntoIterator<Item = Message>;
+
+    /// Steps until `stop` is reached, returning the messages of every step.
+    /// Stops early once the simulation `is_exhausted`, leaving the clock at
+    /// the time of the last event instead of running it out to infinity.
+    fn run(&mut self, stop: &StopCondition) -> Result<Vec<Message>, SimulationError>;
+
+    /// True when nothing is left to happen: no message is pending and no
+    /// model has an internal event scheduled.
+    fn is_exhausted(&self) -> bool;
+
+    /// The messages scheduled but not yet delivered, in event order (see
+    /// `session::event_order`), without stepping or changing anything.
+    /// Internal events that models have scheduled for themselves are not
+    /// messages and do not appear; `until_next_event` on a model gives
+    /// those.
+    fn pending_events(&self) -> Vec<&Message>;
+
+    /// Steps until the simulation `is_exhausted`, however far the clock has
+    /// to go, and returns the messages of every step. Afterwards `time`
+    /// is the time of the last event. Never returns for a simulation that
+    /// keeps generating events, such as a `Source` without a `max_count`.
+    fn run_to_completion(&mut self) -> Result<Vec<Message>, SimulationError>;
+
+    /// The messages of the simulation from now on, lazily: the simulation is
+    /// stepped only when the messages of the previous step have all been
+    /// yielded, so a step's time advance happens on the `next` call that
+    /// yields its first message. Ends once the simulation `is_exhausted`, or
+    /// after yielding the error of a failed step. Collecting it yields the
+    /// same messages as `run_to_completion`.
+    fn events(&mut self) -> Events<'_>;
+
+    /// Steps once, like `step`, after checking that every pending message
+    /// can be delivered. Fails with `RunError::EmptyQueue`, without
+    /// stepping, if the simulation `is_exhausted`.
+    fn try_step(&mut self) -> Result<Vec<Message>, RunError>;
+
+    /// Like `run`, with every step taken by `try_step`, so that a message
+    /// that would be lost stops the run with an error instead. The models'
+    /// input ports are looked up once for the whole run.
+    fn try_run(&mut self, stop: &StopCondition) -> Result<Vec<Message>, RunError>;
+
+    /// Steps, as `try_run` does, until the clock reaches `end_time` or
+    /// `max_iterations` steps have been taken, whichever comes first, and
+    /// returns the messages of every step with the reason it stopped. A
+    /// condition that is `None` never stops the run; with neither given it
+    /// only stops once the simulation `is_exhausted`. When both are met by
+    /// the same step, the end time is reported.
+    fn step_until_any(
+        &mut self,
+        end_time: Option<f64>,
+        max_iterations: Option<usize>,
+    ) -> Result<(Vec<Message>, StopReason), RunError>;
+
+    /// Steps until `predicate` returns true for the simulation, checking it
+    /// after every step, and returns the messages of the steps taken during
+    /// this call. Also stops if nothing is left to happen, i.e. the clock
+    /// has advanced to infinity.
+    fn step_until_predicate<F>(&mut self, predicate: F) -> Result<Vec<Message>, SimulationError>
+    where
+        F: FnMut(&Simulation) -> bool;
+
+    /// Adds `model` to a simulation that may already be running. Pending
+    /// messages, the clock and the state of every other model are kept; the
+    /// new model receives messages once a connector leads to it. Fails, and
+    /// leaves the simulation as it was, if the id is taken or the model is
+    /// misconfigured.
+    ///
+    /// The simulation is rebuilt from its serialized form, so every model in
+    /// it must be deserializable (`sim`'s built-ins and every model in
+    /// `crate::models` are). The global RNG and the private RNGs carry on
+    /// with their streams, with the exceptions `SimulationState` lists.
+    fn add_model(&mut self, model: Model) -> Result<(), CheckError>;
+
+    /// Adds `connector` to a simulation that may already be running; messages
+    /// emitted from now on are routed over it. Fails, and leaves the
+    /// simulation as it was, if the id is taken or the connector does not
+    /// pass `TopologyChecker::check_topology`. See `add_model`.
+    fn add_connector(&mut self, connector: Connector) -> Result<(), CheckError>;
+
+    /// Sets the parameter `param` of the model `model_id` to `value`, in a
+    /// simulation that may already be running: `param` is a field of the
+    /// serialized model, such as the `serviceTime` of a `Processor` or
+    /// `Station`, and `value` takes its serialized form. The model's state
+    /// is kept, queue and statistics included, so only draws made from now
+    /// on see the new value; a job already in service keeps its draw. Fails,
+    /// and leaves the simulation as it was, if there is no such model or
+    /// parameter, or the changed model is misconfigured. See `add_model`.
+    fn update_model_param<V: Serialize>(
+        &mut self,
+        model_id: &str,
+        param: &str,
+        value: V,
+    ) -> Result<(), CheckError>;
+
+    /// Runs like `run`, and returns a `Frame` every `frame_interval` units of
+    /// simulated time from the current clock on, however many events fall
+    /// in between. The frame at time `t` shows the state after every event
+    /// up to and including `t`. Frames continue up to the end time of an
+    /// `EndTime` stop, even if the simulation is exhausted earlier, and
+    /// otherwise up to the clock where the run stopped. Fails with
+    /// `SimulationError::InvalidModelConfiguration` if `frame_interval` is
+    /// not positive.
+    fn run_with_frames(
+        &mut self,
+        stop: &StopCondition,
+        frame_interval: f64,
+    ) -> Result<Vec<Frame>, SimulationError>;
+
+    /// Captures the current state so it can be restored later.
+    fn snapshot(&self) -> SimulationState;
+
+    /// Rewinds to a captured state. The simulation is rebuilt from the
+    /// state's serialized form, so every model in it must be deserializable,
+    /// as for `add_model`; otherwise this fails and leaves the simulation as
+    /// it was.
+    fn restore(&mut self, state: SimulationState) -> Result<(), CheckError>;
+
+    /// Where this simulation and `other` diverge; see `diff::diff`.
+    fn diff(&self, other: &Simulation) -> Vec<StateDifference>;
+
+    /// Runs like `run`, writing an event log of every step to `writer`;
+    /// see `crate::replay`.
+    fn record_events<W: Write>(
+        &mut self,
+        stop: &StopCondition,
+        writer: W,
+    ) -> Result<Vec<Message>, ReplayError>;
+
+    /// Replays an event log written by `record_events`, returning the
+    /// recorded messages in order. The simulation should be built like the
+    /// recorded one, in the state the recording started from; it takes on
+    /// the recorded global RNG and steps through the log, ending in the state
+    /// the recording ended in. Fails at the first line that cannot be read
+    /// or that the simulation steps differently from, with the steps before
+    /// it taken; see `crate::replay`.
+    fn replay_events<R: BufRead>(&mut self, reader: R) -> Result<Vec<Message>, ReplayError>;
+
+    /// The complete state as a YAML document: models with their internal
+    /// state, pending messages, connectors and clock. It has the structure of
+    /// the JSON serialization, enum variants included, written as YAML maps
+    /// and lists without tags; only infinite times, which JSON writes as
+    /// `null`, are kept as `.inf`. A top-level `rng` key next to them holds
+    /// the state of the global RNG, as in `snapshot`, so that a simulation
+    /// read back with `from_yaml` steps the same way as this one.
+    #[cfg(feature = "yaml")]
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error>;
+
+    /// Reads a simulation written by `to_yaml`, or any YAML document with
+    /// the structure of the JSON serialization. The global RNG resumes from
+    /// the top-level `rng` key if there is one, and is the one `sim` gives a
+    /// deserialized simulation otherwise; the private RNGs of the models of
+    /// this crate resume from their serialized state, see `register_models`.
+    #[cfg(feature = "yaml")]
+    fn from_yaml(document: &str) -> Result<Self, serde_yaml::Error>
+    where
+        Self: Sized;
+}
+
+impl SimulationExt for Simulation {
+    fn post_with_seed(models: Vec<Model>, connectors: Vec<Connector>, seed: u64) -> Self {
+        Simulation::post_with_rng(models, connectors, Pcg64Mcg::seed_from_u64(seed))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.set_rng(Pcg64Mcg::seed_from_u64(seed));
+    }
+
+    fn time(&self) -> f64 {
+        self.get_global_time()
+    }
+
+    fn inject_inputs<I>(&mut self, messages: I) -> Result<(), CheckError>
+    where
+        I: IntoIterator<Item = Message>,
+    {
+        let messages: Vec<Message> = messages.into_iter().collect();
+        checker::check_messages(self, &messages)?;
+        messages
+            .into_iter()
+            .for_each(|message| self.inject_input(message));
+        Ok(())
+    }
+
+    fn run(&mut self, stop: &StopCondition) -> Result<Vec<Message>, SimulationError> {
+        let mut messages = Vec::new();
+        let mut iterations = 0;
+        while !self.is_exhausted() && !stop.reached(self, iterations) {
+            messages.extend(self.step()?);
+            iterations += 1;
+        }
+        Ok(messages)
+    }
+
+    fn run_with_frames(
+        &mut self,
+        stop: &StopCondition,
+        frame_interval: f64,
+    ) -> Result<Vec<Frame>, SimulationError> {
+        if !(frame_interval.is_finite() && frame_interval > 0.0) {
+            return Err(SimulationError::InvalidModelConfiguration);
+        }
+        let start = self.get_global_time();
+        let frame_time = |index: usize| start + index as f64 * frame_interval;
+        let end_time = match stop {
+            StopCondition::EndTime(end_time) => Some(*end_time),
+            _ => None,
+        };
+        let mut frames = Vec::new();
+        let mut iterations = 0;
+        while !self.is_exhausted() && !stop.reached(self, iterations) {
+            let before = model_frames(self);
+            self.step()?;
+            iterations += 1;
+            // the step jumped over these frame times, so they show the state before it.
+            while frame_time(frames.len()) < self.get_global_time()
+                && end_time.is_none_or(|end_time| frame_time(frames.len()) <= end_time)
+            {
+                frames.push(Frame {
+                    time: frame_time(frames.len()),
+                    models: before.clone(),
+                });
+            }
+        }
+        let end = end_time.unwrap_or_else(|| self.get_global_time());
+        if end.is_finite() {
+            let models = model_frames(self);
+            while frame_time(frames.len()) <= end {
+                frames.push(Frame {
+                    time: frame_time(frames.len()),
+                    models: models.clone(),
+                });
+            }
+        }
+        Ok(frames)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.get_messages().is_empty()
+            && self
+                .get_models()
+                .values()
+                .all(|model| model.until_next_event().is_infinite())
+    }
+
+    fn pending_events(&self) -> Vec<&Message> {
+        let mut pending: Vec<&Message> = self.get_messages().iter().collect();
+        pending.sort_by(|a, b| session::event_order(a, b));
+        pending
+    }
+
+    fn run_to_completion(&mut self) -> Result<Vec<Message>, SimulationError> {
+        self.run(&StopCondition::Completion)
+    }
+
+    fn events(&mut self) -> Events<'_> {
+        Events {
+            simulation: self,
+            buffered: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    fn try_step(&mut self) -> Result<Vec<Message>, RunError> {
+        let ports = InputPorts::of(self);
+        try_step_checked(self, &ports)
+    }
+
+    fn try_run(&mut self, stop: &StopCondition) -> Result<Vec<Message>, RunError> {
+        let ports = InputPorts::of(self);
+        let mut messages = Vec::new();
+        let mut iterations = 0;
+        while !self.is_exhausted() && !stop.reached(self, iterations) {
+            messages.extend(try_step_checked(self, &ports)?);
+            iterations += 1;
+        }
+        Ok(messages)
+    }
+
+    fn step_until_any(
+        &mut self,
+        end_time: Option<f64>,
+        max_iterations: Option<usize>,
+    ) -> Result<(Vec<Message>, StopReason), RunError> {
+        let ports = InputPorts::of(self);
+        let mut messages = Vec::new();
+        let mut iterations = 0;
+        loop {
+            if end_time.is_some_and(|end_time| self.get_global_time() >= end_time) {
+                return Ok((messages, StopReason::EndTime));
+            }
+            if max_iterations.is_some_and(|max_iterations| iterations >= max_iterations) {
+                return Ok((messages, StopReason::Iterations));
+            }
+            if self.is_exhausted() {
+                return Ok((messages, StopReason::Exhausted));
+            }
+            messages.extend(try_step_checked(self, &ports)?);
+            iterations += 1;
+        }
+    }
+
+    fn step_until_predicate<F>(&mut self, mut predicate: F) -> Result<Vec<Message>, SimulationError>
+    where
+        F: FnMut(&Simulation) -> bool,
+    {
+        let mut messages = Vec::new();
+        loop {
+            messages.extend(self.step()?);
+            if predicate(self) || self.get_global_time().is_infinite() {
+                break;
+            }
+        }
+        Ok(messages)
+    }
+
+    fn add_model(&mut self, model: Model) -> Result<(), CheckError> {
+        if self.get_models().contains_key(model.id()) {
+            return Err(CheckError::DuplicateModel {
+                model_id: model.id().to_string(),
+            });
+        }
+        checker::check_models(std::slice::from_ref(&model))?;
+        let id = Value::from(model.id());
+        let model = serde_yaml::to_value(&model).map_err(|err| err.to_string());
+        rebuild(self, |simulation| {
+            let model = model?;
+            serialized_models(simulation)?.insert(id, model);
+            Ok(())
+        })
+    }
+
+    fn add_connector(&mut self, connector: Connector) -> Result<(), CheckError> {
+        if introspection::connectors(self)
+            .iter()
+            .any(|existing| existing.id() == connector.id())
+        {
+            return Err(CheckError::DuplicateConnector {
+                connector_id: connector.id().to_string(),
+            });
+        }
+        let connector = serde_yaml::to_value(&connector).map_err(|err| err.to_string());
+        rebuild(self, |simulation| match (connector, simulation.get_mut("connectors")) {
+            (Ok(connector), Some(Value::Sequence(connectors))) => {
+                connectors.push(connector);
+                Ok(())
+            }
+            (Err(reason), _) => Err(reason),
+            (Ok(_), _) => Err(String::from("the serialized simulation has no connectors")),
+        })
+    }
+
+    fn update_model_param<V: Serialize>(
+        &mut self,
+        model_id: &str,
+        param: &str,
+        value: V,
+    ) -> Result<(), CheckError> {
+        let invalid = |reason: String| CheckError::InvalidModel {
+            model_id: model_id.to_string(),
+            reason,
+        };
+        let mut model = self
+            .get_models()
+            .get(model_id)
+            .map(introspection::serialized)
+            .ok_or_else(|| CheckError::MissingModel {
+                model_id: model_id.to_string(),
+            })?;
+        let value = serde_yaml::to_value(value).map_err(|err| invalid(err.to_string()))?;
+        match model.get_mut(param) {
+            Some(field) if !["id", "type", "state"].contains(&param) => *field = value,
+            _ => return Err(invalid(format!("no parameter '{}'", param))),
+        }
+        register_models();
+        let updated: Model =
+            serde_yaml::from_value(model.clone()).map_err(|err| invalid(err.to_string()))?;
+        checker::check_models(std::slice::from_ref(&updated))?;
+        let id = Value::from(model_id);
+        rebuild(self, |simulation| {
+            serialized_models(simulation)?.insert(id, model);
+            Ok(())
+        })
+    }
+
+    fn snapshot(&self) -> SimulationState {
+        SimulationState {
+            simulation: serde_yaml::to_value(self).unwrap_or(Value::Null),
+            rng: RngState::capture(&self.get_rng()),
+        }
+    }
+
+    fn restore(&mut self, state: SimulationState) -> Result<(), CheckError> {
+        *self = resume(state.simulation, state.rng.as_ref())
+            .map_err(|reason| CheckError::Rebuild { reason })?;
+        Ok(())
+    }
+
+    fn diff(&self, other: &Simulation) -> Vec<StateDifference> {
+        diff::diff(self, other)
+    }
+
+    fn record_events<W: Write>(
+        &mut self,
+        stop: &StopCondition,
+        writer: W,
+    ) -> Result<Vec<Message>, ReplayError> {
+        replay::record(self, stop, writer)
+    }
+
+    fn replay_events<R: BufRead>(&mut self, reader: R) -> Result<Vec<Message>, ReplayError> {
+        replay::replay(self, reader)
+    }
+
+    #[cfg(feature = "yaml")]
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let mut value = untagged(serde_yaml::to_value(self)?);
+        let rng = RngState::capture(&self.get_rng());
+        if let (Some(fields), Some(rng)) = (value.as_mapping_mut(), rng) {
+            fields.insert(Value::from("rng"), serde_yaml::to_value(rng)?);
+        }
+        serde_yaml::to_string(&value)
+    }
+
+    #[cfg(feature = "yaml")]
+    fn from_yaml(document: &str) -> Result<Self, serde_yaml::Error> {
+        register_models();
+        let mut value: Value = serde_yaml::from_str(document)?;
+        let rng = match value.as_mapping_mut().and_then(|fields| fields.remove("rng")) {
+            Some(rng) => Some(serde_yaml::from_value::<RngState>(rng)?),
+            None => None,
+        };
+        let mut simulation: Simulation = serde_yaml::from_value(value)?;
+        if let Some(rng) = rng {
+            simulation.set_rng(rng.generator());
+        }
+        Ok(simulation)
+    }
+}
+
+/// `SimulationExt::try_step`, checking the pending messages against `ports`,
+/// the input ports of `simulation`'s models.
+fn try_step_checked(
+    simulation: &mut Simulation,
+    ports: &InputPorts,
+) -> Result<Vec<Message>, RunError> {
+    if simulation.is_exhausted() {
+        return Err(RunError::EmptyQueue);
+    }
+    if let Err(CheckError::UndeliverableMessage {
+        target_id,
+        target_port,
+        ..
+    }) = ports.check(simulation.get_messages())
+    {
+        return Err(RunError::UnroutableMessage {
+            target_id,
+            target_port,
+        });
+    }
+    Ok(simulation.step()?)
+}
+
+fn model_frames(simulation: &Simulation) -> Vec<ModelFrame> {
+    introspection::models(simulation)
+        .into_iter()
+        .map(|model| ModelFrame {
+            id: model.id().to_string(),
+            status: model.status(),
+            records: model.records().len(),
+        })
+        .collect()
+}
+
+/// `value` with every enum variant that YAML tags written as a single-key
+/// map from the variant name instead, the way JSON writes it.
+#[cfg(feature = "yaml")]
+fn untagged(value: Value) -> Value {
+    match value {
+        Value::Tagged(tagged) => {
+            let variant = tagged.tag.to_string().trim_start_matches('!').to_string();
+            Value::Mapping(std::iter::once((Value::from(variant), untagged(tagged.value))).collect())
+        }
+        Value::Sequence(values) => Value::Sequence(values.into_iter().map(untagged).collect()),
+        Value::Mapping(entries) => Value::Mapping(
+            entries
+                .into_iter()
+                .map(|(key, value)| (untagged(key), untagged(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Deserializes the simulation `value` with its global RNG resuming from
+/// `rng`; its private RNGs resume from their serialized state.
+pub(crate) fn resume(value: Value, rng: Option<&RngState>) -> Result<Simulation, String> {
+    register_models();
+    let mut simulation: Simulation =
+        serde_yaml::from_value(value).map_err(|err| err.to_string())?;
+    if let Some(rng) = rng {
+        simulation.set_rng(rng.generator());
+    }
+    Ok(simulation)
+}
+
+/// The models of the serialized `simulation` being rebuilt.
+fn serialized_models(simulation: &mut Value) -> Result<&mut Mapping, String> {
+    introspection::serialized_models_mut(simulation)
+        .ok_or_else(|| String::from("the serialized simulation has no models"))
+}
+
+/// A copy of `simulation` whose serialized form was changed by `edit`. The
+/// RNGs of the copy carry on with the streams of `simulation`, as across
+/// `SimulationExt::snapshot` and `restore`.
+pub(crate) fn edited_copy<F>(simulation: &Simulation, edit: F) -> Result<Simulation, String>
+where
+    F: FnOnce(&mut Value) -> Result<(), String>,
+{
+    let mut value = serde_yaml::to_value(simulation).map_err(|err| err.to_string())?;
+    edit(&mut value)?;
+    resume(value, RngState::capture(&simulation.get_rng()).as_ref())
+}
+
+/// Replaces `simulation` with its `edited_copy`, if the copy passes the
+/// topology checks.
+fn rebuild<F>(simulation: &mut Simulation, edit: F) -> Result<(), CheckError>
+where
+    F: FnOnce(&mut Value) -> Result<(), String>,
+{
+    let rebuilt = edited_copy(simulation, edit).map_err(|reason| CheckError::Rebuild { reason })?;
+    rebuilt.check_topology()?;
+    *simulation = rebuilt;
+    Ok(())
+}
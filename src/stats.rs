@@ -0,0 +1,97 @@
+//! Descriptive statistics over collected values.
+
+use crate::input_modeling::continuous::{invert, regularized_beta};
+
+/// Arithmetic mean, or `None` for no values.
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Sample standard deviation (with Bessel's correction), or `None` for fewer
+/// than two values.
+pub fn sample_std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    let sum_of_squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    Some((sum_of_squares / (values.len() - 1) as f64).sqrt())
+}
+
+/// Percentile `p` (0 to 100) of `sorted`, which must be in ascending order,
+/// or `None` for no values.
+///
+/// Interpolates linearly between the two closest ranks: with `n` values the
+/// percentile sits at the zero-based rank `p / 100 * (n - 1)`. This is the
+/// "type 7" estimator of Hyndman and Fan, the default of R and NumPy, so the
+/// 0th and 100th percentiles are the minimum and maximum.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = usize::min(lower + 1, sorted.len() - 1);
+    Some(sorted[lower] + (rank - lower as f64) * (sorted[upper] - sorted[lower]))
+}
+
+/// The quantile `p` (strictly between 0 and 1) of Student's t distribution
+/// with `degrees_of_freedom` (positive). For `p = 0.975` it approaches the
+/// normal 1.96 as the degrees of freedom grow but is about 12.71 for one.
+pub fn student_t_quantile(p: f64, degrees_of_freedom: f64) -> f64 {
+    let cdf = |t: f64| {
+        let tail = 0.5
+            * regularized_beta(
+                degrees_of_freedom / 2.0,
+                0.5,
+                degrees_of_freedom / (degrees_of_freedom + t * t),
+            );
+        if t < 0.0 { tail } else { 1.0 - tail }
+    };
+    invert(cdf, p, -1.0, 1.0)
+}
+
+/// Summary statistics over a set of values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// `None` for a single value.
+    pub std_dev: Option<f64>,
+    pub min: f64,
+    pub max: f64,
+    /// `(p, value)` pairs, in the order they were requested.
+    pub percentiles: Vec<(f64, f64)>,
+}
+
+impl Summary {
+    /// The value of percentile `p`, if it was requested.
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        self.percentiles
+            .iter()
+            .find(|(requested, _)| *requested == p)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Summarizes `values` with the given `percentiles` (see `percentile`), or
+/// `None` for no values.
+pub fn summarize(values: &[f64], percentiles: &[f64]) -> Option<Summary> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Some(Summary {
+        count: sorted.len(),
+        mean: mean(&sorted)?,
+        std_dev: sample_std_dev(&sorted),
+        min: *sorted.first()?,
+        max: *sorted.last()?,
+        percentiles: percentiles
+            .iter()
+            .filter_map(|p| percentile(&sorted, *p).map(|value| (*p, value)))
+            .collect(),
+    })
+}
@@ -0,0 +1,79 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Assembler, Concrete, Sink, Source};
+use sim_dag::payload;
+
+fn parts(interarrival: f64) -> Box<Source> {
+    Box::new(Source::new(
+        ContinuousDistribution::Constant {
+            value: interarrival,
+        },
+        None,
+        String::from("part"),
+        true,
+        None,
+    ))
+}
+
+#[test]
+fn test_output_follows_the_slowest_input() {
+    let models = vec![
+        Model::new(String::from("bodies"), parts(1.0)),
+        Model::new(String::from("wheels"), parts(2.0)),
+        Model::new(
+            String::from("assembly"),
+            Box::new(Assembler::new(
+                vec![String::from("body"), String::from("wheel set")],
+                String::from("car"),
+                false,
+            )),
+        ),
+        Model::new(String::from("lot"), Box::new(Sink::new(String::from("car"), false))),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("bodies to assembly"),
+            String::from("bodies"),
+            String::from("assembly"),
+            String::from("part"),
+            String::from("body"),
+        ),
+        Connector::new(
+            String::from("wheels to assembly"),
+            String::from("wheels"),
+            String::from("assembly"),
+            String::from("part"),
+            String::from("wheel set"),
+        ),
+        Connector::new(
+            String::from("assembly to lot"),
+            String::from("assembly"),
+            String::from("lot"),
+            String::from("car"),
+            String::from("car"),
+        ),
+    ];
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.step_until(999.5).unwrap();
+
+    let generated = |id: &str| simulation.get_models()[id].records().len();
+    let (bodies, wheels) = (generated("bodies"), generated("wheels"));
+    assert!(wheels >= 500);
+    let cars = simulation.get_models().get("lot").unwrap().records();
+    assert_eq!(cars.len(), wheels);
+    let first: Vec<String> = payload::decode(&cars[0].subject).unwrap();
+    let part = payload::stamp("part 1", 0.0);
+    assert_eq!(first, vec![part.clone(), part]);
+
+    // the bodies that found no wheel set keep piling up.
+    let assembler: &Assembler = simulation.get_models()["assembly"].as_concrete().unwrap();
+    assert_eq!(
+        assembler.backlogs(),
+        vec![("body", bodies - wheels), ("wheel set", 0)]
+    );
+    assert_eq!(
+        simulation.get_models()["assembly"].status(),
+        format!("Assembled {}, waiting body: {}, wheel set: 0", wheels, bodies - wheels)
+    );
+}
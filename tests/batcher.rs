@@ -0,0 +1,83 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::models::{Batcher, Sink, Source};
+use sim_dag::payload;
+
+fn pallets(batcher: Batcher) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("boxes"),
+            Box::new(Source::from_schedule(
+                (0..25).map(f64::from).collect(),
+                String::from("box"),
+                false,
+            )),
+        ),
+        Model::new(String::from("palletizer"), Box::new(batcher)),
+        Model::new(
+            String::from("dock"),
+            Box::new(Sink::new(String::from("pallet"), false)),
+        ),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("boxes to palletizer"),
+            String::from("boxes"),
+            String::from("palletizer"),
+            String::from("box"),
+            String::from("box"),
+        ),
+        Connector::new(
+            String::from("palletizer to dock"),
+            String::from("palletizer"),
+            String::from("dock"),
+            String::from("pallet"),
+            String::from("pallet"),
+        ),
+    ];
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.step_until(100.0).unwrap();
+    simulation
+}
+
+fn batch_sizes(simulation: &Simulation) -> Vec<usize> {
+    simulation
+        .get_models()
+        .get("dock")
+        .unwrap()
+        .records()
+        .iter()
+        .map(|record| payload::decode::<Vec<String>>(&record.subject).unwrap().len())
+        .collect()
+}
+
+#[test]
+fn test_full_batches_leave_remainder_pending() {
+    let simulation = pallets(Batcher::new(
+        12,
+        String::from("box"),
+        String::from("pallet"),
+        true,
+    ));
+
+    assert_eq!(batch_sizes(&simulation), vec![12, 12]);
+    let palletizer = simulation.get_models().get("palletizer").unwrap();
+    assert_eq!(palletizer.status(), "Accumulated 1/12 jobs, 2 batches");
+    let last = palletizer.records().last().unwrap();
+    assert_eq!(last.action, "Accumulation");
+    assert_eq!(last.subject, "1/12");
+}
+
+#[test]
+fn test_timeout_flushes_partial_batch() {
+    let simulation = pallets(
+        Batcher::new(12, String::from("box"), String::from("pallet"), true).with_timeout(5.0),
+    );
+
+    assert_eq!(batch_sizes(&simulation), vec![12, 12, 1]);
+    let palletizer = simulation.get_models().get("palletizer").unwrap();
+    let flush = palletizer.records().last().unwrap();
+    assert_eq!(flush.action, "Timeout");
+    assert_eq!(flush.time, 29.0);
+    assert_eq!(flush.subject, "1");
+}
@@ -0,0 +1,114 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Message, Simulation};
+use sim_dag::models::{Buffer, Overflow};
+
+fn to_buffer(port: &str, content: &str) -> Message {
+    Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "buffer".to_string(),
+        port.to_string(),
+        0.0,
+        content.to_string(),
+    )
+}
+
+fn buffer_simulation(capacity: Option<usize>, overflow: Overflow) -> Simulation {
+    let models = vec![Model::new(
+        String::from("buffer"),
+        Box::new(Buffer::new(
+            String::from("put"),
+            String::from("get"),
+            String::from("stored"),
+            capacity,
+            overflow,
+            true,
+        )),
+    )];
+    Simulation::post(models, Vec::new())
+}
+
+fn count(simulation: &Simulation, action: &str) -> usize {
+    simulation
+        .get_models()
+        .get("buffer")
+        .unwrap()
+        .records()
+        .iter()
+        .filter(|record| record.action == action)
+        .count()
+}
+
+#[test]
+fn test_full_buffer_records_overflows() {
+    let mut simulation = buffer_simulation(Some(3), Overflow::Reject);
+    (1..=5).for_each(|i| simulation.inject_input(to_buffer("put", &format!("item-{}", i))));
+    simulation.step().unwrap();
+
+    assert_eq!(count(&simulation, "Put"), 3);
+    assert_eq!(count(&simulation, "Overflow"), 2);
+    let status = simulation.get_models().get("buffer").unwrap().status();
+    assert_eq!(status, "Holding 3/3 items, 0 blocked, 2 overflows");
+}
+
+#[test]
+fn test_get_frees_capacity_for_blocked_put() {
+    let mut simulation = buffer_simulation(Some(3), Overflow::Block);
+    (1..=5).for_each(|i| simulation.inject_input(to_buffer("put", &format!("item-{}", i))));
+    simulation.step().unwrap();
+    simulation.inject_input(to_buffer("get", ""));
+    simulation.step_n(2).unwrap();
+
+    let records = simulation.get_models().get("buffer").unwrap().records();
+    let taken: Vec<&str> = records
+        .iter()
+        .filter(|record| record.action == "Get")
+        .map(|record| record.subject.as_str())
+        .collect();
+    assert_eq!(taken, vec!["item-1"]);
+    assert_eq!(records.last().unwrap().subject, "item-4");
+    assert_eq!(count(&simulation, "Overflow"), 0);
+    let status = simulation.get_models().get("buffer").unwrap().status();
+    assert_eq!(status, "Holding 3/3 items, 1 blocked, 0 overflows");
+}
+
+#[test]
+fn test_unbounded_buffer_never_overflows() {
+    let mut simulation = buffer_simulation(None, Overflow::Reject);
+    (1..=5).for_each(|i| simulation.inject_input(to_buffer("put", &format!("item-{}", i))));
+    simulation.step().unwrap();
+
+    assert_eq!(count(&simulation, "Put"), 5);
+    let status = simulation.get_models().get("buffer").unwrap().status();
+    assert_eq!(status, "Holding 5/unbounded items, 0 blocked, 0 overflows");
+}
+
+#[test]
+fn test_initial_contents_available_to_gets() {
+    let items = (1..=50).map(|i| format!("unit-{}", i)).collect();
+    let models = vec![Model::new(
+        String::from("buffer"),
+        Box::new(
+            Buffer::new(
+                String::from("put"),
+                String::from("get"),
+                String::from("stored"),
+                Some(50),
+                Overflow::Reject,
+                true,
+            )
+            .with_initial_contents(items),
+        ),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    let status = simulation.get_models().get("buffer").unwrap().status();
+    assert_eq!(status, "Holding 50/50 items, 0 blocked, 0 overflows");
+
+    (0..51).for_each(|_| simulation.inject_input(to_buffer("get", "")));
+    simulation.step().unwrap();
+    let records = simulation.get_models().get("buffer").unwrap().records();
+    assert_eq!(count(&simulation, "Get"), 50);
+    assert_eq!(records[0].subject, "unit-1");
+    assert_eq!(records[49].subject, "unit-50");
+    assert_eq!(count(&simulation, "Put"), 0);
+}
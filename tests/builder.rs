@@ -0,0 +1,175 @@
+mod common;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Processor, Reportable, Storage};
+use sim::simulator::Simulation;
+use sim_dag::builder::{CoupledModel, SimulationBuilder};
+use sim_dag::checker::CheckError;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Sink, Source, Station};
+use sim_dag::simulation::SimulationExt;
+
+fn player() -> Box<Processor> {
+    Box::new(Processor::new(
+        ContinuousRandomVariable::Exp { lambda: 0.9 },
+        None,
+        String::from("receive"),
+        String::from("send"),
+        false,
+        None,
+    ))
+}
+
+#[test]
+fn test_builder_matches_manual_construction() {
+    let built = SimulationBuilder::new()
+        .model("player-01", player())
+        .model("player-02", player())
+        .model(
+            "Store",
+            Box::new(Storage::new(
+                "put".to_string(),
+                "get".to_string(),
+                "stored".to_string(),
+                true,
+            )),
+        )
+        .connect("player-01 to player-02", "player-01", "send", "player-02", "receive")
+        .connect("player-02 to player-01", "player-02", "send", "player-01", "receive")
+        .connect("player-02 to Store", "player-02", "send", "Store", "put")
+        .build()
+        .unwrap();
+    let manual = Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    assert_eq!(
+        serde_json::to_value(&built).unwrap(),
+        serde_json::to_value(&manual).unwrap()
+    );
+}
+
+#[test]
+fn test_builder_rejects_duplicate_ids() {
+    let result = SimulationBuilder::new()
+        .model("player-01", player())
+        .model("player-01", player())
+        .build();
+    assert!(matches!(
+        result,
+        Err(CheckError::DuplicateModel { model_id }) if model_id == "player-01"
+    ));
+}
+
+#[test]
+fn test_builder_rejects_connector_to_missing_model() {
+    let result = SimulationBuilder::new()
+        .model("player-01", player())
+        .connect("p1 to p2", "player-01", "send", "player-02", "receive")
+        .build();
+    assert!(matches!(result, Err(CheckError::UnknownModel { .. })));
+}
+
+#[test]
+fn test_connector_delay_shifts_arrivals() {
+    let arrivals = |delay: f64| {
+        let mut simulation = SimulationBuilder::new()
+            .model("player-01", player())
+            .model("player-02", player())
+            .connect_delayed("p1 to p2", "player-01", "send", "player-02", "receive", delay)
+            .connect("p2 to p1", "player-02", "send", "player-01", "receive")
+            .seed(57)
+            .build()
+            .unwrap();
+        simulation.inject_input(common::ball());
+        simulation
+            .step_n(100)
+            .unwrap()
+            .into_iter()
+            .filter(|message| message.target_id() == "player-02")
+            .map(|message| message.time())
+            .take(5)
+            .collect::<Vec<f64>>()
+    };
+    let instant = arrivals(0.0);
+    let delayed = arrivals(2.0);
+    assert_eq!(instant.len(), 5);
+    assert_eq!(delayed.len(), 5);
+    // every round trip crosses the delayed connector once, so the n-th
+    // arrival at player-02 is 2.0 later per crossing so far.
+    instant.iter().zip(&delayed).enumerate().for_each(|(n, (instant, delayed))| {
+        assert!((delayed - instant - 2.0 * (n + 1) as f64).abs() < 1e-9);
+    });
+}
+
+#[test]
+fn test_negative_connector_delay_rejected() {
+    let result = SimulationBuilder::new()
+        .model("player-01", player())
+        .model("player-02", player())
+        .connect_delayed("p1 to p2", "player-01", "send", "player-02", "receive", -1.0)
+        .build();
+    assert!(matches!(result, Err(CheckError::InvalidModel { .. })));
+}
+
+fn two_stage_line() -> CoupledModel {
+    let station = || {
+        Box::new(Station::new(
+            ContinuousDistribution::Exp { lambda: 3.0 },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        ))
+    };
+    CoupledModel::new()
+        .model("first", station())
+        .model("second", station())
+        .connect("first to second", "first", "processed job", "second", "job")
+        .input("job", "first", "job")
+        .output("done", "second", "processed job")
+}
+
+#[test]
+fn test_coupled_models_are_flattened() {
+    let line = two_stage_line();
+    let mut simulation = SimulationBuilder::new()
+        .model(
+            "arrivals",
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                Some(20),
+                String::from("job"),
+                false,
+                None,
+            )),
+        )
+        .coupled("line-a", &line)
+        .coupled("line-b", &line)
+        .model("exit", Box::new(Sink::new(String::from("job"), true)))
+        .connect("arrivals to line-a", "arrivals", "job", "line-a", "job")
+        .connect("line-a to line-b", "line-a", "done", "line-b", "job")
+        .connect("line-b to exit", "line-b", "done", "exit", "job")
+        .seed(83)
+        .build()
+        .unwrap();
+    simulation.run_to_completion().unwrap();
+
+    let models = simulation.get_models();
+    assert_eq!(models.get("exit").unwrap().records().len(), 20);
+    assert!(models.contains_key("line-a/first"));
+    assert!(models.contains_key("line-b/second"));
+    assert!(!models.contains_key("line-a"));
+}
+
+#[test]
+fn test_coupled_model_rejects_unknown_exposed_port() {
+    let result = SimulationBuilder::new()
+        .coupled("line", &two_stage_line())
+        .model("exit", Box::new(Sink::new(String::from("job"), true)))
+        .connect("line to exit", "line", "finished", "exit", "job")
+        .build();
+    assert!(matches!(
+        result,
+        Err(CheckError::UnknownPort { model_id, port, .. }) if model_id == "line" && port == "finished"
+    ));
+}
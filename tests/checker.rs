@@ -0,0 +1,265 @@
+mod common;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Model, Processor};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::checker::{
+    check, check_acyclic, check_connected, check_models, cycles, orphans, CheckError, Orphan,
+    TopologyChecker,
+};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Sink, Source, Station};
+
+#[test]
+fn test_ping_pong_topology_passes() {
+    assert_eq!(
+        check(&common::ping_pong_models(2), &common::ping_pong_connectors(2)),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_connector_to_unknown_model() {
+    let mut connectors = common::ping_pong_connectors(2);
+    connectors.push(Connector::new(
+        String::from("p2 to p99"),
+        String::from("player-02"),
+        String::from("player-99"),
+        String::from("send"),
+        String::from("receive"),
+    ));
+    let expected = Err(CheckError::UnknownModel {
+        connector_id: String::from("p2 to p99"),
+        model_id: String::from("player-99"),
+    });
+    assert_eq!(check(&common::ping_pong_models(2), &connectors), expected);
+
+    let simulation = Simulation::post(common::ping_pong_models(2), connectors);
+    assert_eq!(simulation.check_topology(), expected);
+}
+
+#[test]
+fn test_duplicate_model_id() {
+    let mut models = common::ping_pong_models(2);
+    models.extend(common::ping_pong_models(1).into_iter().take(1));
+    assert_eq!(
+        check(&models, &common::ping_pong_connectors(2)),
+        Err(CheckError::DuplicateModel {
+            model_id: String::from("player-01"),
+        })
+    );
+}
+
+#[test]
+fn test_connector_to_misspelled_port() {
+    let mut connectors = common::ping_pong_connectors(2);
+    connectors.pop();
+    connectors.push(Connector::new(
+        String::from("player-02 to Store"),
+        String::from("player-02"),
+        String::from("Store"),
+        String::from("send"),
+        String::from("recieve"),
+    ));
+    assert_eq!(
+        check(&common::ping_pong_models(2), &connectors),
+        Err(CheckError::UnknownPort {
+            connector_id: String::from("player-02 to Store"),
+            model_id: String::from("Store"),
+            port: String::from("recieve"),
+        })
+    );
+}
+
+#[test]
+fn test_connector_into_source_rejected() {
+    // a source has no input ports at all.
+    let models = vec![Model::new(
+        String::from("arrivals"),
+        Box::new(Source::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            None,
+            String::from("job"),
+            false,
+            None,
+        )),
+    )];
+    let connectors = vec![Connector::new(
+        String::from("arrivals to arrivals"),
+        String::from("arrivals"),
+        String::from("arrivals"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    assert_eq!(
+        check(&models, &connectors),
+        Err(CheckError::UnknownPort {
+            connector_id: String::from("arrivals to arrivals"),
+            model_id: String::from("arrivals"),
+            port: String::from("job"),
+        })
+    );
+}
+
+#[test]
+fn test_connector_out_of_sink_rejected() {
+    let models = vec![
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+        Model::new(String::from("archive"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("exit to archive"),
+        String::from("exit"),
+        String::from("archive"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    assert_eq!(
+        check(&models, &connectors),
+        Err(CheckError::UnknownPort {
+            connector_id: String::from("exit to archive"),
+            model_id: String::from("exit"),
+            port: String::from("job"),
+        })
+    );
+}
+
+#[test]
+fn test_non_positive_gamma_shape_rejected() {
+    let models = vec![Model::new(
+        String::from("repair"),
+        Box::new(Station::new(
+            ContinuousDistribution::Gamma {
+                shape: -1.0,
+                scale: 1.0,
+            },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        )),
+    )];
+    assert_eq!(
+        check_models(&models),
+        Err(CheckError::InvalidModel {
+            model_id: String::from("repair"),
+            reason: String::from("shape and scale must be positive, got shape -1 and scale 1"),
+        })
+    );
+}
+
+#[test]
+fn test_negative_processor_lambda_rejected() {
+    let models = vec![Model::new(
+        String::from("player-01"),
+        Box::new(Processor::new(
+            ContinuousRandomVariable::Exp { lambda: -1.0 },
+            None,
+            String::from("receive"),
+            String::from("send"),
+            false,
+            None,
+        )),
+    )];
+    assert_eq!(
+        check_models(&models),
+        Err(CheckError::InvalidModel {
+            model_id: String::from("player-01"),
+            reason: String::from("serviceTime: exp lambda must be positive, got -1"),
+        })
+    );
+}
+
+#[test]
+fn test_zero_station_lambda_rejected() {
+    let models = vec![Model::new(
+        String::from("server"),
+        Box::new(Station::new(
+            ContinuousDistribution::Exp { lambda: 0.0 },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        )),
+    )];
+    assert!(matches!(
+        check_models(&models),
+        Err(CheckError::InvalidModel { model_id, reason })
+            if model_id == "server" && reason.contains("lambda")
+    ));
+}
+
+#[test]
+fn test_ping_pong_ring_is_a_cycle() {
+    let simulation = Simulation::post(common::ping_pong_models(3), common::ping_pong_connectors(3));
+    let ring = vec![
+        String::from("player-01"),
+        String::from("player-02"),
+        String::from("player-03"),
+    ];
+    assert_eq!(simulation.cycles(), vec![ring.clone()]);
+    assert_eq!(
+        simulation.check_acyclic(),
+        Err(CheckError::Cycle { model_ids: ring })
+    );
+}
+
+#[test]
+fn test_linear_pipeline_is_acyclic() {
+    let connectors: Vec<Connector> = ["a", "b", "c"]
+        .windows(2)
+        .map(|pair| {
+            Connector::new(
+                format!("{} to {}", pair[0], pair[1]),
+                pair[0].to_string(),
+                pair[1].to_string(),
+                String::from("send"),
+                String::from("receive"),
+            )
+        })
+        .collect();
+    assert!(cycles(&connectors).is_empty());
+    assert_eq!(check_acyclic(&connectors), Ok(()));
+}
+
+#[test]
+fn test_orphan_processor_reported() {
+    let mut models = common::ping_pong_models(2);
+    models.push(Model::new(
+        String::from("player-99"),
+        Box::new(Processor::new(
+            ContinuousRandomVariable::Exp { lambda: 0.9 },
+            None,
+            String::from("receive"),
+            String::from("send"),
+            false,
+            None,
+        )),
+    ));
+    let connectors = common::ping_pong_connectors(2);
+    // a warning only, unless asked for.
+    assert_eq!(check(&models, &connectors), Ok(()));
+
+    let found = orphans(&models, &connectors);
+    assert!(found.contains(&Orphan {
+        model_id: String::from("player-99"),
+        no_incoming: true,
+        no_outgoing: true,
+    }));
+    assert!(found.iter().all(|orphan| !orphan.model_id.starts_with("player-0")));
+    assert_eq!(
+        check_connected(&models, &connectors),
+        Err(CheckError::Orphan(found[0].clone()))
+    );
+    assert_eq!(
+        CheckError::Orphan(found.last().unwrap().clone()).to_string(),
+        "model 'player-99' has no connectors at all"
+    );
+
+    let simulation = Simulation::post(models, connectors);
+    assert_eq!(simulation.orphans(), found);
+}
@@ -0,0 +1,112 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::models::{Classifier, Concrete, Condition, Sink};
+use sim_dag::payload;
+use sim_dag::simulation::SimulationExt;
+
+fn to_classifier(content: &str) -> Message {
+    Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "triage".to_string(),
+        "job".to_string(),
+        0.0,
+        content.to_string(),
+    )
+}
+
+fn connector(source_port: &str, target_id: &str) -> Connector {
+    Connector::new(
+        format!("triage to {}", target_id),
+        String::from("triage"),
+        target_id.to_string(),
+        source_port.to_string(),
+        String::from("job"),
+    )
+}
+
+fn subjects<'a>(simulation: &'a Simulation, id: &str) -> Vec<&'a str> {
+    simulation.get_models()[id]
+        .records()
+        .iter()
+        .map(|record| record.subject.as_str())
+        .collect()
+}
+
+#[test]
+fn test_jobs_exit_the_port_of_their_tag() {
+    let models = vec![
+        Model::new(
+            String::from("triage"),
+            Box::new(Classifier::new(
+                String::from("job"),
+                String::from("priority"),
+                vec![
+                    (Condition::Equals(String::from("high")), String::from("left")),
+                    (Condition::Equals(String::from("low")), String::from("right")),
+                ],
+                String::from("other"),
+                false,
+            )),
+        ),
+        Model::new(String::from("urgent"), Box::new(Sink::new(String::from("job"), false))),
+        Model::new(String::from("routine"), Box::new(Sink::new(String::from("job"), false))),
+        Model::new(String::from("unknown"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        connector("left", "urgent"),
+        connector("right", "routine"),
+        connector("other", "unknown"),
+    ];
+    let mut simulation = Simulation::post(models, connectors);
+    let high = payload::stamp("priority=high job 1", 0.0);
+    let json = payload::encode(&serde_json::json!({ "priority": "low", "job": 3 })).unwrap();
+    for content in [high.as_str(), "priority=low job 2", json.as_str(), "job 4", "priority=high job 5"] {
+        simulation.inject_input(to_classifier(content));
+    }
+    simulation.run_to_completion().unwrap();
+
+    assert_eq!(subjects(&simulation, "urgent"), vec![high.as_str(), "priority=high job 5"]);
+    assert_eq!(subjects(&simulation, "routine"), vec!["priority=low job 2", json.as_str()]);
+    assert_eq!(subjects(&simulation, "unknown"), vec!["job 4"]);
+    let models = simulation.get_models();
+    let classifier = models["triage"].as_concrete::<Classifier>().unwrap();
+    assert_eq!(classifier.rule_counts(), vec![("left", 2), ("right", 2)]);
+    assert_eq!(classifier.unmatched(), 1);
+    assert_eq!(models["triage"].status(), "Classified left: 2, right: 2, default other: 1");
+}
+
+#[test]
+fn test_numeric_thresholds() {
+    let cases = [("size=3", "small"), ("size=12.5", "large"), ("size=7", "medium"), ("size=big", "medium")];
+    let classifier = Classifier::new(
+        String::from("job"),
+        String::from("size"),
+        vec![
+            (Condition::LessThan(5.0), String::from("small")),
+            (Condition::GreaterThan(10.0), String::from("large")),
+        ],
+        String::from("medium"),
+        false,
+    );
+    let models = vec![
+        Model::new(String::from("triage"), Box::new(classifier)),
+        Model::new(String::from("small"), Box::new(Sink::new(String::from("job"), false))),
+        Model::new(String::from("large"), Box::new(Sink::new(String::from("job"), false))),
+        Model::new(String::from("medium"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        connector("small", "small"),
+        connector("large", "large"),
+        connector("medium", "medium"),
+    ];
+    let mut simulation = Simulation::post(models, connectors);
+    cases
+        .iter()
+        .for_each(|(content, _)| simulation.inject_input(to_classifier(content)));
+    simulation.run_to_completion().unwrap();
+
+    for (content, sink) in cases {
+        assert!(subjects(&simulation, sink).contains(&content), "{} should reach {}", content, sink);
+    }
+}
@@ -0,0 +1,73 @@
+//! Shared fixtures for the integration tests.
+#![allow(dead_code)]
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Model, Processor, Storage};
+use sim::simulator::{Connector, Message};
+
+/// The models of an `n` player ping-pong ring whose last player also reports
+/// each round trip to a `Store`, as built by the `sim_dag` binary.
+pub fn ping_pong_models(num_players: usize) -> Vec<Model> {
+    let mut models: Vec<_> = (0..num_players)
+        .map(|i| {
+            Model::new(
+                format!("player-{:02}", i + 1),
+                Box::new(Processor::new(
+                    ContinuousRandomVariable::Exp { lambda: 0.9 },
+                    None,
+                    String::from("receive"),
+                    String::from("send"),
+                    false,
+                    None,
+                )),
+            )
+        })
+        .collect();
+    models.push(Model::new(
+        "Store".to_string(),
+        Box::new(Storage::new(
+            "put".to_string(),
+            "get".to_string(),
+            "stored".to_string(),
+            true,
+        )),
+    ));
+    models
+}
+
+pub fn ping_pong_connectors(num_players: usize) -> Vec<Connector> {
+    let mut connectors: Vec<_> = (0..num_players)
+        .map(|i| {
+            let source_player = format!("player-{:02}", i + 1);
+            let target_player = format!("player-{:02}", (i + 1) % num_players + 1);
+            Connector::new(
+                format!("{} to {}", source_player, target_player),
+                source_player,
+                target_player,
+                String::from("send"),
+                String::from("receive"),
+            )
+        })
+        .collect();
+    let source_player = format!("player-{:02}", num_players);
+    connectors.push(Connector::new(
+        format!("{} to Store", source_player),
+        source_player,
+        String::from("Store"),
+        String::from("send"),
+        String::from("put"),
+    ));
+    connectors
+}
+
+/// The 'ball' that starts the ping-pong rally.
+pub fn ball() -> Message {
+    Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "player-01".to_string(),
+        "receive".to_string(),
+        0.0,
+        "Ball".to_string(),
+    )
+}
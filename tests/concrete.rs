@@ -0,0 +1,45 @@
+mod common;
+
+use sim::models::{Model, Storage};
+use sim::simulator::{Message, Simulation};
+use sim_dag::models::{Buffer, Concrete, Overflow, Sink};
+
+#[test]
+fn test_buffer_occupancy_through_downcast() {
+    let models = vec![Model::new(
+        String::from("buffer"),
+        Box::new(Buffer::new(
+            String::from("put"),
+            String::from("get"),
+            String::from("stored"),
+            Some(2),
+            Overflow::Reject,
+            false,
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    (0..3).for_each(|i| {
+        simulation.inject_input(Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("buffer"),
+            String::from("put"),
+            0.0,
+            format!("item-{}", i),
+        ))
+    });
+    simulation.step().unwrap();
+
+    let buffer: &Buffer = simulation.get_models()["buffer"].as_concrete().unwrap();
+    assert_eq!(buffer.occupancy(), 2);
+    assert_eq!(buffer.overflows(), 1);
+}
+
+#[test]
+fn test_downcast_checks_model_type() {
+    let simulation = Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    let store = &simulation.get_models()["Store"];
+    assert!(store.as_concrete::<Storage>().is_some());
+    assert!(store.as_concrete::<Buffer>().is_none());
+    assert!(store.as_concrete::<Sink>().is_none());
+}
@@ -0,0 +1,137 @@
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model, Processor};
+use sim::simulator::{Connector, Simulation};
+use sim::utils::errors::SimulationError;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Source, Station};
+use sim_dag::session::Session;
+use sim_dag::simulation::SimulationExt;
+
+/// A source feeding a station whose output is not connected to anything.
+fn dangling_line(service_time: f64) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Constant { value: 1.0 },
+                Some(3),
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("desk"),
+            Box::new(Station::new(
+                ContinuousDistribution::Constant {
+                    value: service_time,
+                },
+                None,
+                1,
+                String::from("job"),
+                String::from("done"),
+                false,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("arrivals to desk"),
+        String::from("arrivals"),
+        String::from("desk"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    Simulation::post_with_seed(models, connectors, 96)
+}
+
+#[test]
+fn test_unconnected_port_fills_dead_letters() {
+    let mut session = Session::new(dangling_line(0.5));
+    session.enable_dead_letters(false).unwrap();
+    let messages = session.run_to_completion().unwrap();
+
+    assert!(messages.iter().all(|message| message.target_id() == "desk"));
+    let dead_letters = session.dead_letters();
+    assert_eq!(dead_letters.len(), 3);
+    for (index, message) in dead_letters.iter().enumerate() {
+        assert_eq!(message.source_id(), "desk");
+        assert_eq!(message.source_port(), "done");
+        assert_eq!(message.target_id(), "");
+        assert_eq!(message.time(), index as f64 + 0.5);
+    }
+    // the run itself is the same as without dead letters.
+    let mut unwatched = Session::new(dangling_line(0.5));
+    unwatched.run_to_completion().unwrap();
+    assert_eq!(session.simulation().get_models().len(), 2);
+    assert!(session.simulation().diff(unwatched.simulation()).is_empty());
+}
+
+#[test]
+fn test_strict_mode_fails_on_first_dead_letter() {
+    let mut session = Session::new(dangling_line(0.5));
+    session.enable_dead_letters(true).unwrap();
+    assert!(matches!(session.run_to_completion(), Err(SimulationError::PortNotFound)));
+    assert_eq!(session.dead_letters().len(), 1);
+    assert_eq!(session.time(), 0.5);
+
+    let mut session = Session::new(dangling_line(0.5));
+    session.run_to_completion().unwrap();
+    assert!(session.dead_letters().is_empty());
+}
+
+#[test]
+fn test_strict_failure_still_counts_routed_messages() {
+    // at time 1 the desk finishes the first job as the second one arrives.
+    let mut session = Session::new(dangling_line(1.0));
+    session.enable_dead_letters(true).unwrap();
+    session.enable_traffic();
+    session.step().unwrap();
+    assert!(matches!(session.step(), Err(SimulationError::PortNotFound)));
+    assert_eq!(session.dead_letters().len(), 1);
+    assert_eq!(session.events_processed(), 2);
+    assert_eq!(session.traffic().unwrap()["arrivals to desk"], 2);
+}
+
+#[test]
+fn test_builtin_model_fills_dead_letters() {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 1.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("processor"),
+            Box::new(Processor::new(
+                ContinuousRandomVariable::Exp { lambda: 2.0 },
+                None,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("generator to processor"),
+        String::from("generator"),
+        String::from("processor"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut session = Session::new(Simulation::post_with_seed(models, connectors, 96));
+    session.enable_dead_letters(false).unwrap();
+    session.step_until(20.0).unwrap();
+
+    let dead_letters = session.dead_letters();
+    assert!(!dead_letters.is_empty());
+    assert!(dead_letters.iter().all(|message| {
+        message.source_id() == "processor" && message.source_port() == "processed job"
+    }));
+}
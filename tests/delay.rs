@@ -0,0 +1,183 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::input_modeling::dynamic_rng::{DynRng, dyn_rng};
+use sim::models::{Generator, Model, Reportable, Storage};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Delay, Sink};
+use sim_dag::session::Session;
+
+#[test]
+fn test_constant_delay_offsets_arrivals() {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 0.5 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("conveyor"),
+            Box::new(Delay::new(
+                ContinuousDistribution::Constant { value: 5.0 },
+                String::from("job"),
+                String::from("delayed job"),
+                true,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("Store"),
+            Box::new(Storage::new(
+                String::from("put"),
+                String::from("get"),
+                String::from("stored"),
+                true,
+            )),
+        ),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("generator to conveyor"),
+            String::from("generator"),
+            String::from("conveyor"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("conveyor to Store"),
+            String::from("conveyor"),
+            String::from("Store"),
+            String::from("delayed job"),
+            String::from("put"),
+        ),
+    ];
+
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.step_until(100.0).unwrap();
+
+    let records = simulation.get_models().get("conveyor").unwrap().records();
+    let arrivals: Vec<_> = records.iter().filter(|r| r.action == "Arrival").collect();
+    let departures: Vec<_> = records.iter().filter(|r| r.action == "Departure").collect();
+    assert!(!departures.is_empty());
+    arrivals.iter().zip(departures.iter()).for_each(|(arrival, departure)| {
+        assert_eq!(arrival.subject, departure.subject);
+        assert!((departure.time - arrival.time - 5.0).abs() < 1e-9);
+    });
+}
+
+#[test]
+fn test_shorter_draw_does_not_overtake() {
+    let delay_time = ContinuousDistribution::Exp { lambda: 0.5 };
+    let rng = |seed: u64| -> DynRng { dyn_rng(StdRng::seed_from_u64(seed)) };
+    let draws = |seed: u64| {
+        let rng = rng(seed);
+        let first = delay_time.random_variate(rng.clone()).unwrap();
+        (first, delay_time.random_variate(rng).unwrap())
+    };
+    // a seed whose second draw is clearly the shorter one.
+    let seed = (0..)
+        .find(|seed| {
+            let (first, second) = draws(*seed);
+            first > second + 1.0 && first < 50.0
+        })
+        .unwrap();
+    let (first_delay, second_delay) = draws(seed);
+
+    let models = vec![Model::new(
+        String::from("conveyor"),
+        Box::new(Delay::new(
+            delay_time.clone(),
+            String::from("job"),
+            String::from("delayed job"),
+            true,
+            Some(rng(seed)),
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    for content in ["first", "second"] {
+        simulation.inject_input(Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("conveyor"),
+            String::from("job"),
+            0.0,
+            String::from(content),
+        ));
+    }
+    simulation.step_until(100.0).unwrap();
+
+    let records = simulation.get_models().get("conveyor").unwrap().records();
+    let departures: Vec<(&str, f64)> = records
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .map(|record| (record.subject.as_str(), record.time))
+        .collect();
+    assert!(second_delay < first_delay);
+    // the second job waits for the first instead of leaving at its own draw.
+    assert_eq!(departures.len(), 2);
+    assert_eq!(departures[0].0, "first");
+    assert_eq!(departures[1].0, "second");
+    assert!((departures[0].1 - first_delay).abs() < 1e-9);
+    assert!((departures[1].1 - first_delay).abs() < 1e-9);
+}
+
+#[test]
+fn test_large_payload_survives_chain() {
+    let hops = 10;
+    let mut models: Vec<Model> = (0..hops)
+        .map(|i| {
+            Model::new(
+                format!("hop-{}", i),
+                Box::new(Delay::new(
+                    ContinuousDistribution::Constant { value: 1.0 },
+                    String::from("job"),
+                    String::from("delayed job"),
+                    false,
+                    None,
+                )),
+            )
+        })
+        .collect();
+    models.push(Model::new(
+        String::from("exit"),
+        Box::new(Sink::new(String::from("job"), false)),
+    ));
+    let connectors = (0..hops)
+        .map(|i| {
+            let target = if i + 1 == hops {
+                String::from("exit")
+            } else {
+                format!("hop-{}", i + 1)
+            };
+            Connector::new(
+                format!("hop-{} to {}", i, target),
+                format!("hop-{}", i),
+                target,
+                String::from("delayed job"),
+                String::from("job"),
+            )
+        })
+        .collect();
+    let payload: String = (0..100_000).map(|i| char::from(b'a' + (i % 26) as u8)).collect();
+    let mut session = Session::new(Simulation::post(models, connectors));
+    session.inject_input(Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from("hop-0"),
+        String::from("job"),
+        0.0,
+        payload.clone(),
+    ));
+    session.step_until(hops as f64 + 1.0).unwrap();
+
+    let records = session.simulation().get_models().get("exit").unwrap().records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].time, hops as f64);
+    assert_eq!(records[0].subject, payload);
+}
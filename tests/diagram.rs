@@ -0,0 +1,137 @@
+mod common;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::diagram::{Diagram, RenderError};
+use sim_dag::session::Session;
+
+#[test]
+fn test_mermaid_ping_pong_ring() {
+    let simulation = Simulation::post(common::ping_pong_models(3), common::ping_pong_connectors(3));
+    let graph = simulation.generate_mermaid_graph();
+
+    assert!(graph.starts_with("graph LR\n"));
+    assert!(graph.contains("m_player_01[\"player-01\"]"));
+    assert!(graph.contains("m_Store[\"Store\"]"));
+    assert!(graph.contains("m_player_01 -->|\"player-01 to player-02: send -> receive\"| m_player_02"));
+    assert!(graph.contains("m_player_03 -->|\"player-03 to Store: send -> put\"| m_Store"));
+    assert_eq!(graph.matches("-->").count(), 4);
+}
+
+#[test]
+fn test_dot_edges_attach_to_port_cells() {
+    let simulation = Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    let graph = simulation.generate_port_dot_graph();
+
+    assert!(graph.starts_with("digraph {\n"));
+    assert!(graph.contains("node [shape=record];"));
+    assert!(graph.contains("m_player_01 [label=\"{{<in_0> receive}|player-01|{<out_0> send}}\"];"));
+    assert!(graph.contains("m_player_01:out_0 -> m_player_02:in_0 [label=\"send -> receive\"];"));
+    assert!(graph.contains("m_player_02:out_0 -> m_Store:"));
+    assert!(graph.contains("[label=\"send -> put\"];"));
+    assert_eq!(graph.matches(" -> m_").count(), 3);
+}
+
+#[test]
+fn test_dot_clusters_by_model_type() {
+    let mut models = common::ping_pong_models(2);
+    models.push(Model::new(
+        String::from("serve"),
+        Box::new(Generator::new(
+            ContinuousRandomVariable::Exp { lambda: 1.0 },
+            None,
+            String::from("job"),
+            false,
+            None,
+        )),
+    ));
+    let mut connectors = common::ping_pong_connectors(2);
+    connectors.push(Connector::new(
+        String::from("serve to player-01"),
+        String::from("serve"),
+        String::from("player-01"),
+        String::from("job"),
+        String::from("receive"),
+    ));
+    let simulation = Simulation::post(models, connectors);
+    let graph = simulation.generate_dot_graph_clustered();
+
+    for model_type in ["Generator", "Processor", "Storage"] {
+        assert!(graph.contains(&format!(
+            "    subgraph cluster_{} {{\n        label=\"{}\";\n",
+            model_type, model_type
+        )));
+    }
+    let processors = graph.split("subgraph cluster_Processor").nth(1).unwrap();
+    let processors = &processors[..processors.find("    }\n").unwrap()];
+    assert!(processors.contains("m_player_01 [label="));
+    assert!(processors.contains("m_player_02 [label="));
+    assert!(!processors.contains("m_Store"));
+    assert!(graph.contains("m_serve:out_0 -> m_player_01:in_0 [label=\"job -> receive\"];"));
+}
+
+#[test]
+fn test_dot_edges_labeled_with_traffic() {
+    let mut connectors = common::ping_pong_connectors(2);
+    connectors.push(Connector::new(
+        String::from("Store to player-01"),
+        String::from("Store"),
+        String::from("player-01"),
+        String::from("stored"),
+        String::from("receive"),
+    ));
+    let mut simulation = Simulation::post(common::ping_pong_models(2), connectors);
+    simulation.inject_input(common::ball());
+    let mut session = Session::new(simulation);
+    session.enable_traffic();
+    session.step_until(100.0).unwrap();
+    let traffic = session.traffic().unwrap().clone();
+
+    let count = |label: &str| -> usize {
+        let start = label.rfind('(').unwrap() + 1;
+        label[start..label.rfind(')').unwrap()].parse().unwrap()
+    };
+    let graph = session.simulation().generate_dot_graph_with_traffic(&traffic);
+    let edges: Vec<&str> = graph.lines().filter(|line| line.contains(" -> m_")).collect();
+    assert_eq!(edges.len(), 4);
+    let ring: usize = edges
+        .iter()
+        .filter(|edge| edge.contains("send -> receive"))
+        .map(|edge| count(edge))
+        .sum();
+    let round_trips = traffic["player-02 to Store"];
+    assert!(round_trips > 0);
+    // the run may stop between the two halves of a round trip.
+    assert!(ring == 2 * round_trips || ring == 2 * round_trips + 1);
+
+    let idle = edges.iter().find(|edge| edge.starts_with("    m_Store")).unwrap();
+    assert!(idle.contains("[label=\"stored -> receive (0)\", style=dashed];"));
+    assert_eq!(graph.matches("style=dashed").count(), 1);
+
+    let mermaid = session.simulation().generate_mermaid_graph_with_traffic(&traffic);
+    assert!(mermaid.contains("m_Store -.->|\"Store to player-01: stored -> receive (0)\"| m_player_01"));
+    assert!(mermaid.contains(&format!(
+        "m_player_02 -->|\"player-02 to Store: send -> put ({})\"| m_Store",
+        round_trips
+    )));
+}
+
+#[test]
+fn test_render_ping_pong_svg() {
+    let simulation = Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    let path = std::env::temp_dir().join("sim_dag_ping_pong.svg");
+    match simulation.render_svg(&path) {
+        Ok(()) => {
+            let svg = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert!(svg.contains("<svg"));
+            for id in ["player-01", "player-02", "Store"] {
+                assert!(svg.contains(&format!(">{}</text>", id)), "{}", id);
+            }
+        }
+        // nothing to check without Graphviz installed.
+        Err(RenderError::GraphvizUnavailable(_)) => {}
+        Err(err) => panic!("{}", err),
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use sim::simulator::Simulation;
+use sim_dag::diff::StateDifference;
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_clones_do_not_differ() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 69);
+    simulation.inject_input(common::ball());
+    simulation.step_n(5).unwrap();
+    assert_eq!(simulation.diff(&simulation.clone()), Vec::new());
+}
+
+#[test]
+fn test_diff_pinpoints_extra_step() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 69);
+    simulation.inject_input(common::ball());
+    simulation.step().unwrap();
+    let mut ahead = simulation.clone();
+    ahead.step().unwrap();
+
+    let differences = simulation.diff(&ahead);
+    assert!(matches!(
+        differences.first(),
+        Some(StateDifference::Clock { left, right }) if left < right
+    ));
+    let changed: Vec<&str> = differences
+        .iter()
+        .filter_map(|difference| match difference {
+            StateDifference::Model { model_id, .. } => Some(model_id.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(changed.contains(&"player-01"), "{:?}", differences);
+    assert!(!changed.contains(&"Store"), "{:?}", differences);
+    assert!(differences
+        .iter()
+        .any(|difference| matches!(difference, StateDifference::PendingMessage { .. })));
+    assert!(!differences
+        .iter()
+        .any(|difference| matches!(difference, StateDifference::MissingModel { .. })));
+}
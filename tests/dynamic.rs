@@ -0,0 +1,186 @@
+mod common;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model, Processor};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::checker::CheckError;
+use sim_dag::simulation::SimulationExt;
+
+fn player(id: &str) -> Model {
+    Model::new(
+        String::from(id),
+        Box::new(Processor::new(
+            ContinuousRandomVariable::Exp { lambda: 0.9 },
+            None,
+            String::from("receive"),
+            String::from("send"),
+            false,
+            None,
+        )),
+    )
+}
+
+fn connector(source_id: &str, target_id: &str) -> Connector {
+    Connector::new(
+        format!("{} to {}", source_id, target_id),
+        String::from(source_id),
+        String::from(target_id),
+        String::from("send"),
+        String::from("receive"),
+    )
+}
+
+#[test]
+fn test_player_joins_running_rally() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 4);
+    simulation.inject_input(common::ball());
+    simulation.step_n(20).unwrap();
+    let time = simulation.time();
+    let pending = serde_json::to_string(simulation.get_messages()).unwrap();
+
+    simulation.add_model(player("player-03")).unwrap();
+    simulation.add_connector(connector("player-02", "player-03")).unwrap();
+    assert_eq!(simulation.time(), time);
+    assert_eq!(serde_json::to_string(simulation.get_messages()).unwrap(), pending);
+
+    let messages = simulation.step_n(20).unwrap();
+    assert!(messages.iter().any(|message| message.target_id() == "player-03"));
+}
+
+#[test]
+fn test_additions_keep_the_rng_stream() {
+    let mut extended =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 4);
+    let mut untouched =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 4);
+    extended.inject_input(common::ball());
+    untouched.inject_input(common::ball());
+    extended.step_n(20).unwrap();
+    untouched.step_n(20).unwrap();
+
+    extended.add_model(player("player-03")).unwrap();
+    extended.add_connector(connector("player-03", "player-01")).unwrap();
+    assert_eq!(
+        serde_json::to_string(&extended.step_n(20).unwrap()).unwrap(),
+        serde_json::to_string(&untouched.step_n(20).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_invalid_additions_are_rejected() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 4);
+    assert_eq!(
+        simulation.add_model(player("player-01")),
+        Err(CheckError::DuplicateModel {
+            model_id: String::from("player-01")
+        })
+    );
+    assert_eq!(
+        simulation.add_connector(connector("player-01", "player-02")),
+        Err(CheckError::DuplicateConnector {
+            connector_id: String::from("player-01 to player-02")
+        })
+    );
+    assert_eq!(
+        simulation.add_connector(connector("player-02", "player-09")),
+        Err(CheckError::UnknownModel {
+            connector_id: String::from("player-02 to player-09"),
+            model_id: String::from("player-09"),
+        })
+    );
+    assert!(!simulation.get_models().contains_key("player-09"));
+}
+
+#[test]
+fn test_faster_service_after_parameter_update() {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 4.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("processor"),
+            Box::new(Processor::new(
+                ContinuousRandomVariable::Exp { lambda: 1.0 },
+                None,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("generator to processor"),
+        String::from("generator"),
+        String::from("processor"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 76);
+    let processed = |messages: &[Message]| {
+        messages
+            .iter()
+            .filter(|message| message.source_id() == "processor")
+            .count()
+    };
+    let before = processed(&simulation.step_until(50.0).unwrap());
+    let pending = serde_json::to_string(simulation.get_messages()).unwrap();
+
+    simulation
+        .update_model_param(
+            "processor",
+            "serviceTime",
+            ContinuousRandomVariable::Exp { lambda: 2.0 },
+        )
+        .unwrap();
+    assert_eq!(serde_json::to_string(simulation.get_messages()).unwrap(), pending);
+    let after = processed(&simulation.step_until(100.0).unwrap());
+    assert!(after as f64 > 1.5 * before as f64);
+
+    assert_eq!(
+        simulation.update_model_param("processor", "serviceSpeed", 2.0),
+        Err(CheckError::InvalidModel {
+            model_id: String::from("processor"),
+            reason: String::from("no parameter 'serviceSpeed'"),
+        })
+    );
+    assert_eq!(
+        simulation.update_model_param("nowhere", "serviceTime", 2.0),
+        Err(CheckError::MissingModel {
+            model_id: String::from("nowhere")
+        })
+    );
+}
+
+#[test]
+fn test_parameter_update_keeps_the_rng_stream() {
+    let mut updated =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 76);
+    let mut untouched =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 76);
+    updated.inject_input(common::ball());
+    untouched.inject_input(common::ball());
+    updated.step_n(20).unwrap();
+    untouched.step_n(20).unwrap();
+
+    updated
+        .update_model_param(
+            "player-01",
+            "serviceTime",
+            ContinuousRandomVariable::Exp { lambda: 0.9 },
+        )
+        .unwrap();
+    assert_eq!(
+        serde_json::to_string(&updated.step_n(20).unwrap()).unwrap(),
+        serde_json::to_string(&untouched.step_n(20).unwrap()).unwrap()
+    );
+}
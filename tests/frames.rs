@@ -0,0 +1,42 @@
+mod common;
+
+use sim::simulator::Simulation;
+use sim_dag::simulation::{SimulationExt, StopCondition};
+
+#[test]
+fn test_frames_at_fixed_intervals() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 81);
+    simulation.inject_input(common::ball());
+    let frames = simulation
+        .run_with_frames(&StopCondition::EndTime(100.0), 10.0)
+        .unwrap();
+
+    assert_eq!(frames.len(), 11);
+    let times: Vec<f64> = frames.iter().map(|frame| frame.time).collect();
+    assert_eq!(times, (0..=10).map(|i| i as f64 * 10.0).collect::<Vec<_>>());
+    let store_records: Vec<usize> = frames
+        .iter()
+        .map(|frame| {
+            assert_eq!(frame.models.len(), 3);
+            frame.models[0].records
+        })
+        .collect();
+    assert_eq!(frames[0].models[0].id, "Store");
+    assert!(store_records.is_sorted());
+    assert!(store_records.last().unwrap() > &0);
+
+    let json = serde_json::to_value(&frames[5]).unwrap();
+    assert_eq!(json["time"], 50.0);
+    assert_eq!(json["models"][1]["id"], "player-01");
+    assert!(json["models"][1]["status"].is_string());
+}
+
+#[test]
+fn test_frames_need_a_positive_interval() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 81);
+    assert!(simulation
+        .run_with_frames(&StopCondition::EndTime(100.0), 0.0)
+        .is_err());
+}
@@ -0,0 +1,49 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Message, Simulation};
+use sim_dag::models::Gate;
+
+fn to_gate(port: &str, content: &str) -> Message {
+    Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "signal".to_string(),
+        port.to_string(),
+        0.0,
+        content.to_string(),
+    )
+}
+
+#[test]
+fn test_held_jobs_released_in_order_on_open() {
+    let models = vec![Model::new(
+        String::from("signal"),
+        Box::new(Gate::new(
+            String::from("job"),
+            String::from("control"),
+            String::from("released"),
+            true,
+            true,
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    simulation.inject_input(to_gate("control", "close"));
+    ["car-1", "car-2", "car-3"]
+        .iter()
+        .for_each(|car| simulation.inject_input(to_gate("job", car)));
+    simulation.step().unwrap();
+    simulation.inject_input(to_gate("control", "open"));
+    simulation.step_n(2).unwrap();
+
+    let records = simulation.get_models().get("signal").unwrap().records();
+    let opened_at = records.iter().position(|r| r.action == "Open").unwrap();
+    let released: Vec<&str> = records
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.action == "Release")
+        .map(|(i, r)| {
+            assert!(i > opened_at);
+            r.subject.as_str()
+        })
+        .collect();
+    assert_eq!(released, vec!["car-1", "car-2", "car-3"]);
+}
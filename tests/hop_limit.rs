@@ -0,0 +1,101 @@
+mod common;
+
+use sim::models::Reportable;
+use sim::simulator::{Message, Simulation};
+use sim_dag::session::Session;
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_ball_expires_after_max_hops() {
+    let simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 78);
+    let mut session = Session::new(simulation);
+    session.enable_hop_limit(10);
+    session.inject_input(common::ball());
+    let messages = session.run_to_completion().unwrap();
+
+    // player-02 sends each of its 5 passes to the Store too, at the same hop.
+    let passes = messages
+        .iter()
+        .filter(|message| message.target_port() == "receive")
+        .count();
+    assert_eq!(passes, 10);
+    let store = session.simulation().get_models().get("Store").unwrap();
+    assert_eq!(store.records().len(), 5);
+
+    let expired = session.expired();
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].source_id(), "player-01");
+    assert_eq!(expired[0].target_id(), "player-02");
+    assert!(session.simulation().is_exhausted());
+}
+
+#[test]
+fn test_no_hop_limit_by_default() {
+    let simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 78);
+    let mut session = Session::new(simulation);
+    session.inject_input(common::ball());
+    let messages = session.step_n(50).unwrap();
+    assert!(messages.len() > 10);
+    assert!(session.expired().is_empty());
+    assert!(!session.simulation().is_exhausted());
+}
+
+#[test]
+fn test_injected_message_starts_at_zero_hops() {
+    let simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 78);
+    let mut session = Session::new(simulation);
+    session.enable_hop_limit(10);
+    session.inject_input(common::ball());
+    session.run_to_completion().unwrap();
+    assert_eq!(session.expired().len(), 1);
+
+    // player-01 sent the expired pass, but a new ball owes it nothing.
+    session.inject_input(common::ball());
+    let messages = session.run_to_completion().unwrap();
+    let passes = messages
+        .iter()
+        .filter(|message| message.target_port() == "receive")
+        .count();
+    assert_eq!(passes, 10);
+    assert_eq!(session.expired().len(), 2);
+}
+
+#[test]
+fn test_concurrent_balls_count_their_own_hops() {
+    let simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 78);
+    let mut session = Session::new(simulation);
+    session.enable_hop_limit(10);
+    let ball = |content: &str, player: &str| {
+        Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from(player),
+            String::from("receive"),
+            0.0,
+            String::from(content),
+        )
+    };
+    session
+        .inject_inputs([ball("Ball A", "player-01"), ball("Ball B", "player-02")])
+        .unwrap();
+    let messages = session.run_to_completion().unwrap();
+
+    for content in ["Ball A", "Ball B"] {
+        let passes = messages
+            .iter()
+            .filter(|message| message.target_port() == "receive" && message.content() == content)
+            .count();
+        assert_eq!(passes, 10);
+    }
+    let mut expired: Vec<&str> = session
+        .expired()
+        .iter()
+        .map(|message| message.content())
+        .collect();
+    expired.sort();
+    assert_eq!(expired, ["Ball A", "Ball B"]);
+}
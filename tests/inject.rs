@@ -0,0 +1,106 @@
+mod common;
+
+use sim::models::Model;
+use sim::simulator::{Message, Simulation};
+use sim_dag::checker::CheckError;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::Source;
+use sim_dag::simulation::SimulationExt;
+
+fn to_player(player: &str, port: &str) -> Message {
+    Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from(player),
+        String::from(port),
+        0.0,
+        String::from("Ball"),
+    )
+}
+
+#[test]
+fn test_bad_message_leaves_nothing_injected() {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    let batch = vec![
+        to_player("player-01", "receive"),
+        to_player("player-02", "recieve"),
+        to_player("player-02", "receive"),
+    ];
+    assert_eq!(
+        simulation.inject_inputs(batch),
+        Err(CheckError::UndeliverableMessage {
+            index: 1,
+            target_id: String::from("player-02"),
+            target_port: String::from("recieve"),
+        })
+    );
+    assert!(simulation.get_messages().is_empty());
+
+    let result = simulation.inject_inputs(vec![to_player("player-09", "receive")]);
+    assert!(matches!(result, Err(CheckError::UndeliverableMessage { index: 0, .. })));
+    assert!(simulation.get_messages().is_empty());
+}
+
+#[test]
+fn test_message_to_model_without_inputs_rejected() {
+    // without backpressure a source takes no messages at all.
+    let models = vec![Model::new(
+        String::from("arrivals"),
+        Box::new(Source::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            None,
+            String::from("job"),
+            false,
+            None,
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    assert_eq!(
+        simulation.inject_inputs(vec![to_player("arrivals", "ready")]),
+        Err(CheckError::UndeliverableMessage {
+            index: 0,
+            target_id: String::from("arrivals"),
+            target_port: String::from("ready"),
+        })
+    );
+}
+
+#[test]
+fn test_valid_batch_is_scheduled() {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    simulation
+        .inject_inputs(vec![to_player("player-01", "receive"), to_player("player-02", "receive")])
+        .unwrap();
+    assert_eq!(simulation.get_messages().len(), 2);
+}
+
+#[test]
+fn test_pending_events_in_time_order() {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    let at = |time: f64, player: &str| {
+        Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from(player),
+            String::from("receive"),
+            time,
+            format!("Ball@{}", time),
+        )
+    };
+    simulation
+        .inject_inputs(vec![at(7.5, "player-01"), at(2.0, "player-02"), at(4.0, "player-01")])
+        .unwrap();
+
+    let pending: Vec<(f64, &str)> = simulation
+        .pending_events()
+        .iter()
+        .map(|message| (message.time(), message.target_id()))
+        .collect();
+    assert_eq!(pending, vec![(2.0, "player-02"), (4.0, "player-01"), (7.5, "player-01")]);
+    assert_eq!(simulation.pending_events().len(), 3);
+    assert_eq!(simulation.get_messages().len(), 3);
+    assert_eq!(simulation.time(), 0.0);
+}
@@ -0,0 +1,403 @@
+use sim::input_modeling::dynamic_rng::default_rng;
+use sim_dag::input_modeling::{
+    goodness_of_fit, seeded_rng, BooleanDistribution, ContinuousDistribution, IndexDistribution,
+};
+
+fn moments(distribution: &ContinuousDistribution, n: usize) -> (f64, f64) {
+    let rng = default_rng();
+    let samples: Vec<f64> = (0..n)
+        .map(|_| distribution.random_variate(rng.clone()).unwrap())
+        .collect();
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    (mean, variance)
+}
+
+#[test]
+fn test_normal_moments() {
+    let (mean, variance) = moments(
+        &ContinuousDistribution::Normal {
+            mean: 10.0,
+            std_dev: 2.0,
+        },
+        100_000,
+    );
+    assert!((mean - 10.0).abs() < 0.05);
+    assert!((variance - 4.0).abs() < 0.1);
+}
+
+#[test]
+fn test_truncated_normal_non_negative() {
+    let distribution = ContinuousDistribution::TruncatedNormal {
+        mean: 0.5,
+        std_dev: 1.0,
+    };
+    let rng = default_rng();
+    (0..10_000).for_each(|_| assert!(distribution.random_variate(rng.clone()).unwrap() >= 0.0));
+}
+
+#[test]
+fn test_empirical_resampling_preserves_mean() {
+    let source = ContinuousDistribution::Exp { lambda: 0.25 };
+    let rng = default_rng();
+    let samples: Vec<f64> = (0..10_000)
+        .map(|_| source.random_variate(rng.clone()).unwrap())
+        .collect();
+    let original_mean = samples.iter().sum::<f64>() / samples.len() as f64;
+
+    let (mean, _) = moments(&ContinuousDistribution::empirical(samples), 100_000);
+    assert!((mean - original_mean).abs() / original_mean < 0.02);
+}
+
+#[test]
+fn test_histogram_validation() {
+    let uneven = ContinuousDistribution::Histogram {
+        bins: vec![0.0, 2.0, 1.0],
+        counts: vec![1.0, 1.0],
+    };
+    assert!(uneven.validate().is_err());
+    assert!(ContinuousDistribution::Empirical { samples: Vec::new() }
+        .validate()
+        .is_err());
+
+    let (mean, _) = moments(
+        &ContinuousDistribution::Histogram {
+            bins: vec![0.0, 1.0, 2.0],
+            counts: vec![3.0, 1.0],
+        },
+        100_000,
+    );
+    // 0.75 * 0.5 + 0.25 * 1.5
+    assert!((mean - 0.75).abs() < 0.01);
+}
+
+#[test]
+fn test_weibull_moments() {
+    // shape 1 is the exponential distribution with mean `scale`.
+    let (mean, variance) = moments(
+        &ContinuousDistribution::Weibull {
+            shape: 1.0,
+            scale: 2.0,
+        },
+        100_000,
+    );
+    assert!((mean - 2.0).abs() < 0.05);
+    assert!((variance - 4.0).abs() < 0.15);
+
+    // shape 2 (Rayleigh): mean = scale * sqrt(pi) / 2, variance = scale^2 * (1 - pi / 4)
+    let (mean, variance) = moments(
+        &ContinuousDistribution::Weibull {
+            shape: 2.0,
+            scale: 3.0,
+        },
+        100_000,
+    );
+    assert!((mean - 3.0 * std::f64::consts::PI.sqrt() / 2.0).abs() < 0.03);
+    assert!((variance - 9.0 * (1.0 - std::f64::consts::PI / 4.0)).abs() < 0.05);
+}
+
+#[test]
+fn test_gamma_moments() {
+    for (shape, scale) in [(0.5, 2.0), (3.0, 1.5)] {
+        let (mean, variance) = moments(&ContinuousDistribution::Gamma { shape, scale }, 100_000);
+        assert!((mean - shape * scale).abs() / (shape * scale) < 0.02);
+        assert!((variance - shape * scale * scale).abs() / (shape * scale * scale) < 0.05);
+    }
+}
+
+#[test]
+fn test_weibull_and_gamma_validation() {
+    assert!(ContinuousDistribution::Weibull {
+        shape: 0.0,
+        scale: 1.0
+    }
+    .validate()
+    .is_err());
+    assert!(ContinuousDistribution::Gamma {
+        shape: 1.0,
+        scale: -1.0
+    }
+    .validate()
+    .is_err());
+    assert!(ContinuousDistribution::Gamma {
+        shape: 2.0,
+        scale: 1.0
+    }
+    .validate()
+    .is_ok());
+}
+
+#[test]
+fn test_triangular_moments() {
+    let (min, mode, max) = (2.0, 3.0, 10.0);
+    let distribution = ContinuousDistribution::Triangular { min, mode, max };
+    let (mean, variance) = moments(&distribution, 100_000);
+    let expected_variance =
+        (min * min + mode * mode + max * max - min * mode - min * max - mode * max) / 18.0;
+    assert!((mean - (min + mode + max) / 3.0).abs() < 0.03);
+    assert!((variance - expected_variance).abs() / expected_variance < 0.03);
+
+    let rng = default_rng();
+    (0..10_000).for_each(|_| {
+        let variate = distribution.random_variate(rng.clone()).unwrap();
+        assert!((min..=max).contains(&variate));
+    });
+}
+
+#[test]
+fn test_triangular_validation() {
+    let triangular = |min, mode, max| ContinuousDistribution::Triangular { min, mode, max };
+    assert!(triangular(1.0, 0.5, 2.0).validate().is_err());
+    assert!(triangular(1.0, 1.0, 1.0).validate().is_err());
+    assert!(triangular(1.0, 1.0, 2.0).validate().is_ok());
+    assert!(triangular(1.0, 2.0, 2.0).validate().is_ok());
+}
+
+#[test]
+fn test_lognormal_median() {
+    let (mu, sigma) = (1.5, 0.75);
+    let distribution = ContinuousDistribution::LogNormal { mu, sigma };
+    let rng = default_rng();
+    let mut variates: Vec<f64> = (0..100_001)
+        .map(|_| distribution.random_variate(rng.clone()).unwrap())
+        .collect();
+    variates.sort_by(f64::total_cmp);
+    let median = variates[variates.len() / 2];
+    assert!((median - mu.exp()).abs() / mu.exp() < 0.02);
+    assert!(variates[0] > 0.0);
+
+    assert!(ContinuousDistribution::LogNormal { mu, sigma: 0.0 }
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_uniform_mean_and_bounds() {
+    let (min, max) = (2.0, 5.0);
+    let distribution = ContinuousDistribution::Uniform { min, max };
+    let (mean, variance) = moments(&distribution, 100_000);
+    assert!((mean - (min + max) / 2.0).abs() < 0.02);
+    assert!((variance - (max - min).powi(2) / 12.0).abs() < 0.02);
+
+    let rng = default_rng();
+    (0..10_000).for_each(|_| {
+        let variate = distribution.random_variate(rng.clone()).unwrap();
+        assert!((min..max).contains(&variate));
+    });
+
+    assert!(ContinuousDistribution::Uniform { min: 1.0, max: 1.0 }
+        .validate()
+        .is_err());
+    assert!(ContinuousDistribution::Uniform { min: 2.0, max: 1.0 }
+        .random_variate(rng)
+        .is_err());
+}
+
+#[test]
+fn test_pareto_tail_matches_cdf() {
+    let (scale, shape) = (2.0, 1.5);
+    let distribution = ContinuousDistribution::Pareto { scale, shape };
+    let rng = default_rng();
+    let n = 200_000;
+    let variates: Vec<f64> = (0..n)
+        .map(|_| distribution.random_variate(rng.clone()).unwrap())
+        .collect();
+    assert!(variates.iter().all(|variate| *variate >= scale));
+    for x in [4.0, 20.0] {
+        let exceedance = variates.iter().filter(|variate| **variate > x).count() as f64 / n as f64;
+        let expected = (scale / x).powf(shape);
+        assert!((exceedance - expected).abs() / expected < 0.05);
+    }
+
+    assert!(ContinuousDistribution::Pareto { scale: 0.0, shape }
+        .validate()
+        .is_err());
+    assert!(ContinuousDistribution::Pareto { scale, shape: -1.0 }
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_beta_mean_and_bounds() {
+    let (alpha, beta) = (2.0, 5.0);
+    let distribution = ContinuousDistribution::Beta { alpha, beta };
+    let (mean, variance) = moments(&distribution, 100_000);
+    let expected_mean = alpha / (alpha + beta);
+    assert!((mean - expected_mean).abs() < 0.005);
+    let expected_variance = alpha * beta / ((alpha + beta).powi(2) * (alpha + beta + 1.0));
+    assert!((variance - expected_variance).abs() < 0.002);
+
+    let rng = default_rng();
+    (0..10_000).for_each(|_| {
+        let variate = distribution.random_variate(rng.clone()).unwrap();
+        assert!((0.0..=1.0).contains(&variate));
+    });
+    assert!((distribution.cdf(0.5) - 0.890_625).abs() < 1e-12);
+    let uniform = ContinuousDistribution::Beta { alpha: 1.0, beta: 1.0 };
+    assert!((uniform.cdf(0.3) - 0.3).abs() < 1e-12);
+
+    assert!(ContinuousDistribution::Beta { alpha: 0.0, beta }
+        .validate()
+        .is_err());
+    assert!(ContinuousDistribution::Beta { alpha, beta: -1.0 }
+        .random_variate(rng)
+        .is_err());
+}
+
+#[test]
+fn test_cdf_closed_forms_agree() {
+    let exp = ContinuousDistribution::Exp { lambda: 0.5 };
+    let gamma = ContinuousDistribution::Gamma {
+        shape: 1.0,
+        scale: 2.0,
+    };
+    let normal = ContinuousDistribution::Normal {
+        mean: 3.0,
+        std_dev: 2.0,
+    };
+    for x in [0.0, 0.5, 1.0, 4.0, 10.0] {
+        assert!((exp.cdf(x) - gamma.cdf(x)).abs() < 1e-12);
+    }
+    assert!((normal.cdf(3.0) - 0.5).abs() < 1e-12);
+    assert!((normal.cdf(5.0) - 0.841_344_746_068_542_9).abs() < 1e-12);
+    assert!((normal.cdf(1.0) + normal.cdf(5.0) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_inverse_cdf_round_trips() {
+    let cases = [
+        (ContinuousDistribution::Exp { lambda: 0.5 }, 0.1, 12.0),
+        (
+            ContinuousDistribution::Normal {
+                mean: 3.0,
+                std_dev: 2.0,
+            },
+            -4.0,
+            10.0,
+        ),
+        (
+            ContinuousDistribution::TruncatedNormal {
+                mean: 0.5,
+                std_dev: 1.0,
+            },
+            0.05,
+            3.5,
+        ),
+        (ContinuousDistribution::empirical(vec![1.0, 2.0, 4.0, 8.0]), 1.0, 8.0),
+        (
+            ContinuousDistribution::Histogram {
+                bins: vec![0.0, 1.0, 3.0, 4.0],
+                counts: vec![2.0, 5.0, 1.0],
+            },
+            0.0,
+            4.0,
+        ),
+        (
+            ContinuousDistribution::Weibull {
+                shape: 1.5,
+                scale: 2.0,
+            },
+            0.1,
+            6.0,
+        ),
+        (
+            ContinuousDistribution::Gamma {
+                shape: 2.5,
+                scale: 1.5,
+            },
+            0.1,
+            15.0,
+        ),
+        (
+            ContinuousDistribution::Triangular {
+                min: 1.0,
+                mode: 2.0,
+                max: 5.0,
+            },
+            1.0,
+            5.0,
+        ),
+        (ContinuousDistribution::LogNormal { mu: 0.5, sigma: 0.8 }, 0.1, 12.0),
+        (ContinuousDistribution::Uniform { min: -2.0, max: 3.0 }, -2.0, 3.0),
+        (ContinuousDistribution::Pareto { scale: 2.0, shape: 1.5 }, 2.0, 50.0),
+        (ContinuousDistribution::Beta { alpha: 2.0, beta: 5.0 }, 0.01, 0.99),
+    ];
+    for (distribution, from, to) in cases {
+        (0..=40).map(|i| from + (to - from) * i as f64 / 40.0).for_each(|x| {
+            let round_trip = distribution.inverse_cdf(distribution.cdf(x));
+            assert!(
+                (round_trip - x).abs() < 1e-6 * f64::max(1.0, x.abs()),
+                "{:?} at {}: {}",
+                distribution,
+                x,
+                round_trip
+            );
+        });
+        assert!(distribution.inverse_cdf(1.5).is_nan());
+    }
+
+    let constant = ContinuousDistribution::Constant { value: 4.0 };
+    assert_eq!(constant.inverse_cdf(0.3), 4.0);
+    assert_eq!(constant.cdf(constant.inverse_cdf(0.3)), 1.0);
+    let exp = ContinuousDistribution::Exp { lambda: 0.5 };
+    assert_eq!(exp.inverse_cdf(0.0), 0.0);
+    assert_eq!(exp.inverse_cdf(1.0), f64::INFINITY);
+}
+
+#[test]
+fn test_goodness_of_fit_separates_distributions() {
+    let exp = ContinuousDistribution::Exp { lambda: 2.0 };
+    let rng = seeded_rng(88);
+    let n = 2_000;
+    let samples: Vec<f64> = (0..n)
+        .map(|_| exp.random_variate(rng.clone()).unwrap())
+        .collect();
+    let critical = 1.36 / (n as f64).sqrt();
+
+    assert!(goodness_of_fit(&samples, &exp) < critical);
+    let uniform = ContinuousDistribution::Uniform { min: 0.0, max: 2.0 };
+    assert!(goodness_of_fit(&samples, &uniform) > 0.3);
+    let slower = ContinuousDistribution::Exp { lambda: 0.5 };
+    assert!(goodness_of_fit(&samples, &slower) > 0.3);
+}
+
+#[test]
+fn test_bernoulli_true_fraction() {
+    let rng = default_rng();
+    for p in [0.1, 0.5, 0.85] {
+        let distribution = BooleanDistribution::Bernoulli { p };
+        let trues = (0..100_000)
+            .filter(|_| distribution.random_variate(rng.clone()).unwrap())
+            .count();
+        assert!((trues as f64 / 100_000.0 - p).abs() < 0.01);
+    }
+    assert!(BooleanDistribution::Bernoulli { p: 1.5 }.validate().is_err());
+    assert!(BooleanDistribution::Bernoulli { p: 1.5 }
+        .random_variate(rng)
+        .is_err());
+}
+
+#[test]
+fn test_weighted_index_frequencies() {
+    let weights = vec![1.0, 0.0, 3.0, 6.0];
+    let distribution = IndexDistribution::WeightedIndex {
+        weights: weights.clone(),
+    };
+    let rng = default_rng();
+    let mut counts = vec![0usize; weights.len()];
+    (0..100_000).for_each(|_| counts[distribution.random_variate(rng.clone()).unwrap()] += 1);
+
+    let total: f64 = weights.iter().sum();
+    counts.iter().zip(&weights).for_each(|(count, weight)| {
+        assert!((*count as f64 / 100_000.0 - weight / total).abs() < 0.01);
+    });
+    assert_eq!(counts[1], 0);
+    assert!(IndexDistribution::WeightedIndex { weights: Vec::new() }
+        .validate()
+        .is_err());
+    assert!(IndexDistribution::WeightedIndex {
+        weights: vec![1.0, -1.0]
+    }
+    .validate()
+    .is_err());
+}
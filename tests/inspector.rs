@@ -0,0 +1,68 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Inspector, Sink, Source};
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_fail_ratio_matches_probability() {
+    let models = vec![
+        Model::new(
+            String::from("parts"),
+            Box::new(Source::new(
+                ContinuousDistribution::Constant { value: 1.0 },
+                Some(10_000),
+                String::from("part"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("quality"),
+            Box::new(Inspector::new(
+                0.9,
+                String::from("part"),
+                String::from("pass"),
+                String::from("fail"),
+                true,
+                None,
+            )),
+        ),
+        Model::new(String::from("shipping"), Box::new(Sink::new(String::from("part"), false))),
+        Model::new(String::from("scrap"), Box::new(Sink::new(String::from("part"), false))),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("parts to quality"),
+            String::from("parts"),
+            String::from("quality"),
+            String::from("part"),
+            String::from("part"),
+        ),
+        Connector::new(
+            String::from("quality to shipping"),
+            String::from("quality"),
+            String::from("shipping"),
+            String::from("pass"),
+            String::from("part"),
+        ),
+        Connector::new(
+            String::from("quality to scrap"),
+            String::from("quality"),
+            String::from("scrap"),
+            String::from("fail"),
+            String::from("part"),
+        ),
+    ];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 53);
+    simulation.run_to_completion().unwrap();
+
+    let records = |id: &str| simulation.get_models().get(id).unwrap().records().len();
+    let quality = simulation.get_models().get("quality").unwrap().records();
+    let failed = quality.iter().filter(|record| record.action == "Fail").count();
+    let passed = quality.iter().filter(|record| record.action == "Pass").count();
+    assert_eq!(passed + failed, 10_000);
+    assert!((failed as f64 / 10_000.0 - 0.1).abs() < 0.01, "{} failed", failed);
+    assert_eq!(records("shipping"), passed);
+    assert_eq!(records("scrap"), failed);
+}
@@ -0,0 +1,143 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Balancing, LoadBalancer, Source, Station};
+use sim_dag::simulation::SimulationExt;
+
+fn dispatch_counts(simulation: &Simulation) -> Vec<usize> {
+    simulation
+        .get_models()
+        .get("balancer")
+        .unwrap()
+        .records()
+        .iter()
+        .fold(vec![0, 0], |mut counts, record| {
+            counts[if record.subject == "server-a" { 0 } else { 1 }] += 1;
+            counts
+        })
+}
+
+fn balanced_servers(balancing: Balancing) -> Simulation {
+    let server = || {
+        Box::new(Station::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        ))
+    };
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 1.5 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("balancer"),
+            Box::new(LoadBalancer::new(
+                String::from("job"),
+                String::from("queue length"),
+                vec![String::from("server-a"), String::from("server-b")],
+                balancing,
+                true,
+            )),
+        ),
+        Model::new(String::from("a"), server()),
+        Model::new(String::from("b"), server()),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("arrivals to balancer"),
+            String::from("arrivals"),
+            String::from("balancer"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("balancer to a"),
+            String::from("balancer"),
+            String::from("a"),
+            String::from("server-a"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("balancer to b"),
+            String::from("balancer"),
+            String::from("b"),
+            String::from("server-b"),
+            String::from("job"),
+        ),
+    ];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 40);
+    simulation.step_until(1_000.0).unwrap();
+    simulation
+}
+
+#[test]
+fn test_symmetric_servers_stay_balanced() {
+    for balancing in [Balancing::RoundRobin, Balancing::ShortestQueue] {
+        let counts = dispatch_counts(&balanced_servers(balancing));
+        assert!(counts[0] > 100);
+        assert!(counts[0].abs_diff(counts[1]) <= 1, "{:?}", counts);
+    }
+}
+
+fn message(port: &str, content: &str) -> Message {
+    Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from("balancer"),
+        String::from(port),
+        0.0,
+        String::from(content),
+    )
+}
+
+#[test]
+fn test_queue_feedback_steers_jobs() {
+    let models = vec![Model::new(
+        String::from("balancer"),
+        Box::new(LoadBalancer::new(
+            String::from("job"),
+            String::from("queue length"),
+            vec![String::from("server-a"), String::from("server-b")],
+            Balancing::ShortestQueue,
+            true,
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    simulation.inject_input(message("queue length", "server-a=5"));
+    (0..4).for_each(|i| simulation.inject_input(message("job", &format!("job {}", i))));
+    simulation.step_n(2).unwrap();
+
+    // b catches up with the reported backlog of a before a gets any job.
+    assert_eq!(dispatch_counts(&simulation), vec![0, 4]);
+    let status = simulation.get_models().get("balancer").unwrap().status();
+    assert_eq!(status, "Dispatched server-a: 0, server-b: 4");
+}
+
+#[test]
+fn test_deserialized_balancer_without_state() {
+    let balancer: LoadBalancer = serde_json::from_value(serde_json::json!({
+        "portsIn": { "job": "job", "control": "queue length" },
+        "outputs": ["server-a", "server-b"],
+        "balancing": "shortestQueue",
+        "storeRecords": true,
+    }))
+    .unwrap();
+    let models = vec![Model::new(String::from("balancer"), Box::new(balancer))];
+    let mut simulation = Simulation::post(models, Vec::new());
+    simulation.inject_input(message("queue length", "server-b=2"));
+    (0..3).for_each(|i| simulation.inject_input(message("job", &format!("job {}", i))));
+    simulation.step_n(2).unwrap();
+
+    assert_eq!(dispatch_counts(&simulation), vec![3, 0]);
+}
@@ -0,0 +1,84 @@
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::Station;
+use sim_dag::session::Session;
+
+/// Collects the crate's log lines; installed once for the whole test binary.
+struct Capture(Mutex<Vec<String>>);
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("sim_dag")
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let line = format!("{} {} {}", record.level(), record.target(), record.args());
+            self.0.lock().unwrap().push(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn test_session_logs_transfers_and_models_their_transitions() {
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 1.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("agents"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 2.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("generator to agents"),
+        String::from("generator"),
+        String::from("agents"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut session = Session::new(Simulation::post_with_seed(models, connectors, 70));
+    session.step_until(20.0).unwrap();
+
+    let lines = CAPTURE.0.lock().unwrap();
+    let count = |level: &str, pattern: &str| {
+        lines
+            .iter()
+            .filter(|line| line.starts_with(level) && line.contains(pattern))
+            .count()
+    };
+    let transfers = count("DEBUG sim_dag::session", "model=agents kind=transfer ");
+    let arrivals = count("TRACE sim_dag::models::station", "type=Station kind=Arrival ");
+    assert!(transfers > 0);
+    assert_eq!(transfers, arrivals);
+    assert!(count("TRACE sim_dag::models::station", "kind=Processing Start ") > 0);
+    assert!(count("TRACE sim_dag::models::station", "kind=Departure ") > 0);
+    assert!(lines.iter().all(|line| line.contains("time=")));
+}
@@ -0,0 +1,61 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Concrete, Merge, Sink, Source};
+use sim_dag::simulation::SimulationExt;
+
+fn connector(source_id: &str, source_port: &str, target_id: &str, target_port: &str) -> Connector {
+    Connector::new(
+        format!("{} to {}", source_id, target_id),
+        source_id.to_string(),
+        target_id.to_string(),
+        source_port.to_string(),
+        target_port.to_string(),
+    )
+}
+
+#[test]
+fn test_merge_funnels_every_job() {
+    let source = |lambda: f64, max_count: usize| {
+        Box::new(Source::new(
+            ContinuousDistribution::Exp { lambda },
+            Some(max_count),
+            String::from("job"),
+            false,
+            None,
+        ))
+    };
+    let models = vec![
+        Model::new(String::from("walk-ins"), source(1.0, 15)),
+        Model::new(String::from("calls"), source(2.0, 25)),
+        Model::new(
+            String::from("intake"),
+            Box::new(Merge::new(
+                vec![String::from("walk-in"), String::from("call")],
+                String::from("job"),
+                false,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        connector("walk-ins", "job", "intake", "walk-in"),
+        connector("calls", "job", "intake", "call"),
+        connector("intake", "job", "exit", "job"),
+    ];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 90);
+    simulation.run_to_completion().unwrap();
+
+    let models = simulation.get_models();
+    let merge = models["intake"].as_concrete::<Merge>().unwrap();
+    assert_eq!(merge.counts(), vec![("walk-in", 15), ("call", 25)]);
+    assert_eq!(merge.merged(), 40);
+    assert_eq!(models["intake"].status(), "Merged 40, walk-in: 15, call: 25");
+    let arrivals: Vec<f64> = models["exit"]
+        .records()
+        .iter()
+        .map(|record| record.time)
+        .collect();
+    assert_eq!(arrivals.len(), 40);
+    assert!(arrivals.is_sorted());
+}
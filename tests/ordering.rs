@@ -0,0 +1,152 @@
+mod common;
+
+use sim::models::{Model, Reportable};
+use sim::simulator::{Message, Simulation};
+use sim_dag::models::Gate;
+use sim_dag::event_queue::EventQueue;
+use sim_dag::session::{DEFAULT_TIME_EPSILON, event_order, Session};
+use sim_dag::simulation::SimulationExt;
+
+fn to_gate(port: &str, content: &str) -> Message {
+    to_gate_at(port, content, 0.0)
+}
+
+fn to_gate_at(port: &str, content: &str, time: f64) -> Message {
+    Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "signal".to_string(),
+        port.to_string(),
+        time,
+        content.to_string(),
+    )
+}
+
+fn gate_status(first: Message, second: Message) -> String {
+    gate_status_within(first, second, DEFAULT_TIME_EPSILON)
+}
+
+fn gate_status_within(first: Message, second: Message, epsilon: f64) -> String {
+    let models = vec![Model::new(
+        String::from("signal"),
+        Box::new(Gate::new(
+            String::from("job"),
+            String::from("control"),
+            String::from("released"),
+            true,
+            false,
+        )),
+    )];
+    let mut session = Session::new(Simulation::post(models, Vec::new()));
+    session.set_time_epsilon(epsilon);
+    session.inject_input(first);
+    session.inject_input(second);
+    session.step().unwrap();
+    session.simulation().get_models().get("signal").unwrap().status()
+}
+
+#[test]
+fn test_simultaneous_messages_resolve_by_port() {
+    // "control" sorts before "job", so the gate closes before the job arrives
+    // regardless of injection order.
+    let job_first = gate_status(to_gate("job", "car"), to_gate("control", "close"));
+    let close_first = gate_status(to_gate("control", "close"), to_gate("job", "car"));
+    assert_eq!(job_first, "Closed, holding 1 jobs");
+    assert_eq!(job_first, close_first);
+}
+
+#[test]
+fn test_times_within_epsilon_are_simultaneous() {
+    // the job is stamped an ULP-sized step earlier, but still ties with the
+    // close and loses to it on port.
+    let job = to_gate_at("job", "car", 0.1 + 0.2 - 1e-15);
+    let close = to_gate_at("control", "close", 0.1 + 0.2);
+    assert!(job.time() < close.time());
+    assert_eq!(gate_status(job.clone(), close.clone()), "Closed, holding 1 jobs");
+    assert_ne!(gate_status_within(job, close, 0.0), "Closed, holding 1 jobs");
+}
+
+#[test]
+fn test_near_times_order_transitively() {
+    // each time is within the epsilon of the next but not of the one after,
+    // and the sources sort against the times.
+    let messages = [("c", 0.0), ("b", 0.6e-9), ("a", 1.2e-9)].map(|(source, time)| {
+        Message::new(
+            source.to_string(),
+            String::from("out"),
+            String::from("target"),
+            String::from("in"),
+            time,
+            source.to_string(),
+        )
+    });
+    for a in &messages {
+        for b in &messages {
+            for c in &messages {
+                if event_order(a, b).is_le() && event_order(b, c).is_le() {
+                    assert!(event_order(a, c).is_le());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_event_queue_matches_stable_sort() {
+    let messages: Vec<Message> = (0..200)
+        .map(|i| {
+            Message::new(
+                format!("source-{}", i % 3),
+                String::from("out"),
+                format!("target-{}", i % 5),
+                String::from("in"),
+                ((i * 37) % 11) as f64,
+                format!("message {}", i),
+            )
+        })
+        .collect();
+    let mut sorted = messages.clone();
+    sorted.sort_by(event_order);
+
+    let mut queue: EventQueue = messages.iter().cloned().collect();
+    assert_eq!(queue.len(), 200);
+    let contents = |messages: Vec<Message>| -> Vec<String> {
+        messages
+            .iter()
+            .map(|message| message.content().to_string())
+            .collect()
+    };
+    let expected = contents(sorted);
+    assert_eq!(contents(queue.drain_ordered()), expected);
+
+    // a drained queue is empty and orders the next batch the same way.
+    assert!(queue.is_empty());
+    queue.extend(messages);
+    assert_eq!(contents(queue.into_ordered_vec()), expected);
+}
+
+#[test]
+fn test_step_n_returns_messages_in_event_order() {
+    let run = || {
+        let simulation = Simulation::post_with_seed(
+            common::ping_pong_models(4),
+            common::ping_pong_connectors(4),
+            46,
+        );
+        let mut session = Session::new(simulation);
+        session.inject_input(common::ball());
+        session.step_n(200).unwrap()
+    };
+    let messages = run();
+    assert!(messages.len() > 100);
+    assert!(messages.windows(2).all(|pair| pair[0].time() <= pair[1].time()));
+    assert!(messages.is_sorted_by(|a, b| event_order(a, b).is_le()));
+
+    let contents = |messages: &[Message]| -> Vec<(f64, String)> {
+        messages
+            .iter()
+            .map(|message| (message.time(), message.target_id().to_string()))
+            .collect()
+    };
+    assert_eq!(contents(&messages), contents(&run()));
+}
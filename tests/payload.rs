@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Delay, Sink, Source, Station};
+use sim_dag::payload::{self, Payload};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Job {
+    id: u32,
+    weight: f64,
+}
+
+#[test]
+fn test_struct_payload_survives_transfer() {
+    let models = vec![
+        Model::new(
+            String::from("conveyor"),
+            Box::new(Delay::new(
+                ContinuousDistribution::Constant { value: 2.0 },
+                String::from("job"),
+                String::from("delayed job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("exit"),
+            Box::new(Sink::new(String::from("job"), false)),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("conveyor to exit"),
+        String::from("conveyor"),
+        String::from("exit"),
+        String::from("delayed job"),
+        String::from("job"),
+    )];
+    let job = Job { id: 7, weight: 12.5 };
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.inject_input(
+        payload::typed_message(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("conveyor"),
+            String::from("job"),
+            0.0,
+            &job,
+        )
+        .unwrap(),
+    );
+    let messages = simulation.step_n(2).unwrap();
+
+    let delivered = messages
+        .iter()
+        .find(|message| message.target_id() == "exit")
+        .unwrap();
+    assert_eq!(delivered.payload::<Job>().unwrap(), job);
+    let records = simulation.get_models().get("exit").unwrap().records();
+    assert_eq!(payload::decode::<Job>(&records[0].subject).unwrap(), job);
+}
+
+#[test]
+fn test_struct_payload_from_source_decodes_despite_stamp() {
+    let job = Job { id: 3, weight: 4.5 };
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(
+                Source::from_schedule(vec![1.0], String::from("job"), false)
+                    .with_payload(&job)
+                    .unwrap(),
+            ),
+        ),
+        Model::new(
+            String::from("machine"),
+            Box::new(Station::new(
+                ContinuousDistribution::Constant { value: 2.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("exit"),
+            Box::new(Sink::new(String::from("job"), false)),
+        ),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("arrivals to machine"),
+            String::from("arrivals"),
+            String::from("machine"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("machine to exit"),
+            String::from("machine"),
+            String::from("exit"),
+            String::from("processed job"),
+            String::from("job"),
+        ),
+    ];
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.step_until(10.0).unwrap();
+
+    let records = simulation.get_models().get("exit").unwrap().records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(payload::creation_time(&records[0].subject), Some(1.0));
+    assert_eq!(payload::decode::<Job>(&records[0].subject).unwrap(), job);
+}
+
+#[test]
+fn test_plain_strings_are_unchanged() {
+    assert_eq!(payload::encode(&String::from("Ball")).unwrap(), "Ball");
+    assert_eq!(payload::decode::<String>("Ball").unwrap(), "Ball");
+    assert_eq!(payload::decode::<String>("42").unwrap(), "42");
+    assert_eq!(payload::decode::<u32>("42").unwrap(), 42);
+    assert!(payload::decode::<Job>("Ball").is_err());
+}
+
+#[test]
+fn test_creation_time_stamps() {
+    let stamped = payload::stamp("job 3", 12.5);
+    assert_eq!(stamped, r#"{"content":"job 3","createdAt":12.5}"#);
+    assert_eq!(payload::creation_time(&stamped), Some(12.5));
+    assert_eq!(payload::unstamped(&stamped), "job 3");
+    assert_eq!(payload::creation_time("Ball"), None);
+    assert_eq!(payload::unstamped("job 3@12.5"), "job 3@12.5");
+
+    // content is given back exactly as it was stamped, JSON or not.
+    for content in [r#"{"id":7}"#, "42", r#""quoted""#, " 42", "[1, 2]"] {
+        let stamped = payload::stamp(content, 1.0);
+        assert_eq!(payload::unstamped(&stamped), content);
+        assert_eq!(payload::creation_time(&stamped), Some(1.0));
+    }
+    // an object that merely has the fields of a stamp is not stamped.
+    let lookalike = r#"{"content":"job 3","createdAt":12.5,"id":7}"#;
+    assert_eq!(payload::creation_time(lookalike), None);
+    assert_eq!(payload::unstamped(lookalike), lookalike);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sim::simulator::Simulation;
+use sim_dag::session::Session;
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_events_processed_counts_step_messages() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(3), common::ping_pong_connectors(3), 55);
+    simulation.inject_input(common::ball());
+    let mut session = Session::new(simulation);
+    assert_eq!(session.events_processed(), 0);
+
+    let first = session.step_n(25).unwrap();
+    assert_eq!(session.events_processed(), first.len());
+    let second = session.step_n(25).unwrap();
+    assert_eq!(session.events_processed(), first.len() + second.len());
+    assert!(session.time() > 0.0);
+}
+
+#[test]
+fn test_observer_fires_once_per_routed_message() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(3), common::ping_pong_connectors(3), 60);
+    simulation.inject_input(common::ball());
+    let mut session = Session::new(simulation);
+    let observed = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&observed);
+    session.on_message(move |message, time| {
+        log.borrow_mut().push((message.target_id().to_string(), time));
+    });
+
+    let messages = session.step_n(25).unwrap();
+    let observed = observed.borrow();
+    assert_eq!(observed.len(), messages.len());
+    messages.iter().zip(observed.iter()).for_each(|(message, (target, time))| {
+        assert_eq!(message.target_id(), target);
+        assert_eq!(message.time(), *time);
+    });
+}
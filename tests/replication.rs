@@ -0,0 +1,166 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::Station;
+use sim_dag::replication::{self, replicate_seeded, sweep, MetricSummary, Replicate};
+use sim_dag::simulation::{SimulationExt, StopCondition};
+
+fn ping_pong() -> Simulation {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    simulation.inject_input(common::ball());
+    simulation
+}
+
+#[test]
+fn test_replications_are_reproducible() {
+    let simulation = ping_pong();
+    let stop = StopCondition::EndTime(100.0);
+    let first = simulation.replicate(30, 1000, &stop).unwrap();
+    let second = simulation.replicate(30, 1000, &stop).unwrap();
+    assert_eq!(first, second);
+
+    let round_trips = first.metric("Store").unwrap();
+    assert_eq!(round_trips.values.len(), 30);
+    assert!(round_trips.mean > 0.0);
+    assert!(round_trips.std_dev > 0.0);
+    // the replications should not all be copies of one run.
+    assert!(round_trips.values.iter().any(|value| *value != round_trips.values[0]));
+}
+
+#[test]
+fn test_seeded_replications_match_reseeded_copies() {
+    let stop = StopCondition::EndTime(100.0);
+    let copies = ping_pong().replicate(8, 500, &stop).unwrap();
+    let built =
+        replicate_seeded(|_| ping_pong(), 8, 500, &stop, replication::record_counts).unwrap();
+    assert_eq!(copies, built);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_replications_match_serial() {
+    use sim_dag::replication::replicate_parallel;
+
+    let stop = StopCondition::EndTime(100.0);
+    let serial = ping_pong().replicate(16, 77, &stop).unwrap();
+    let parallel = replicate_parallel(ping_pong, 16, 77, &stop).unwrap();
+    assert_eq!(serial, parallel);
+}
+
+/// A generator feeding a desk that serves at rate `lambda`.
+fn desk_line(seed: u64, lambda: f64) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 3.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("desk"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda },
+                None,
+                1,
+                String::from("job"),
+                String::from("done"),
+                true,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("generator to desk"),
+        String::from("generator"),
+        String::from("desk"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    Simulation::post_with_seed(models, connectors, seed)
+}
+
+#[test]
+fn test_sweep_throughput_rises_with_service_rate() {
+    let throughput = |simulation: &Simulation| {
+        let departures = simulation.get_models()["desk"]
+            .records()
+            .iter()
+            .filter(|record| record.action == "Departure")
+            .count();
+        BTreeMap::from([(
+            String::from("throughput"),
+            departures as f64 / simulation.get_global_time(),
+        )])
+    };
+    let points = sweep(
+        |seed, lambda| desk_line(seed, *lambda),
+        vec![0.5, 1.0, 2.0],
+        5,
+        98,
+        &StopCondition::EndTime(500.0),
+        throughput,
+    )
+    .unwrap();
+
+    let values: Vec<f64> = points.iter().map(|point| point.value).collect();
+    assert_eq!(values, vec![0.5, 1.0, 2.0]);
+    let means: Vec<f64> = points.iter().map(|point| point.mean("throughput").unwrap()).collect();
+    assert!(means.is_sorted_by(|a, b| a < b), "{:?}", means);
+    // the desk is saturated at every rate, so it completes jobs at its rate.
+    for point in &points {
+        assert!((point.mean("throughput").unwrap() - point.value).abs() < 0.15 * point.value);
+    }
+    assert_eq!(points[0].replications.metric("throughput").unwrap().values.len(), 5);
+
+    // each point replicates what `replicate_seeded` does for its value.
+    let replications = replicate_seeded(
+        |seed| desk_line(seed, 1.0),
+        5,
+        98,
+        &StopCondition::EndTime(500.0),
+        throughput,
+    )
+    .unwrap();
+    assert_eq!(points[1].replications, replications);
+}
+
+#[test]
+fn test_confidence_interval_uses_student_t() {
+    let values = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    let metric = MetricSummary {
+        mean: 5.0,
+        std_dev: (32.0_f64 / 7.0).sqrt(),
+        values,
+    };
+    // t(0.975, 7) = 2.364624 by hand: 5 ± 2.364624 * 2.138090 / sqrt(8).
+    let (lower, upper) = metric.confidence_interval(0.95).unwrap();
+    assert!((lower - 3.212_512).abs() < 1e-5);
+    assert!((upper - 6.787_488).abs() < 1e-5);
+
+    // two replications: t(0.975, 1) = 12.706205, far above the normal 1.96.
+    let pair = MetricSummary {
+        values: vec![1.0, 3.0],
+        mean: 2.0,
+        std_dev: 2.0_f64.sqrt(),
+    };
+    let (lower, upper) = pair.confidence_interval(0.95).unwrap();
+    assert!((upper - lower - 2.0 * 12.706_205).abs() < 1e-4);
+
+    let single = MetricSummary {
+        values: vec![1.0],
+        mean: 1.0,
+        std_dev: 0.0,
+    };
+    assert_eq!(single.confidence_interval(0.95), None);
+    assert_eq!(metric.confidence_interval(1.0), None);
+}
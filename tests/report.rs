@@ -0,0 +1,164 @@
+mod common;
+
+use sim::models::Reportable;
+use sim::simulator::Simulation;
+use sim_dag::report::{JSON_SCHEMA_VERSION, LoadError, Report, SAVED_SCHEMA_VERSION, TimeUnit};
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_csv_round_trip_count() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let mut csv = Vec::new();
+    Report::new(&simulation).write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("model_id,time,action,subject"));
+    let store_rows = lines.filter(|line| line.starts_with("Store,")).count();
+    let store_records = simulation.get_models().get("Store").unwrap().records().len();
+    assert!(store_records > 0);
+    assert_eq!(store_rows, store_records);
+}
+
+#[test]
+fn test_warmup_excludes_early_records() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(200.0).unwrap();
+
+    let report = Report::new(&simulation).with_warmup(50.0);
+    let records = report.records("Store").unwrap();
+    assert!(!records.is_empty());
+    assert!(records.iter().all(|record| record.time >= 50.0));
+    assert!(records.len() < simulation.get_models().get("Store").unwrap().records().len());
+}
+
+#[test]
+fn test_summarize_record_times() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let report = Report::new(&simulation);
+    let summary = report.summarize_numeric("Store", "time").unwrap();
+    let records = simulation.get_models().get("Store").unwrap().records();
+    assert_eq!(summary.count, records.len());
+    assert_eq!(summary.min, records.first().unwrap().time);
+    assert_eq!(summary.max, records.last().unwrap().time);
+    assert!(summary.percentile(50.0).unwrap() <= summary.percentile(95.0).unwrap());
+    assert!(report.summarize_numeric("Store", "action").is_none());
+    assert!(report.summarize_numeric("Nowhere", "time").is_none());
+}
+
+#[test]
+fn test_throughput_times_elapsed_is_round_trips() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    assert_eq!(Report::new(&simulation).throughput("Store"), None);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let round_trips = simulation.get_models().get("Store").unwrap().records().len() as f64;
+    let throughput = Report::new(&simulation).throughput("Store").unwrap();
+    assert!((throughput * simulation.get_global_time() - round_trips).abs() < 1e-9);
+
+    let report = Report::new(&simulation).with_warmup(50.0);
+    let after_warmup = report.records("Store").unwrap().len() as f64;
+    let throughput = report.throughput("Store").unwrap();
+    assert!((throughput * (simulation.get_global_time() - 50.0) - after_warmup).abs() < 1e-9);
+}
+
+#[test]
+fn test_time_unit_scales_throughput() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let raw = Report::new(&simulation).throughput("Store").unwrap();
+    assert_eq!(Report::new(&simulation).throughput_per("Store", TimeUnit::Hours), None);
+
+    let report = Report::new(&simulation).with_time_unit(TimeUnit::Minutes);
+    assert_eq!(report.throughput("Store"), Some(raw));
+    assert_eq!(report.throughput_per("Store", TimeUnit::Minutes), Some(raw));
+    let per_hour = report.throughput_per("Store", TimeUnit::Hours).unwrap();
+    assert!((per_hour - raw * 60.0).abs() < 1e-9);
+    assert_eq!(
+        report.format_throughput("Store", TimeUnit::Hours).unwrap(),
+        format!("{:.4} jobs/hour", raw * 60.0)
+    );
+    assert!(report.format_elapsed().unwrap().ends_with(" minutes"));
+    assert_eq!(report.to_json()["timeUnit"], "minutes");
+    assert!(Report::new(&simulation).to_json()["timeUnit"].is_null());
+}
+
+#[test]
+fn test_json_report_schema() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let report = Report::new(&simulation).with_seed(5).to_json();
+    assert_eq!(report["version"], JSON_SCHEMA_VERSION);
+    assert_eq!(report["seed"], 5);
+    assert!(report["time"].as_f64().unwrap() >= 100.0);
+    assert_eq!(report["warmup"], 0.0);
+    assert!(report["elapsed"].is_f64());
+    assert!(report["events"].is_null());
+
+    let models = report["models"].as_array().unwrap();
+    let ids: Vec<&str> = models.iter().map(|model| model["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["Store", "player-01", "player-02"]);
+    for model in models {
+        assert!(model["type"].is_string());
+        assert!(model["status"].is_string());
+        assert!(model["records"].is_u64());
+        assert!(model["utilization"].is_null());
+    }
+    let store = &models[0];
+    assert_eq!(store["type"], "Storage");
+    assert!(store["records"].as_u64().unwrap() > 0);
+    assert!(store["throughput"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_saved_report_round_trip() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 5);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let report = Report::new(&simulation).with_warmup(20.0).with_seed(5);
+    let path = std::env::temp_dir().join("sim_dag_saved_report.json");
+    report.save(&path).unwrap();
+    let loaded = Report::load(&path).unwrap();
+    assert_eq!(loaded.version(), SAVED_SCHEMA_VERSION);
+    assert_eq!(loaded.seed(), Some(5));
+    assert_eq!(loaded.time(), simulation.get_global_time());
+    assert_eq!(loaded.model_ids(), vec!["Store", "player-01", "player-02"]);
+    for id in loaded.model_ids() {
+        assert_eq!(loaded.records(id).unwrap().len(), report.records(id).unwrap().len());
+        assert_eq!(loaded.throughput(id), report.throughput(id));
+        assert_eq!(
+            loaded.status(id).unwrap(),
+            simulation.get_models().get(id).unwrap().status()
+        );
+    }
+    assert!(loaded.throughput("Store").unwrap() > 0.0);
+
+    let mut document: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    document["version"] = serde_json::json!(SAVED_SCHEMA_VERSION + 1);
+    std::fs::write(&path, document.to_string()).unwrap();
+    assert!(matches!(
+        Report::load(&path),
+        Err(LoadError::UnsupportedVersion { version }) if version == u64::from(SAVED_SCHEMA_VERSION) + 1
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,130 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::config::FromConfig;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Sink, Source, Station};
+use sim_dag::session::Session;
+use sim_dag::simulation::SimulationExt;
+
+fn line() -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                Some(30),
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("desk"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 1.2 },
+                None,
+                1,
+                String::from("job"),
+                String::from("done"),
+                true,
+                None,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("arrivals to desk"),
+            String::from("arrivals"),
+            String::from("desk"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("desk to exit"),
+            String::from("desk"),
+            String::from("exit"),
+            String::from("done"),
+            String::from("job"),
+        ),
+    ];
+    Simulation::post_with_seed(models, connectors, 94)
+}
+
+fn outcome(messages: &[Message]) -> Vec<(f64, String, String)> {
+    messages
+        .iter()
+        .map(|message| (message.time(), message.target_id().to_string(), message.content().to_string()))
+        .collect()
+}
+
+#[test]
+fn test_reset_reruns_identically() {
+    let mut session = Session::new(line());
+    assert!(!session.reset().unwrap());
+    session.enable_reset();
+    session.enable_traffic();
+
+    let first = session.run_to_completion().unwrap();
+    let first_time = session.time();
+    let first_status = session.simulation().get_models()["desk"].status();
+    assert_eq!(session.simulation().get_models()["exit"].records().len(), 30);
+
+    assert!(session.reset().unwrap());
+    assert_eq!(session.time(), 0.0);
+    assert_eq!(session.events_processed(), 0);
+    assert!(session.simulation().get_models()["desk"].records().is_empty());
+    assert!(session.traffic().unwrap().values().all(|count| *count == 0));
+
+    let second = session.run_to_completion().unwrap();
+    assert_eq!(outcome(&second), outcome(&first));
+    assert_eq!(session.time(), first_time);
+    assert_eq!(session.simulation().get_models()["desk"].status(), first_status);
+    assert_eq!(session.events_processed(), first.len());
+}
+
+/// The line of `line`, configured with a private seed for the desk.
+const SEEDED_LINE: &str = "
+seed: 94
+models:
+  - id: arrivals
+    type: Source
+    interarrivalTime: { exp: { lambda: 1.0 } }
+    maxCount: 30
+    jobPort: job
+  - id: desk
+    type: Station
+    seed: 5
+    serviceTime: { exp: { lambda: 1.2 } }
+    servers: 1
+    jobPort: job
+    processedJobPort: done
+    storeRecords: true
+  - id: exit
+    type: Sink
+    jobPort: job
+connectors:
+  - id: arrivals to desk
+    sourceId: arrivals
+    sourcePort: job
+    targetId: desk
+    targetPort: job
+  - id: desk to exit
+    sourceId: desk
+    sourcePort: done
+    targetId: exit
+    targetPort: job
+";
+
+#[test]
+fn test_reset_restarts_configured_seeds() {
+    let mut session = Session::new(Simulation::from_yaml_str(SEEDED_LINE).unwrap());
+    session.enable_reset();
+    let first = session.run_to_completion().unwrap();
+    session.reset().unwrap();
+    let second = session.run_to_completion().unwrap();
+    assert_eq!(outcome(&second), outcome(&first));
+
+    let mut fresh = Session::new(Simulation::from_yaml_str(SEEDED_LINE).unwrap());
+    assert_eq!(outcome(&fresh.run_to_completion().unwrap()), outcome(&first));
+}
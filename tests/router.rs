@@ -0,0 +1,86 @@
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model, Reportable, Storage};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::checker::check_models;
+use sim_dag::models::Router;
+use sim_dag::simulation::SimulationExt;
+
+fn storage(id: &str) -> Model {
+    Model::new(
+        String::from(id),
+        Box::new(Storage::new(
+            String::from("put"),
+            String::from("get"),
+            String::from("stored"),
+            false,
+        )),
+    )
+}
+
+#[test]
+fn test_split_ratio_converges() {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 5.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("router"),
+            Box::new(Router::new(
+                String::from("job"),
+                vec![(String::from("left"), 7.0), (String::from("right"), 3.0)],
+                true,
+                None,
+            )),
+        ),
+        storage("A"),
+        storage("B"),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("generator to router"),
+            String::from("generator"),
+            String::from("router"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("router to A"),
+            String::from("router"),
+            String::from("A"),
+            String::from("left"),
+            String::from("put"),
+        ),
+        Connector::new(
+            String::from("router to B"),
+            String::from("router"),
+            String::from("B"),
+            String::from("right"),
+            String::from("put"),
+        ),
+    ];
+    check_models(&models).unwrap();
+    let mut simulation = Simulation::post_with_seed(models, connectors, 3);
+    simulation.step_until(2000.0).unwrap();
+
+    let records = simulation.get_models().get("router").unwrap().records();
+    let left = records.iter().filter(|r| r.subject == "left").count();
+    let ratio = left as f64 / records.len() as f64;
+    assert!(records.len() > 5000);
+    assert!((ratio - 0.7).abs() < 0.02);
+}
+
+#[test]
+fn test_empty_routes_rejected() {
+    let router = Model::new(
+        String::from("router"),
+        Box::new(Router::new(String::from("job"), Vec::new(), false, None)),
+    );
+    assert!(check_models(&[router]).is_err());
+}
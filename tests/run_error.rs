@@ -0,0 +1,37 @@
+mod common;
+
+use sim::simulator::{Message, Simulation};
+use sim_dag::simulation::{RunError, SimulationExt, StopCondition};
+
+#[test]
+fn test_unroutable_message_stops_the_run() {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    simulation.inject_input(Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from("player-99"),
+        String::from("receive"),
+        0.0,
+        String::from("Ball"),
+    ));
+
+    let result = simulation.try_run(&StopCondition::EndTime(10.0));
+    assert!(matches!(
+        result,
+        Err(RunError::UnroutableMessage { target_id, target_port })
+            if target_id == "player-99" && target_port == "receive"
+    ));
+    assert_eq!(simulation.time(), 0.0);
+}
+
+#[test]
+fn test_step_without_events_is_an_error() {
+    let mut simulation =
+        Simulation::post(common::ping_pong_models(2), common::ping_pong_connectors(2));
+    assert!(matches!(simulation.try_step(), Err(RunError::EmptyQueue)));
+
+    simulation.inject_input(common::ball());
+    let messages = simulation.try_run(&StopCondition::Iterations(10)).unwrap();
+    assert!(!messages.is_empty());
+}
@@ -0,0 +1,194 @@
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Model, Processor, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::input_modeling::{model_rng, stream_rng, stream_seed, ContinuousDistribution};
+use sim_dag::models::{Concrete, Source, Station};
+use sim_dag::simulation::SimulationExt;
+
+fn seeded_ping_pong(seed: u64) -> Simulation {
+    let models = (1..=2)
+        .map(|i| {
+            Model::new(
+                format!("player-{:02}", i),
+                Box::new(Processor::new(
+                    ContinuousRandomVariable::Exp { lambda: 0.9 },
+                    None,
+                    String::from("receive"),
+                    String::from("send"),
+                    false,
+                    None,
+                )),
+            )
+        })
+        .collect();
+    let connectors = vec![
+        Connector::new(
+            String::from("p1 to p2"),
+            String::from("player-01"),
+            String::from("player-02"),
+            String::from("send"),
+            String::from("receive"),
+        ),
+        Connector::new(
+            String::from("p2 to p1"),
+            String::from("player-02"),
+            String::from("player-01"),
+            String::from("send"),
+            String::from("receive"),
+        ),
+    ];
+    let mut simulation = Simulation::post_with_seed(models, connectors, seed);
+    simulation.inject_input(Message::new(
+        "manual".to_string(),
+        "manual".to_string(),
+        "player-01".to_string(),
+        "receive".to_string(),
+        0.0,
+        "Ball".to_string(),
+    ));
+    simulation
+}
+
+#[test]
+fn test_same_seed_same_run() {
+    let mut first = seeded_ping_pong(7);
+    let mut second = seeded_ping_pong(7);
+    let first_msgs = first.step_n(100).unwrap();
+    let second_msgs = second.step_n(100).unwrap();
+    assert_eq!(
+        serde_json::to_string(&first_msgs).unwrap(),
+        serde_json::to_string(&second_msgs).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+}
+
+/// Two independent source-to-station lines, every model on its own stream.
+fn two_lines(seed: u64, rate_a: f64) -> Simulation {
+    let mut models = Vec::new();
+    let mut connectors = Vec::new();
+    for (line, rate) in [("a", rate_a), ("b", 1.0)] {
+        let source_id = format!("arrivals-{}", line);
+        let station_id = format!("server-{}", line);
+        models.push(Model::new(
+            source_id.clone(),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 0.5 },
+                None,
+                String::from("job"),
+                false,
+                Some(stream_rng(seed, &source_id)),
+            )),
+        ));
+        models.push(Model::new(
+            station_id.clone(),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: rate },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                true,
+                Some(stream_rng(seed, &station_id)),
+            )),
+        ));
+        connectors.push(Connector::new(
+            format!("line {}", line),
+            source_id,
+            station_id,
+            String::from("job"),
+            String::from("job"),
+        ));
+    }
+    Simulation::post_with_seed(models, connectors, seed)
+}
+
+fn departures(simulation: &Simulation, model_id: &str) -> Vec<f64> {
+    simulation
+        .get_models()
+        .get(model_id)
+        .unwrap()
+        .records()
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .map(|record| record.time)
+        .collect()
+}
+
+#[test]
+fn test_streams_isolate_unrelated_models() {
+    let mut baseline = two_lines(51, 1.0);
+    let mut perturbed = two_lines(51, 3.0);
+    baseline.step_until(500.0).unwrap();
+    perturbed.step_until(500.0).unwrap();
+
+    assert_ne!(departures(&baseline, "server-a"), departures(&perturbed, "server-a"));
+    let unrelated = departures(&baseline, "server-b");
+    assert!(unrelated.len() > 100);
+    assert_eq!(unrelated, departures(&perturbed, "server-b"));
+}
+
+#[test]
+fn test_stream_seeds_differ_by_model_and_master_seed() {
+    assert_eq!(stream_seed(7, "server-a"), stream_seed(7, "server-a"));
+    assert_ne!(stream_seed(7, "server-a"), stream_seed(7, "server-b"));
+    assert_ne!(stream_seed(7, "server-a"), stream_seed(8, "server-a"));
+}
+
+/// The service times drawn by a station pinned to seed 11 and by one on its
+/// derived stream, both fed the same scheduled jobs.
+fn pinned_and_derived(master_seed: u64) -> (Vec<f64>, Vec<f64>) {
+    let mut models = Vec::new();
+    let mut connectors = Vec::new();
+    for (station_id, seed) in [("pinned", Some(11)), ("derived", None)] {
+        let source_id = format!("arrivals to {}", station_id);
+        models.push(Model::new(
+            source_id.clone(),
+            Box::new(Source::from_schedule(
+                (0..50).map(f64::from).collect(),
+                String::from("job"),
+                false,
+            )),
+        ));
+        models.push(Model::new(
+            station_id.to_string(),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 2.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                true,
+                Some(model_rng(master_seed, station_id, seed)),
+            )),
+        ));
+        connectors.push(Connector::new(
+            source_id.clone(),
+            source_id,
+            station_id.to_string(),
+            String::from("job"),
+            String::from("job"),
+        ));
+    }
+    let mut simulation = Simulation::post_with_seed(models, connectors, master_seed);
+    simulation.run_to_completion().unwrap();
+    let service_times = |id: &str| {
+        simulation.get_models()[id]
+            .as_concrete::<Station>()
+            .unwrap()
+            .service_times()
+            .to_vec()
+    };
+    (service_times("pinned"), service_times("derived"))
+}
+
+#[test]
+fn test_pinned_seed_ignores_master_seed() {
+    let (pinned_1, derived_1) = pinned_and_derived(1);
+    let (pinned_2, derived_2) = pinned_and_derived(2);
+    assert_eq!(pinned_1.len(), 50);
+    assert_eq!(pinned_1, pinned_2);
+    assert_ne!(derived_1, derived_2);
+}
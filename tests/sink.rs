@@ -0,0 +1,236 @@
+mod common;
+
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Concrete, Sink, Source, Station};
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_sink_records_each_round_trip() {
+    let mut models = common::ping_pong_models(2);
+    models.retain(|model| model.id() != "Store");
+    models.push(Model::new(
+        String::from("Store"),
+        Box::new(Sink::new(String::from("put"), false)),
+    ));
+    let mut simulation = Simulation::post_with_seed(models, common::ping_pong_connectors(2), 12);
+    simulation.inject_input(common::ball());
+    let messages = simulation.step_until(100.0).unwrap();
+
+    let round_trips: Vec<f64> = messages
+        .iter()
+        .filter(|message| message.target_id() == "Store")
+        .map(|message| message.time())
+        .collect();
+    let records = simulation.get_models().get("Store").unwrap().records();
+    assert!(!round_trips.is_empty());
+    assert!(round_trips.len() - records.len() <= 1);
+    records.iter().zip(&round_trips).for_each(|(record, time)| {
+        assert_eq!(record.action, "Arrival");
+        assert_eq!(record.subject, "Ball");
+        assert_eq!(record.time, *time);
+    });
+}
+
+#[test]
+fn test_sink_interarrival_statistics() {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 2.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), true))),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("arrivals to exit"),
+        String::from("arrivals"),
+        String::from("exit"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 29);
+    simulation.step_until(5_000.0).unwrap();
+
+    let exit = simulation.get_models()["exit"]
+        .as_concrete::<Sink>()
+        .unwrap();
+    let mean = exit.mean_interarrival().unwrap();
+    assert!((mean - 0.5).abs() < 0.02, "{}", mean);
+    // exponential interarrival times have a standard deviation equal to the mean.
+    let std_dev = exit.interarrival_std_dev().unwrap();
+    assert!((std_dev - 0.5).abs() < 0.05, "{}", std_dev);
+}
+
+#[test]
+fn test_mean_sojourn_matches_mm1() {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 0.5 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("server"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("arrivals to server"),
+            String::from("arrivals"),
+            String::from("server"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("server to exit"),
+            String::from("server"),
+            String::from("exit"),
+            String::from("processed job"),
+            String::from("job"),
+        ),
+    ];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 44);
+    simulation.step_until(20_000.0).unwrap();
+
+    // M/M/1 with lambda = 0.5 and mu = 1: W = 1 / (mu - lambda) = 2.
+    let mean_sojourn = simulation.get_models()["exit"]
+        .as_concrete::<Sink>()
+        .unwrap()
+        .mean_sojourn()
+        .unwrap();
+    assert!((mean_sojourn - 2.0).abs() < 0.15, "{}", mean_sojourn);
+}
+
+#[test]
+fn test_unstamped_jobs_have_no_sojourn() {
+    let mut models = common::ping_pong_models(2);
+    models.retain(|model| model.id() != "Store");
+    models.push(Model::new(
+        String::from("Store"),
+        Box::new(Sink::new(String::from("put"), false)),
+    ));
+    let mut simulation = Simulation::post_with_seed(models, common::ping_pong_connectors(2), 12);
+    simulation.inject_input(common::ball());
+    simulation.step_until(100.0).unwrap();
+
+    let store = simulation.get_models()["Store"]
+        .as_concrete::<Sink>()
+        .unwrap();
+    assert!(store.arrivals() > 0);
+    assert_eq!(store.mean_sojourn(), None);
+}
+
+#[test]
+fn test_max_records_bounds_records_not_statistics() {
+    let run = |sink: Sink| {
+        let models = vec![
+            Model::new(
+                String::from("arrivals"),
+                Box::new(Source::new(
+                    ContinuousDistribution::Exp { lambda: 2.0 },
+                    None,
+                    String::from("job"),
+                    false,
+                    None,
+                )),
+            ),
+            Model::new(String::from("exit"), Box::new(sink)),
+        ];
+        let connectors = vec![Connector::new(
+            String::from("arrivals to exit"),
+            String::from("arrivals"),
+            String::from("exit"),
+            String::from("job"),
+            String::from("job"),
+        )];
+        let mut simulation = Simulation::post_with_seed(models, connectors, 62);
+        simulation.step_until(2_000.0).unwrap();
+        let exit = simulation.get_models().get("exit").unwrap();
+        (exit.status(), exit.records().clone())
+    };
+    let (status, records) = run(Sink::new(String::from("job"), true));
+    let (bounded_status, bounded_records) =
+        run(Sink::new(String::from("job"), true).with_max_records(100));
+
+    assert!(records.len() > 1_000);
+    assert_eq!(bounded_records.len(), 100);
+    assert_eq!(bounded_status, status);
+    bounded_records
+        .iter()
+        .zip(&records[records.len() - 100..])
+        .for_each(|(bounded, record)| {
+            assert_eq!(bounded.time, record.time);
+            assert_eq!(bounded.subject, record.subject);
+        });
+}
+
+#[test]
+fn test_sink_tags_records_with_their_port() {
+    let source = |count| {
+        Box::new(Source::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            Some(count),
+            String::from("job"),
+            false,
+            None,
+        ))
+    };
+    let models = vec![
+        Model::new(String::from("a"), source(20)),
+        Model::new(String::from("b"), source(30)),
+        Model::new(
+            String::from("store"),
+            Box::new(
+                Sink::new(String::from("put_a"), false)
+                    .with_additional_ports(vec![String::from("put_b")]),
+            ),
+        ),
+    ];
+    let connector = |source: &str, port: &str| {
+        Connector::new(
+            format!("{} to store", source),
+            String::from(source),
+            String::from("store"),
+            String::from("job"),
+            String::from(port),
+        )
+    };
+    let connectors = vec![connector("a", "put_a"), connector("b", "put_b")];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 73);
+    let messages = simulation.step_until(1000.0).unwrap();
+
+    let store = simulation.get_models().get("store").unwrap();
+    let sink = store.as_concrete::<Sink>().unwrap();
+    assert_eq!(sink.arrivals(), 50);
+    assert_eq!(sink.arrivals_on("put_a"), 20);
+    assert_eq!(sink.arrivals_on("put_b"), 30);
+    assert_eq!(store.records().len(), 50);
+    let ports: Vec<&str> = messages
+        .iter()
+        .filter(|message| message.target_id() == "store")
+        .map(|message| message.target_port())
+        .collect();
+    assert_eq!(sink.arrival_ports(), ports.as_slice());
+}
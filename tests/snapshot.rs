@@ -0,0 +1,132 @@
+mod common;
+
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::input_modeling::{ContinuousDistribution, seeded_rng};
+use sim_dag::models::{Delay, Sink};
+use sim_dag::simulation::SimulationExt;
+
+/// Jobs drawn from the global RNG, held for draws from a private one.
+fn conveyor_line(seed: u64) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 0.5 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("conveyor"),
+            Box::new(Delay::new(
+                ContinuousDistribution::Exp { lambda: 0.25 },
+                String::from("job"),
+                String::from("delayed job"),
+                false,
+                Some(seeded_rng(5)),
+            )),
+        ),
+        Model::new(
+            String::from("exit"),
+            Box::new(Sink::new(String::from("job"), false)),
+        ),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("generator to conveyor"),
+            String::from("generator"),
+            String::from("conveyor"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("conveyor to exit"),
+            String::from("conveyor"),
+            String::from("exit"),
+            String::from("delayed job"),
+            String::from("job"),
+        ),
+    ];
+    Simulation::post_with_seed(models, connectors, seed)
+}
+
+#[test]
+fn test_restore_replays_identically() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 9);
+    simulation.inject_input(common::ball());
+    simulation.step_n(10).unwrap();
+
+    let state = simulation.snapshot();
+    let first = simulation.step_n(10).unwrap();
+    simulation.restore(state).unwrap();
+    let second = simulation.step_n(10).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+}
+
+#[test]
+fn test_restore_rewinds_private_rngs() {
+    let mut simulation = conveyor_line(3);
+    simulation.step_n(20).unwrap();
+
+    let state = simulation.snapshot();
+    let first = simulation.step_n(20).unwrap();
+    simulation.restore(state.clone()).unwrap();
+    let second = simulation.step_n(20).unwrap();
+    simulation.restore(state).unwrap();
+    let third = simulation.step_n(20).unwrap();
+
+    assert!(first.iter().any(|message| message.target_id() == "exit"));
+    let first = serde_json::to_string(&first).unwrap();
+    assert_eq!(first, serde_json::to_string(&second).unwrap());
+    assert_eq!(first, serde_json::to_string(&third).unwrap());
+}
+
+#[test]
+fn test_snapshot_leaves_the_run_unchanged() {
+    let mut snapshotted = conveyor_line(3);
+    let mut untouched = conveyor_line(3);
+    snapshotted.step_n(20).unwrap();
+    untouched.step_n(20).unwrap();
+
+    let _state = snapshotted.snapshot();
+    assert_eq!(
+        serde_json::to_string(&snapshotted.step_n(20).unwrap()).unwrap(),
+        serde_json::to_string(&untouched.step_n(20).unwrap()).unwrap()
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_round_trip_steps_identically() {
+    let ping_pong = || {
+        let mut simulation = Simulation::post_with_seed(
+            common::ping_pong_models(2),
+            common::ping_pong_connectors(2),
+            99,
+        );
+        simulation.inject_input(common::ball());
+        simulation.step_n(10).unwrap();
+        simulation
+    };
+    let mut simulation = ping_pong();
+    let mut untouched = ping_pong();
+
+    let document = simulation.to_yaml().unwrap();
+    assert!(!document.contains('!'));
+    let mut restored = Simulation::from_yaml(&document).unwrap();
+    assert_eq!(restored.time(), simulation.time());
+    assert!(restored.diff(&simulation).is_empty());
+
+    let expected = serde_json::to_string(&untouched.step_n(10).unwrap()).unwrap();
+    assert_eq!(serde_json::to_string(&simulation.step_n(10).unwrap()).unwrap(), expected);
+    assert_eq!(serde_json::to_string(&restored.step_n(10).unwrap()).unwrap(), expected);
+}
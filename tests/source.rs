@@ -0,0 +1,172 @@
+use sim::models::{Model, Reportable, Storage};
+use sim::simulator::{Connector, Simulation};
+use sim_dag::checker::check_models;
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Arrivals, Sink, Source};
+use sim_dag::simulation::SimulationExt;
+
+#[test]
+fn test_max_count_limits_jobs() {
+    let models = vec![
+        Model::new(
+            String::from("source"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                Some(100),
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("Store"),
+            Box::new(Storage::new(
+                String::from("put"),
+                String::from("get"),
+                String::from("stored"),
+                true,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("source to Store"),
+        String::from("source"),
+        String::from("Store"),
+        String::from("job"),
+        String::from("put"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 4);
+    simulation.step_until(10_000.0).unwrap();
+
+    let store = simulation.get_models().get("Store").unwrap();
+    assert_eq!(store.records().len(), 100);
+}
+
+#[test]
+fn test_schedule_emits_at_listed_times() {
+    let models = vec![
+        Model::new(
+            String::from("trace"),
+            Box::new(Source::from_schedule(
+                vec![1.0, 2.5, 4.0],
+                String::from("job"),
+                false,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("trace to exit"),
+        String::from("trace"),
+        String::from("exit"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.step_until(100.0).unwrap();
+
+    let arrivals: Vec<f64> = simulation
+        .get_models()
+        .get("exit")
+        .unwrap()
+        .records()
+        .iter()
+        .map(|record| record.time)
+        .collect();
+    assert_eq!(arrivals, vec![1.0, 2.5, 4.0]);
+}
+
+#[test]
+fn test_unsorted_schedule_is_rejected() {
+    let models = vec![Model::new(
+        String::from("trace"),
+        Box::new(Source::from_schedule(vec![2.0, 1.0], String::from("job"), false)),
+    )];
+    assert!(check_models(&models).is_err());
+}
+
+#[test]
+fn test_periodic_arrivals_with_jitter() {
+    let arrivals = Arrivals::Periodic {
+        period: 1.0,
+        jitter: ContinuousDistribution::Triangular {
+            min: -0.1,
+            mode: 0.0,
+            max: 0.1,
+        },
+    };
+    let models = vec![
+        Model::new(
+            String::from("sensor"),
+            Box::new(Source::from_arrivals(
+                arrivals,
+                Some(2_001),
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("sensor to exit"),
+        String::from("sensor"),
+        String::from("exit"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 68);
+    simulation.run_to_completion().unwrap();
+
+    let times: Vec<f64> = simulation
+        .get_models()
+        .get("exit")
+        .unwrap()
+        .records()
+        .iter()
+        .map(|record| record.time)
+        .collect();
+    assert_eq!(times.len(), 2_001);
+    assert_eq!(times[0], 0.0);
+    let interarrivals: Vec<f64> = times.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let mean = interarrivals.iter().sum::<f64>() / interarrivals.len() as f64;
+    assert!((mean - 1.0).abs() < 0.005, "{}", mean);
+    assert!(interarrivals.iter().all(|gap| (0.9 - 1e-9..=1.1 + 1e-9).contains(gap)));
+    let (min, max) = interarrivals
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), gap| {
+            (min.min(*gap), max.max(*gap))
+        });
+    assert!(min < 0.93 && max > 1.07, "{} {}", min, max);
+}
+
+#[test]
+fn test_jitter_never_moves_arrivals_back() {
+    let arrivals = Arrivals::Periodic {
+        period: 0.1,
+        jitter: ContinuousDistribution::Normal {
+            mean: 0.0,
+            std_dev: 1.0,
+        },
+    };
+    let models = vec![
+        Model::new(
+            String::from("sensor"),
+            Box::new(Source::from_arrivals(arrivals, Some(500), String::from("job"), false, None)),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("sensor to exit"),
+        String::from("sensor"),
+        String::from("exit"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 68);
+    simulation.run_to_completion().unwrap();
+
+    let records = simulation.get_models().get("exit").unwrap().records();
+    assert_eq!(records.len(), 500);
+    assert!(records.windows(2).all(|pair| pair[1].time >= pair[0].time));
+}
@@ -0,0 +1,87 @@
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::models::{Sink, Splitter};
+
+fn unpack(splitter: Splitter, batch: &str) -> Simulation {
+    let models = vec![
+        Model::new(String::from("unpacker"), Box::new(splitter)),
+        Model::new(
+            String::from("shelf"),
+            Box::new(Sink::new(String::from("job"), false)),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("unpacker to shelf"),
+        String::from("unpacker"),
+        String::from("shelf"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post(models, connectors);
+    simulation.inject_input(Message::new(
+        String::from("manual"),
+        String::from("manual"),
+        String::from("unpacker"),
+        String::from("batch"),
+        0.0,
+        String::from(batch),
+    ));
+    simulation.step_until(100.0).unwrap();
+    simulation
+}
+
+fn arrivals(simulation: &Simulation) -> Vec<(f64, String)> {
+    simulation
+        .get_models()
+        .get("shelf")
+        .unwrap()
+        .records()
+        .iter()
+        .map(|record| (record.time, record.subject.clone()))
+        .collect()
+}
+
+#[test]
+fn test_count_splits_into_distinct_jobs() {
+    let simulation = unpack(
+        Splitter::new(String::from("batch"), String::from("job"), false),
+        "5",
+    );
+
+    let arrivals = arrivals(&simulation);
+    let mut subjects: Vec<&str> = arrivals.iter().map(|(_, subject)| subject.as_str()).collect();
+    subjects.sort();
+    subjects.dedup();
+    assert_eq!(subjects.len(), 5);
+    assert!(arrivals.iter().all(|(time, _)| *time == 0.0));
+    let status = simulation.get_models().get("unpacker").unwrap().status();
+    assert_eq!(status, "Emitted 5 jobs, 0 waiting");
+}
+
+#[test]
+fn test_list_is_spread_over_time() {
+    let simulation = unpack(
+        Splitter::new(String::from("batch"), String::from("job"), false).with_spacing(2.0),
+        r#"["a","b","c"]"#,
+    );
+
+    assert_eq!(
+        arrivals(&simulation),
+        vec![
+            (0.0, String::from("a")),
+            (2.0, String::from("b")),
+            (4.0, String::from("c")),
+        ]
+    );
+}
+
+#[test]
+fn test_empty_batch_emits_nothing() {
+    for batch in ["0", "[]"] {
+        let simulation = unpack(
+            Splitter::new(String::from("batch"), String::from("job"), false),
+            batch,
+        );
+        assert!(arrivals(&simulation).is_empty());
+    }
+}
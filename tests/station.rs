@@ -0,0 +1,579 @@
+use sim::input_modeling::ContinuousRandomVariable;
+use sim::models::{Generator, Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::checker::{check_models, CheckError};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Concrete, Priority, Source, Station};
+use sim_dag::simulation::SimulationExt;
+
+fn station_model(servers: usize, queue_capacity: Option<usize>) -> Model {
+    Model::new(
+        String::from("agents"),
+        Box::new(Station::new(
+            ContinuousDistribution::Exp { lambda: 0.5 },
+            queue_capacity,
+            servers,
+            String::from("job"),
+            String::from("processed job"),
+            true,
+            None,
+        )),
+    )
+}
+
+fn station_line(servers: usize, queue_capacity: Option<usize>) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("generator"),
+            Box::new(Generator::new(
+                ContinuousRandomVariable::Exp { lambda: 2.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        station_model(servers, queue_capacity),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("generator to agents"),
+        String::from("generator"),
+        String::from("agents"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    Simulation::post_with_seed(models, connectors, 11)
+}
+
+fn run_station(servers: usize, queue_capacity: Option<usize>) -> Simulation {
+    let mut simulation = station_line(servers, queue_capacity);
+    simulation.step_until(500.0).unwrap();
+    simulation
+}
+
+fn completed_jobs(servers: usize) -> usize {
+    run_station(servers, None)
+        .get_models()
+        .get("agents")
+        .unwrap()
+        .records()
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .count()
+}
+
+#[test]
+fn test_throughput_scales_with_servers() {
+    let single = completed_jobs(1);
+    let quad = completed_jobs(4);
+    assert!(quad as f64 > 2.5 * single as f64);
+}
+
+#[test]
+fn test_zero_servers_rejected() {
+    let result = check_models(&[station_model(0, None)]);
+    assert!(matches!(result, Err(CheckError::InvalidModel { .. })));
+}
+
+#[test]
+fn test_full_queue_drops_jobs() {
+    fn agents(simulation: &Simulation) -> &Station {
+        simulation.get_models()["agents"].as_concrete().unwrap()
+    }
+    let mut simulation = station_line(1, Some(5));
+    let mut max_queued = 0;
+    while simulation.time() < 500.0 {
+        simulation.step().unwrap();
+        max_queued = max_queued.max(agents(&simulation).queue_length());
+    }
+    let station = agents(&simulation);
+    assert!(station.dropped() > 0);
+    assert_eq!(max_queued, 5);
+    let records = simulation.get_models()["agents"].records();
+    let drops = records
+        .iter()
+        .filter(|record| record.action == "Drop")
+        .count();
+    assert_eq!(drops, station.dropped());
+}
+
+fn mm1(warmup: f64) -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 0.5 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("server"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )
+            .with_warmup(warmup)),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("arrivals to server"),
+        String::from("arrivals"),
+        String::from("server"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 21);
+    simulation.step_until(20_000.0).unwrap();
+    simulation
+}
+
+#[test]
+fn test_utilization_matches_traffic_intensity() {
+    let simulation = mm1(0.0);
+    let server = simulation.get_models()["server"]
+        .as_concrete::<Station>()
+        .unwrap();
+    let utilization = server.utilization();
+    assert!((utilization - 0.5).abs() < 0.02, "{}", utilization);
+}
+
+#[test]
+fn test_mean_queue_length_matches_mm1() {
+    let simulation = mm1(100.0);
+    let mean_queue = simulation.get_models()["server"]
+        .as_concrete::<Station>()
+        .unwrap()
+        .mean_queue_length();
+    // L_q = rho^2 / (1 - rho) for rho = 0.5
+    assert!((mean_queue - 0.5).abs() < 0.05, "{}", mean_queue);
+}
+
+fn departures_with_priority(priority: Priority) -> (Vec<(f64, String)>, usize) {
+    let models = vec![Model::new(
+        String::from("cpu"),
+        Box::new(
+            Station::new(
+                ContinuousDistribution::Constant { value: 10.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                true,
+                None,
+            )
+            .with_priority(priority),
+        ),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    let job = |content: &str| {
+        Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("cpu"),
+            String::from("job"),
+            0.0,
+            String::from(content),
+        )
+    };
+    simulation.inject_input(job("0:batch"));
+    simulation.step().unwrap();
+    simulation.inject_input(job("5:interrupt"));
+    simulation.step_until(100.0).unwrap();
+
+    let model = simulation.get_models().get("cpu").unwrap();
+    let departures = model
+        .records()
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .map(|record| (record.time, record.subject.clone()))
+        .collect();
+    (departures, model.as_concrete::<Station>().unwrap().preempted())
+}
+
+#[test]
+fn test_high_priority_job_preempts_long_job() {
+    let (departures, preempted) = departures_with_priority(Priority::Preemptive);
+    assert_eq!(
+        departures,
+        vec![
+            (10.0, String::from("5:interrupt")),
+            (20.0, String::from("0:batch"))
+        ]
+    );
+    assert_eq!(preempted, 1);
+}
+
+#[test]
+fn test_no_preemption_by_default() {
+    let (departures, preempted) = departures_with_priority(Priority::Fifo);
+    assert_eq!(
+        departures,
+        vec![
+            (10.0, String::from("0:batch")),
+            (20.0, String::from("5:interrupt"))
+        ]
+    );
+    assert_eq!(preempted, 0);
+}
+
+#[test]
+fn test_service_times_recorded_per_job() {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 2.0 },
+                None,
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("server"),
+            Box::new(Station::new(
+                ContinuousDistribution::Constant { value: 0.75 },
+                None,
+                3,
+                String::from("job"),
+                String::from("processed job"),
+                true,
+                None,
+            )),
+        ),
+    ];
+    let connectors = vec![Connector::new(
+        String::from("arrivals to server"),
+        String::from("arrivals"),
+        String::from("server"),
+        String::from("job"),
+        String::from("job"),
+    )];
+    let mut simulation = Simulation::post_with_seed(models, connectors, 64);
+    simulation.step_until(200.0).unwrap();
+
+    let model = simulation.get_models().get("server").unwrap();
+    let departures = model
+        .records()
+        .iter()
+        .filter(|record| record.action == "Departure")
+        .count();
+    let station: &Station = model.as_concrete().unwrap();
+    assert!(departures > 100);
+    assert_eq!(station.service_times().len(), departures);
+    assert!(station.service_times().iter().all(|time| *time == 0.75));
+}
+
+#[test]
+fn test_queue_length_histograms() {
+    let models = vec![Model::new(
+        String::from("cpu"),
+        Box::new(Station::new(
+            ContinuousDistribution::Constant { value: 1.0 },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        )),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    ["a", "b", "c"].into_iter().for_each(|content| {
+        simulation.inject_input(Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("cpu"),
+            String::from("job"),
+            0.0,
+            String::from(content),
+        ))
+    });
+    simulation.run_to_completion().unwrap();
+    assert_eq!(simulation.time(), 3.0);
+
+    // the queue holds 2, 1 and 0 jobs for one time unit each.
+    let station = simulation
+        .get_models()
+        .get("cpu")
+        .unwrap()
+        .as_concrete::<Station>()
+        .unwrap();
+    assert_eq!(station.queue_length_counts(), &[2, 1]);
+    let fractions = station.queue_length_fractions();
+    assert_eq!(fractions.len(), 3);
+    assert!(fractions.iter().all(|fraction| (fraction - 1.0 / 3.0).abs() < 1e-12));
+    assert!((station.mean_queue_length() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_setup_time_on_changeover() {
+    let models = vec![Model::new(
+        String::from("lathe"),
+        Box::new(
+            Station::new(
+                ContinuousDistribution::Constant { value: 1.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                true,
+                None,
+            )
+            .with_setup_time(ContinuousDistribution::Constant { value: 0.5 }),
+        ),
+    )];
+    let mut simulation = Simulation::post(models, Vec::new());
+    ["a", "b", "a", "b", "a", "a"].into_iter().for_each(|job_type| {
+        simulation.inject_input(Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("lathe"),
+            String::from("job"),
+            0.0,
+            format!("{{\"type\":\"{}\"}}", job_type),
+        ))
+    });
+    simulation.run_to_completion().unwrap();
+
+    let model = simulation.get_models().get("lathe").unwrap();
+    let station = model.as_concrete::<Station>().unwrap();
+    assert_eq!(station.setups(), 4);
+    assert_eq!(station.total_setup_time(), 4.0 * 0.5);
+    assert_eq!(simulation.time(), 6.0 * 1.0 + 4.0 * 0.5);
+    let setups = model.records().iter().filter(|record| record.action == "Setup").count();
+    assert_eq!(setups, 4);
+    assert!(model.status().ends_with(", 4 setups, setup time 2.0000"));
+}
+
+#[test]
+fn test_backpressure_holds_jobs_at_source() {
+    let run = |backpressure: bool| {
+        let source = Source::new(
+            ContinuousDistribution::Exp { lambda: 10.0 },
+            Some(30),
+            String::from("job"),
+            true,
+            None,
+        );
+        let station = Station::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            Some(0),
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            true,
+            None,
+        );
+        let mut connectors = vec![Connector::new(
+            String::from("arrivals to agents"),
+            String::from("arrivals"),
+            String::from("agents"),
+            String::from("job"),
+            String::from("job"),
+        )];
+        let (source, station): (Source, Station) = if backpressure {
+            connectors.push(Connector::new(
+                String::from("agents to arrivals"),
+                String::from("agents"),
+                String::from("arrivals"),
+                String::from("backpressure"),
+                String::from("control"),
+            ));
+            (
+                source.with_backpressure(String::from("control")),
+                station.with_backpressure(String::from("backpressure")),
+            )
+        } else {
+            (source, station)
+        };
+        let models = vec![
+            Model::new(String::from("arrivals"), Box::new(source)),
+            Model::new(String::from("agents"), Box::new(station)),
+        ];
+        let mut simulation = Simulation::post_with_seed(models, connectors, 86);
+        simulation.run_to_completion().unwrap();
+        let models = simulation.get_models();
+        let source = models["arrivals"].as_concrete::<Source>().unwrap();
+        let station = models["agents"].as_concrete::<Station>().unwrap();
+        let departures = station
+            .records()
+            .iter()
+            .filter(|record| record.action == "Departure")
+            .count();
+        let holds = source
+            .records()
+            .iter()
+            .filter(|record| record.action == "Hold")
+            .count();
+        (station.dropped(), departures, holds, source.waiting())
+    };
+
+    let (dropped, departures, holds, _) = run(false);
+    assert!(dropped > 0);
+    assert_eq!(dropped + departures, 30);
+    assert_eq!(holds, 0);
+
+    let (dropped, departures, holds, waiting) = run(true);
+    assert_eq!(dropped, 0);
+    assert_eq!(departures, 30);
+    assert!(holds > 0);
+    assert_eq!(waiting, 0);
+}
+
+#[test]
+fn test_backpressure_keeps_simultaneous_arrivals() {
+    let run = |backpressure: bool| {
+        let source = Source::from_schedule(vec![0.0; 6], String::from("job"), false);
+        let station = Station::new(
+            ContinuousDistribution::Exp { lambda: 1.0 },
+            Some(1),
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            false,
+            None,
+        );
+        let mut connectors = vec![Connector::new(
+            String::from("arrivals to agents"),
+            String::from("arrivals"),
+            String::from("agents"),
+            String::from("job"),
+            String::from("job"),
+        )];
+        let (source, station): (Source, Station) = if backpressure {
+            connectors.push(Connector::new(
+                String::from("agents to arrivals"),
+                String::from("agents"),
+                String::from("arrivals"),
+                String::from("backpressure"),
+                String::from("control"),
+            ));
+            (
+                source.with_backpressure(String::from("control")),
+                station.with_backpressure(String::from("backpressure")),
+            )
+        } else {
+            (source, station)
+        };
+        let models = vec![
+            Model::new(String::from("arrivals"), Box::new(source)),
+            Model::new(String::from("agents"), Box::new(station)),
+        ];
+        let mut simulation = Simulation::post_with_seed(models, connectors, 86);
+        let departures = simulation
+            .run_to_completion()
+            .unwrap()
+            .iter()
+            .filter(|message| message.source_port() == "processed job")
+            .count();
+        let station = &simulation.get_models()["agents"];
+        (station.as_concrete::<Station>().unwrap().dropped(), departures)
+    };
+
+    // one job in service and one queued; the rest arrive at the same instant.
+    assert_eq!(run(false), (4, 2));
+    assert_eq!(run(true), (0, 6));
+}
+
+#[test]
+fn test_breakdowns_scale_throughput_by_availability() {
+    let departures = |breakdowns: bool| {
+        let station = Station::new(
+            ContinuousDistribution::Exp { lambda: 0.5 },
+            None,
+            1,
+            String::from("job"),
+            String::from("processed job"),
+            true,
+            None,
+        );
+        let station = if breakdowns {
+            station.with_breakdowns(
+                ContinuousDistribution::Exp { lambda: 0.1 },
+                ContinuousDistribution::Exp { lambda: 0.2 },
+            )
+        } else {
+            station
+        };
+        let models = vec![
+            Model::new(
+                String::from("generator"),
+                Box::new(Generator::new(
+                    ContinuousRandomVariable::Exp { lambda: 2.0 },
+                    None,
+                    String::from("job"),
+                    false,
+                    None,
+                )),
+            ),
+            Model::new(String::from("agents"), Box::new(station)),
+        ];
+        let connectors = vec![Connector::new(
+            String::from("generator to agents"),
+            String::from("generator"),
+            String::from("agents"),
+            String::from("job"),
+            String::from("job"),
+        )];
+        let mut simulation = Simulation::post_with_seed(models, connectors, 97);
+        simulation.step_until(5_000.0).unwrap();
+        let agents = simulation.get_models()["agents"]
+            .as_concrete::<Station>()
+            .cloned()
+            .unwrap();
+        let count = agents
+            .records()
+            .iter()
+            .filter(|record| record.action == "Departure")
+            .count();
+        (count as f64, agents)
+    };
+
+    let (always_up, reliable) = departures(false);
+    assert_eq!(reliable.failures(), 0);
+    assert_eq!(reliable.availability(), 1.0);
+    let (breaking, agents) = departures(true);
+    // a saturated station serves at its full rate exactly while it is up.
+    assert!(agents.failures() > 100);
+    assert!((agents.availability() - 2.0 / 3.0).abs() < 0.05);
+    assert!((breaking / always_up - agents.availability()).abs() < 0.05);
+    let records = agents.records();
+    let failures = records.iter().filter(|record| record.action == "Failure").count();
+    assert_eq!(failures, agents.failures());
+    assert!((agents.uptime() + agents.downtime() - 5_000.0).abs() < 5.0);
+}
+
+#[test]
+fn test_idle_station_breaks_down() {
+    let station = Station::new(
+        ContinuousDistribution::Exp { lambda: 0.5 },
+        None,
+        1,
+        String::from("job"),
+        String::from("processed job"),
+        false,
+        None,
+    )
+    .with_breakdowns(
+        ContinuousDistribution::Exp { lambda: 1.0 },
+        ContinuousDistribution::Exp { lambda: 1.0 },
+    );
+    let models = vec![Model::new(String::from("agents"), Box::new(station))];
+    let mut simulation = Simulation::post_with_seed(models, Vec::new(), 97);
+    simulation.step_until(100.0).unwrap();
+
+    let agents = simulation.get_models()["agents"]
+        .as_concrete::<Station>()
+        .unwrap();
+    assert!(agents.failures() > 10);
+    assert!(agents.availability() < 1.0);
+}
@@ -0,0 +1,41 @@
+use sim_dag::stats::{percentile, student_t_quantile, summarize};
+
+#[test]
+fn test_summary_of_known_values() {
+    let values = [15.0, 20.0, 35.0, 40.0, 50.0];
+    let summary = summarize(&values, &[0.0, 25.0, 40.0, 50.0, 90.0, 100.0]).unwrap();
+    assert_eq!(summary.count, 5);
+    assert_eq!(summary.mean, 32.0);
+    assert!((summary.std_dev.unwrap() - 14.404_860_3).abs() < 1e-6);
+    assert_eq!(summary.min, 15.0);
+    assert_eq!(summary.max, 50.0);
+    // ranks 0, 1, 1.6, 2, 3.6 and 4 of the sorted values.
+    assert_eq!(summary.percentile(0.0), Some(15.0));
+    assert_eq!(summary.percentile(25.0), Some(20.0));
+    assert!((summary.percentile(40.0).unwrap() - 29.0).abs() < 1e-9);
+    assert_eq!(summary.percentile(50.0), Some(35.0));
+    assert!((summary.percentile(90.0).unwrap() - 46.0).abs() < 1e-9);
+    assert_eq!(summary.percentile(100.0), Some(50.0));
+    assert_eq!(summary.percentile(99.0), None);
+}
+
+#[test]
+fn test_empty_values_have_no_summary() {
+    assert!(summarize(&[], &[50.0]).is_none());
+    assert!(percentile(&[], 50.0).is_none());
+}
+
+#[test]
+fn test_single_value_has_no_std_dev() {
+    let summary = summarize(&[3.0], &[50.0]).unwrap();
+    assert_eq!(summary.std_dev, None);
+    assert_eq!(summary.percentile(50.0), Some(3.0));
+}
+
+#[test]
+fn test_student_t_quantiles() {
+    assert!((student_t_quantile(0.975, 1.0) - 12.706_205).abs() < 1e-5);
+    assert!((student_t_quantile(0.95, 4.0) - 2.131_847).abs() < 1e-5);
+    assert!((student_t_quantile(0.025, 10.0) + 2.228_139).abs() < 1e-5);
+    assert!(student_t_quantile(0.5, 3.0).abs() < 1e-9);
+}
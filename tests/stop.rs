@@ -0,0 +1,178 @@
+mod common;
+
+use sim::models::{Model, Reportable};
+use sim::simulator::{Connector, Message, Simulation};
+use sim_dag::input_modeling::ContinuousDistribution;
+use sim_dag::models::{Sink, Source, Station};
+use sim_dag::session::Session;
+use sim_dag::simulation::{SimulationExt, StopCondition, StopReason};
+
+fn store_records(simulation: &Simulation) -> usize {
+    simulation.get_models().get("Store").unwrap().records().len()
+}
+
+#[test]
+fn test_stop_after_ten_round_trips() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 3);
+    simulation.inject_input(common::ball());
+    let messages = simulation
+        .step_until_predicate(|simulation| store_records(simulation) >= 10)
+        .unwrap();
+
+    assert_eq!(store_records(&simulation), 10);
+    let to_store = messages
+        .iter()
+        .filter(|message| message.target_id() == "Store")
+        .count();
+    assert_eq!(to_store, 10);
+
+    // a second call only returns the messages of its own steps.
+    let messages = simulation
+        .step_until_predicate(|simulation| store_records(simulation) >= 12)
+        .unwrap();
+    let to_store = messages
+        .iter()
+        .filter(|message| message.target_id() == "Store")
+        .count();
+    assert_eq!(to_store, 2);
+}
+
+#[test]
+fn test_session_stops_when_nothing_is_scheduled() {
+    let simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 3);
+    let mut session = Session::new(simulation);
+    let messages = session.step_until_predicate(|_| false).unwrap();
+    assert!(messages.is_empty());
+    assert!(session.simulation().get_global_time().is_infinite());
+}
+
+#[test]
+fn test_clock_starts_at_zero_and_never_decreases() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 3);
+    assert_eq!(simulation.time(), 0.0);
+    simulation.inject_input(common::ball());
+
+    let mut previous = simulation.time();
+    for _ in 0..50 {
+        simulation.step().unwrap();
+        assert!(simulation.time() >= previous);
+        assert_eq!(simulation.time(), simulation.time());
+        previous = simulation.time();
+    }
+    assert!(previous > 0.0);
+}
+
+fn finite_pipeline() -> Simulation {
+    let models = vec![
+        Model::new(
+            String::from("arrivals"),
+            Box::new(Source::new(
+                ContinuousDistribution::Exp { lambda: 1.0 },
+                Some(10),
+                String::from("job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(
+            String::from("server"),
+            Box::new(Station::new(
+                ContinuousDistribution::Exp { lambda: 2.0 },
+                None,
+                1,
+                String::from("job"),
+                String::from("processed job"),
+                false,
+                None,
+            )),
+        ),
+        Model::new(String::from("exit"), Box::new(Sink::new(String::from("job"), false))),
+    ];
+    let connectors = vec![
+        Connector::new(
+            String::from("arrivals to server"),
+            String::from("arrivals"),
+            String::from("server"),
+            String::from("job"),
+            String::from("job"),
+        ),
+        Connector::new(
+            String::from("server to exit"),
+            String::from("server"),
+            String::from("exit"),
+            String::from("processed job"),
+            String::from("job"),
+        ),
+    ];
+    Simulation::post_with_seed(models, connectors, 50)
+}
+
+#[test]
+fn test_run_stops_when_jobs_drain() {
+    let mut simulation = finite_pipeline();
+    simulation.run(&StopCondition::EndTime(1e6)).unwrap();
+
+    assert!(simulation.is_exhausted());
+    assert!(simulation.time() < 100.0, "{}", simulation.time());
+    let exit = simulation.get_models().get("exit").unwrap().records();
+    assert_eq!(exit.len(), 10);
+    assert_eq!(exit.last().unwrap().time, simulation.time());
+
+    let mut completed = finite_pipeline();
+    let messages = completed.run_to_completion().unwrap();
+    assert_eq!(completed.time(), simulation.time());
+    assert_eq!(
+        messages.iter().filter(|message| message.target_id() == "exit").count(),
+        10
+    );
+}
+
+#[test]
+fn test_step_until_any_stops_at_first_condition() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 61);
+    simulation.inject_input(common::ball());
+    let (_, reason) = simulation.step_until_any(Some(1e9), Some(20)).unwrap();
+    assert_eq!(reason, StopReason::Iterations);
+    assert!(simulation.time() < 1e9);
+
+    let end_time = simulation.time() + 5.0;
+    let (_, reason) = simulation
+        .step_until_any(Some(end_time), Some(1_000_000))
+        .unwrap();
+    assert_eq!(reason, StopReason::EndTime);
+
+    let mut simulation = finite_pipeline();
+    let (messages, reason) = simulation.step_until_any(None, None).unwrap();
+    assert_eq!(reason, StopReason::Exhausted);
+    assert_eq!(
+        messages.iter().filter(|message| message.target_id() == "exit").count(),
+        10
+    );
+}
+
+#[test]
+fn test_events_match_run_to_completion() {
+    let mut completed = finite_pipeline();
+    let expected = completed.run_to_completion().unwrap();
+
+    let mut simulation = finite_pipeline();
+    let events: Vec<Message> = simulation.events().collect::<Result<_, _>>().unwrap();
+    assert_eq!(events.len(), expected.len());
+    events.iter().zip(&expected).for_each(|(event, message)| {
+        assert_eq!(event.time(), message.time());
+        assert_eq!(event.target_id(), message.target_id());
+        assert_eq!(event.content(), message.content());
+    });
+    assert!(simulation.is_exhausted());
+    assert_eq!(simulation.time(), completed.time());
+
+    // only as many steps are taken as needed for the events consumed.
+    let mut partial = finite_pipeline();
+    let first: Vec<Message> = partial.events().take(3).collect::<Result<_, _>>().unwrap();
+    assert_eq!(first.len(), 3);
+    assert!(partial.time() <= first[2].time());
+}
@@ -0,0 +1,55 @@
+use sim::models::Reportable;
+use sim_dag::builder::SimulationBuilder;
+use sim_dag::checker::CheckError;
+use sim_dag::models::{Concrete, Sink, Source, Throttle};
+use sim_dag::simulation::SimulationExt;
+
+fn burst_through_link(rate: f64) -> SimulationBuilder {
+    let times: Vec<f64> = (0..100).map(|n| n as f64 * 0.001).collect();
+    SimulationBuilder::new()
+        .model(
+            "burst",
+            Box::new(Source::from_schedule(times, String::from("job"), false)),
+        )
+        .model("exit", Box::new(Sink::new(String::from("job"), false)))
+        .connect_rate_limited("link", "burst", "job", "exit", "job", rate)
+}
+
+#[test]
+fn test_rate_limited_link_spaces_burst() {
+    let mut simulation = burst_through_link(10.0).build().unwrap();
+
+    simulation.step_until(2.0).unwrap();
+    let throttle: &Throttle = simulation
+        .get_models()
+        .get("link throttle")
+        .unwrap()
+        .as_concrete()
+        .unwrap();
+    assert!(throttle.queue_length() > 70, "{}", throttle.queue_length());
+    assert_eq!(throttle.queue_length() + throttle.released(), 100);
+
+    simulation.run_to_completion().unwrap();
+    let arrivals: Vec<f64> = simulation
+        .get_models()
+        .get("exit")
+        .unwrap()
+        .records()
+        .iter()
+        .map(|record| record.time)
+        .collect();
+    assert_eq!(arrivals.len(), 100);
+    assert_eq!(arrivals[0], 0.0);
+    arrivals.windows(2).for_each(|pair| {
+        assert!((pair[1] - pair[0] - 0.1).abs() < 1e-9, "{:?}", pair);
+    });
+}
+
+#[test]
+fn test_non_positive_rate_rejected() {
+    let result = burst_through_link(0.0).build();
+    assert!(matches!(
+        result,
+        Err(CheckError::InvalidModel { model_id, .. }) if model_id == "link throttle"
+    ));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use sim::models::{Model, Reportable};
+use sim::simulator::{Message, Simulation};
+use sim_dag::models::{Concrete, Sink};
+use sim_dag::session::Session;
+use sim_dag::simulation::SimulationExt;
+
+/// A writer whose contents stay readable after it is handed to the session.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_trace_lists_every_transfer() {
+    let mut simulation =
+        Simulation::post_with_seed(common::ping_pong_models(2), common::ping_pong_connectors(2), 2);
+    simulation.inject_input(common::ball());
+    let mut session = Session::new(simulation);
+    let buffer = SharedBuffer::default();
+    session.enable_trace(buffer.clone());
+    session.step_until(50.0).unwrap();
+
+    let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+    let events: Vec<serde_json::Value> = trace
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let store_transfers = events
+        .iter()
+        .filter(|event| event["kind"] == "transfer" && event["targetPort"] == "put")
+        .count();
+    let round_trips = session
+        .simulation()
+        .get_models()
+        .get("Store")
+        .unwrap()
+        .records()
+        .len();
+    assert!(round_trips > 0);
+    // the final step's transfer to the Store may not have been delivered yet.
+    assert!(store_transfers == round_trips || store_transfers == round_trips + 1);
+}
+
+#[test]
+fn test_trace_follows_bounded_records() {
+    let mut models = common::ping_pong_models(2);
+    models.retain(|model| model.id() != "Store");
+    models.push(Model::new(
+        String::from("Store"),
+        Box::new(Sink::new(String::from("put"), false).with_max_records(3)),
+    ));
+    let mut simulation = Simulation::post_with_seed(models, common::ping_pong_connectors(2), 62);
+    simulation.inject_input(common::ball());
+    let mut session = Session::new(simulation);
+    let buffer = SharedBuffer::default();
+    session.enable_trace(buffer.clone());
+    session.step_until(200.0).unwrap();
+
+    let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+    let arrivals: Vec<f64> = trace
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .filter(|event| event["modelId"] == "Store" && event["kind"] == "Arrival")
+        .map(|event| event["time"].as_f64().unwrap())
+        .collect();
+    let store = session.simulation().get_models().get("Store").unwrap();
+    let absorbed = store.as_concrete::<Sink>().unwrap().arrivals();
+    assert!(absorbed > 3);
+    assert_eq!(store.records().len(), 3);
+    assert_eq!(arrivals.len(), absorbed);
+    assert!(arrivals.is_sorted());
+}
+
+#[test]
+fn test_trace_keeps_records_dropped_within_a_step() {
+    let models = vec![Model::new(
+        String::from("Store"),
+        Box::new(Sink::new(String::from("put"), false).with_max_records(1)),
+    )];
+    let mut session = Session::new(Simulation::post(models, Vec::new()));
+    let buffer = SharedBuffer::default();
+    session.enable_trace(buffer.clone());
+    let jobs = (0..4).map(|job| {
+        Message::new(
+            String::from("manual"),
+            String::from("manual"),
+            String::from("Store"),
+            String::from("put"),
+            0.0,
+            format!("Job {}", job),
+        )
+    });
+    session.inject_inputs(jobs).unwrap();
+    session.step().unwrap();
+
+    let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+    let arrivals = trace
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .filter(|event| event["kind"] == "Arrival")
+        .count();
+    let store = session.simulation().get_models().get("Store").unwrap();
+    assert_eq!(store.records().len(), 1);
+    assert_eq!(arrivals, 4);
+}